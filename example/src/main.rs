@@ -26,6 +26,7 @@ fn main() {
 }
 
 #[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+#[tlv_config(unknown_ie = "ts24007")]
 pub struct Tv4BitStruct {
     #[tlv_config(tag = 0x9, tag_bytes_format = 0, format = "TV")]
     value: Option<u8>,
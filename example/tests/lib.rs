@@ -46,6 +46,7 @@ pub struct Tv4BitStruct {
 }
 
 #[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+#[tlv_config(unknown_ie = "ts24007")]
 pub struct OptionalTv4BitStruct {
     #[tlv_config(tag = 0x9, tag_bytes_format = 0, format = "TV")]
     value: Option<u8>,
@@ -80,6 +81,7 @@ pub struct ComplexMixedStruct {
 
 // Optional fields mixed struct
 #[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+#[tlv_config(unknown_ie = "ts24007")]
 pub struct OptionalMixedStruct {
     #[tlv_config(tag = 80, length_bytes_format = 1, format = "TLV")]
     required: u8,
@@ -227,6 +229,21 @@ fn test_optional_mixed() {
     assert_eq!(optional_none, decoded);
 }
 
+#[test]
+fn test_optional_tv_length() {
+    // An optional TV has no length octets, only its tag counts on top of the value
+    let optional_tv = OptionalMixedStruct {
+        required: 42,
+        optional_tv: Some(43),
+        optional_tlv: None,
+        optional_tlv_e: None,
+    };
+    let mut bytes = BytesMut::with_capacity(32);
+    let len = optional_tv.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &[80, 1, 42, 90, 43]);
+    assert_eq!(len, bytes.len());
+}
+
 // Vector TLV struct
 #[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
 pub struct VectorTlvStruct {
@@ -282,6 +299,39 @@ fn test_vector_lv() {
     assert_eq!(vector_lv, decoded);
 }
 
+#[test]
+fn test_length_overflow() {
+    // A value longer than its length field can count is an error, not a wrapped length
+    let mut bytes = BytesMut::new();
+    assert_eq!(VectorTlvStruct { bytes: vec![0; 255] }.encode(&mut bytes).unwrap(), 257);
+    assert!(matches!(
+        VectorTlvStruct { bytes: vec![0; 300] }.encode(&mut BytesMut::new()),
+        Err(TlvError::InvalidLength { field: "bytes", length: 300 })
+    ));
+    assert!(matches!(
+        VectorLvStruct { bytes: vec![0; 256] }.encode(&mut BytesMut::new()),
+        Err(TlvError::InvalidLength { field: "bytes", length: 256 })
+    ));
+
+    // The enterprise ID counts towards the PFCP length
+    let pdi = Pdi {
+        vendor_specific: Some(vec![0; 65533]),
+        ..Default::default()
+    };
+    assert!(pdi.encode(&mut BytesMut::new()).is_ok());
+    let pdi = Pdi {
+        vendor_specific: Some(vec![0; 65534]),
+        ..Default::default()
+    };
+    assert!(matches!(
+        pdi.encode(&mut BytesMut::new()),
+        Err(TlvError::InvalidLength {
+            field: "vendor_specific",
+            length: 65534
+        })
+    ));
+}
+
 #[test]
 fn test_optional_vector() {
     // Test with optional vector present
@@ -480,3 +530,2369 @@ fn test_mixed_newtype_struct() {
     let decoded = MixedNewtypeStruct::decode(len, &mut bytes.freeze()).unwrap();
     assert_eq!(mixed_none, decoded);
 }
+
+#[test]
+fn test_mandatory_tag_mismatch() {
+    // BasicTlv expects tag 42, the wire carries 43
+    let mut bytes = Bytes::from_static(&[43, 1, 7]);
+    let result = BasicTlv::decode(3, &mut bytes);
+    assert!(matches!(
+        result,
+        Err(TlvError::TagMismatch { expected: 42, found: 43, field: "value" })
+    ));
+
+    // 4bit tag is checked against the upper nibble
+    let mut bytes = Bytes::from_static(&[0x87]);
+    let result = Tv4BitStruct::decode(1, &mut bytes);
+    assert!(matches!(
+        result,
+        Err(TlvError::TagMismatch { expected: 9, found: 8, field: "value" })
+    ));
+}
+
+#[test]
+fn test_mandatory_tag_mismatch_lenient() {
+    let mut bytes = Bytes::from_static(&[43, 1, 7]);
+    let decoded = BasicTlv::decode_with_options(3, &mut bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(decoded, BasicTlv { value: 7 });
+
+    let mut bytes = Bytes::from_static(&[0x87]);
+    let decoded = Tv4BitStruct::decode_with_options(1, &mut bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(decoded, Tv4BitStruct { value: 7 });
+}
+
+// TLV with a fixed length
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct FixedLengthTlvStruct {
+    #[tlv_config(tag = 17, length = 2, length_bytes_format = 1, format = "TLV")]
+    value: Vec<u8>,
+}
+
+// Half octet value followed by a spare half octet
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct SpareHalfOctetStruct {
+    #[tlv_config(format = "V", value_bytes_format = 0)]
+    value: u8,
+    #[tlv_config(format = "V", value_bytes_format = 0, spare)]
+    spare: u8,
+}
+
+// Value followed by two spare octets
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct SpareOctetsStruct {
+    #[tlv_config(format = "V", length = 1)]
+    value: u8,
+    #[tlv_config(format = "V", length = 2, spare)]
+    spare: Vec<u8>,
+}
+
+#[test]
+fn test_strict_trailing_bytes_in_value() {
+    // LV of a u8 carrying two octets
+    let mut bytes = Bytes::from_static(&[2, 5, 6]);
+    let result = LvStruct::decode(3, &mut bytes);
+    assert!(matches!(
+        result,
+        Err(TlvError::TrailingBytes { field: "value", remaining: 1 })
+    ));
+
+    let mut bytes = Bytes::from_static(&[2, 5, 6]);
+    let decoded = LvStruct::decode_with_options(3, &mut bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(decoded, LvStruct { value: 5 });
+}
+
+#[test]
+fn test_strict_fixed_length() {
+    let mut bytes = Bytes::from_static(&[17, 3, 1, 2, 3]);
+    let result = FixedLengthTlvStruct::decode(5, &mut bytes);
+    assert!(matches!(
+        result,
+        Err(TlvError::InvalidLength { field: "value", length: 3 })
+    ));
+
+    // Superfluous octets are ignored in lenient mode
+    let mut bytes = Bytes::from_static(&[17, 3, 1, 2, 3]);
+    let decoded =
+        FixedLengthTlvStruct::decode_with_options(5, &mut bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(decoded, FixedLengthTlvStruct { value: vec![1, 2] });
+    assert!(bytes.is_empty());
+
+    // Too short is an error in both modes
+    let mut bytes = Bytes::from_static(&[17, 1, 1]);
+    let result = FixedLengthTlvStruct::decode_with_options(3, &mut bytes, &DecodeOptions::lenient());
+    assert!(matches!(result, Err(TlvError::InvalidLength { .. })));
+}
+
+#[test]
+fn test_strict_spare_bits() {
+    let spare = SpareHalfOctetStruct { value: 5, spare: 0 };
+    let mut bytes = BytesMut::with_capacity(32);
+    let len = spare.encode(&mut bytes).unwrap();
+    let decoded = SpareHalfOctetStruct::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(spare, decoded);
+
+    let mut bytes = Bytes::from_static(&[0x35]);
+    let result = SpareHalfOctetStruct::decode(1, &mut bytes);
+    assert!(matches!(result, Err(TlvError::NonZeroSpare { field: "spare" })));
+
+    let mut bytes = Bytes::from_static(&[0x35]);
+    let decoded =
+        SpareHalfOctetStruct::decode_with_options(1, &mut bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(decoded, SpareHalfOctetStruct { value: 5, spare: 3 });
+}
+
+#[test]
+fn test_short_spare_octets() {
+    // The spare octets are cut short, in both modes
+    for options in [DecodeOptions::strict(), DecodeOptions::lenient()] {
+        let mut bytes = Bytes::from_static(&[5, 0]);
+        let result = SpareOctetsStruct::decode_with_options(2, &mut bytes, &options);
+        assert!(matches!(
+            result,
+            Err(TlvError::InvalidLength { field: "spare", length: 2 })
+        ));
+    }
+}
+
+#[test]
+fn test_strict_trailing_bytes_in_message() {
+    let mut bytes = Bytes::from_static(&[42, 1, 7, 9]);
+    let result = BasicTlv::decode(4, &mut bytes);
+    assert!(matches!(
+        result,
+        Err(TlvError::TrailingBytes { field: "BasicTlv", remaining: 1 })
+    ));
+
+    let mut bytes = Bytes::from_static(&[42, 1, 7, 9]);
+    let decoded = BasicTlv::decode_with_options(4, &mut bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(decoded, BasicTlv { value: 7 });
+}
+
+#[test]
+fn test_unknown_optional_tag() {
+    // 0x33 is not part of OptionalMixedStruct
+    let wire: &[u8] = &[80, 1, 42, 0x33, 1, 9, 30, 1, 44];
+    let result = OptionalMixedStruct::decode(wire.len(), &mut Bytes::from_static(wire));
+    assert!(matches!(result, Err(TlvError::UnknownTag { tag: 0x33 })));
+
+    let decoded = OptionalMixedStruct::decode_with_options(
+        wire.len(),
+        &mut Bytes::from_static(wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(
+        decoded,
+        OptionalMixedStruct {
+            required: 42,
+            optional_tv: None,
+            optional_tlv: Some(44),
+            optional_tlv_e: None,
+        }
+    );
+}
+
+#[test]
+fn test_unknown_optional_tag_layout() {
+    let lenient = DecodeOptions::lenient();
+    // With `unknown_ie = "ts24007"` 0x71 is TLV-E and 0x95 a single octet
+    let wire: &[u8] = &[80, 1, 42, 0x71, 0x00, 0x01, 9, 0x95, 30, 1, 44];
+    let decoded =
+        OptionalMixedStruct::decode_with_options(wire.len(), &mut Bytes::from_static(wire), &lenient)
+            .unwrap();
+    assert_eq!(decoded.optional_tlv, Some(44));
+
+    // Otherwise both are TLVs like the known optional IEs
+    let wire: &[u8] = &[71, 1, 1, 0x71, 1, 9, 0x95, 2, 7, 7, 82, 1, 4];
+    let decoded =
+        OptionalVectorStruct::decode_with_options(wire.len(), &mut Bytes::from_static(wire), &lenient)
+            .unwrap();
+    assert_eq!(
+        decoded,
+        OptionalVectorStruct {
+            required_bytes: vec![1],
+            optional_bytes: Some(vec![4]),
+        }
+    );
+}
+
+#[test]
+fn test_duplicate_optional_ie() {
+    // optional_tlv (tag 30) is repeated, the first occurrence carries 44
+    let wire: &[u8] = &[80, 1, 42, 30, 1, 44, 30, 1, 45];
+    let result = OptionalMixedStruct::decode(wire.len(), &mut Bytes::from_static(wire));
+    assert!(matches!(result, Err(TlvError::DuplicateIe { field: "optional_tlv" })));
+
+    let decoded = OptionalMixedStruct::decode_with_options(
+        wire.len(),
+        &mut Bytes::from_static(wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(decoded.optional_tlv, Some(44));
+}
+
+static OUT_OF_ORDER_WARNINGS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn count_out_of_order(field: &'static str, tag: usize) {
+    assert_eq!((field, tag), ("optional_tv", 90));
+    OUT_OF_ORDER_WARNINGS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+}
+
+#[test]
+fn test_out_of_order_optional_ie() {
+    // optional_tlv (tag 30) is sent before optional_tv (tag 90)
+    let wire: &[u8] = &[80, 1, 42, 30, 1, 44, 90, 43];
+    let expected = OptionalMixedStruct {
+        required: 42,
+        optional_tv: Some(43),
+        optional_tlv: Some(44),
+        optional_tlv_e: None,
+    };
+
+    let result = OptionalMixedStruct::decode(wire.len(), &mut Bytes::from_static(wire));
+    assert!(matches!(result, Err(TlvError::OutOfOrderIe { field: "optional_tv" })));
+
+    let decoded = OptionalMixedStruct::decode_with_options(
+        wire.len(),
+        &mut Bytes::from_static(wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(decoded, expected);
+
+    let options = DecodeOptions {
+        out_of_order_ie: OutOfOrderIe::Warn(count_out_of_order),
+        ..DecodeOptions::strict()
+    };
+    let decoded =
+        OptionalMixedStruct::decode_with_options(wire.len(), &mut Bytes::from_static(wire), &options)
+            .unwrap();
+    assert_eq!(decoded, expected);
+    assert_eq!(OUT_OF_ORDER_WARNINGS.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+// TS 24.007 IE type presets, octet counts include the IEI and length octets
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct IeTypeStruct {
+    #[tlv_config(ie_type = 1)]
+    half_octet_1: u8,
+    #[tlv_config(ie_type = 1)]
+    half_octet_2: u8,
+    #[tlv_config(ie_type = 3, min_octets = 2)]
+    fixed: Vec<u8>,
+    #[tlv_config(ie_type = 4, min_octets = 2, max_octets = 4)]
+    lv: Vec<u8>,
+    #[tlv_config(ie_type = 6, min_octets = 2)]
+    lv_e: Vec<u8>,
+    #[tlv_config(ie_type = 1, tag = 0xB)]
+    optional_type_1: Option<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x55, min_octets = 2, max_octets = 2)]
+    optional_type_3: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x2E, min_octets = 3, max_octets = 5)]
+    optional_type_4: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x71, min_octets = 3)]
+    optional_type_6: Option<Vec<u8>>,
+}
+
+#[test]
+fn test_ie_type_presets() {
+    let ie_types = IeTypeStruct {
+        half_octet_1: 1,
+        half_octet_2: 2,
+        fixed: vec![9, 9],
+        lv: vec![1, 2],
+        lv_e: vec![],
+        optional_type_1: Some(5),
+        optional_type_3: Some(7),
+        optional_type_4: Some(vec![1, 2, 3]),
+        optional_type_6: None,
+    };
+    let mut bytes = BytesMut::with_capacity(32);
+    let len = ie_types.encode(&mut bytes).unwrap();
+    assert_eq!(
+        bytes.as_ref(),
+        &[0x21, 9, 9, 2, 1, 2, 0, 0, 0xB5, 0x55, 7, 0x2E, 3, 1, 2, 3]
+    );
+
+    let decoded = IeTypeStruct::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(ie_types, decoded);
+
+    // Type 4 LV of 2-4 octets carries at most 3 value octets
+    let wire: &[u8] = &[0x21, 9, 9, 4, 1, 2, 3, 4, 0, 0];
+    let result = IeTypeStruct::decode(wire.len(), &mut Bytes::from_static(wire));
+    assert!(matches!(result, Err(TlvError::InvalidLength { field: "lv", length: 4 })));
+
+    // Encoding checks the same bounds
+    let too_long = IeTypeStruct {
+        lv: vec![1, 2, 3, 4],
+        ..ie_types
+    };
+    assert!(matches!(
+        too_long.encode(&mut BytesMut::new()),
+        Err(TlvError::InvalidLength { field: "lv", length: 4 })
+    ));
+    let too_short = IeTypeStruct {
+        lv: vec![1, 2],
+        optional_type_4: Some(vec![]),
+        ..too_long
+    };
+    assert!(matches!(
+        too_short.encode(&mut BytesMut::new()),
+        Err(TlvError::InvalidLength {
+            field: "optional_type_4",
+            length: 0
+        })
+    ));
+}
+
+// Type 2 IEs carry only the IEI
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct TypeTwoStruct {
+    #[tlv_config(tag = 80, length_bytes_format = 1, format = "TLV")]
+    required: u8,
+    #[tlv_config(ie_type = 2, tag = 0xA1)]
+    follow_on_request: bool,
+    #[tlv_config(tag = 0xA2, format = "T")]
+    nssaa_to_be_performed: Option<()>,
+    // Shares the upper nibble with the type 2 IEIs above
+    #[tlv_config(ie_type = 1, tag = 0xA)]
+    optional_type_1: Option<u8>,
+}
+
+#[test]
+fn test_type_two() {
+    let all = TypeTwoStruct {
+        required: 42,
+        follow_on_request: true,
+        nssaa_to_be_performed: Some(()),
+        optional_type_1: Some(5),
+    };
+    let mut bytes = BytesMut::with_capacity(32);
+    let len = all.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &[80, 1, 42, 0xA1, 0xA2, 0xA5]);
+    assert_eq!(len, 6);
+    let decoded = TypeTwoStruct::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(all, decoded);
+
+    let none = TypeTwoStruct {
+        required: 42,
+        follow_on_request: false,
+        nssaa_to_be_performed: None,
+        optional_type_1: None,
+    };
+    let mut bytes = BytesMut::with_capacity(32);
+    let len = none.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &[80, 1, 42]);
+    let decoded = TypeTwoStruct::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(none, decoded);
+
+    let some = TypeTwoStruct {
+        required: 42,
+        follow_on_request: false,
+        nssaa_to_be_performed: Some(()),
+        optional_type_1: None,
+    };
+    let mut bytes = BytesMut::with_capacity(32);
+    let len = some.encode(&mut bytes).unwrap();
+    let decoded = TypeTwoStruct::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(some, decoded);
+}
+
+// The outer LV length decides which of the trailing values are there
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct LengthDrivenInner {
+    #[tlv_config(format = "V", length = 1)]
+    first: u8,
+    #[tlv_config(format = "V", length = 1, present_if_length = [2, 3])]
+    second: Option<u8>,
+    #[tlv_config(format = "V", length = 1, present_if_length = [3])]
+    third: Option<u8>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct LengthDrivenStruct {
+    #[tlv_config(format = "LV")]
+    inner: LengthDrivenInner,
+}
+
+#[test]
+fn test_present_if_length() {
+    for (wire, second, third) in [
+        (&[1u8, 7][..], None, None),
+        (&[2, 7, 8][..], Some(8), None),
+        (&[3, 7, 8, 9][..], Some(8), Some(9)),
+    ] {
+        let expected = LengthDrivenStruct {
+            inner: LengthDrivenInner {
+                first: 7,
+                second,
+                third,
+            },
+        };
+        let mut bytes = BytesMut::with_capacity(8);
+        let len = expected.encode(&mut bytes).unwrap();
+        assert_eq!(bytes.as_ref(), wire);
+        let decoded = LengthDrivenStruct::decode(len, &mut bytes.freeze()).unwrap();
+        assert_eq!(expected, decoded);
+    }
+
+    let mut bytes = Bytes::from_static(&[4, 7, 8, 9, 10]);
+    assert!(matches!(
+        LengthDrivenStruct::decode(5, &mut bytes),
+        Err(TlvError::InvalidLength { .. })
+    ));
+
+    let invalid = LengthDrivenStruct {
+        inner: LengthDrivenInner {
+            first: 7,
+            second: None,
+            third: Some(9),
+        },
+    };
+    let mut bytes = BytesMut::with_capacity(8);
+    assert!(matches!(
+        invalid.encode(&mut bytes),
+        Err(TlvError::InvalidLength { .. })
+    ));
+}
+
+// Bit fields fill whole octets, the first declared field takes the least significant bits
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct BitFieldStruct {
+    #[tlv_config(format = "V", bits = 3)]
+    low: u8,
+    #[tlv_config(format = "V", bits = 1, spare)]
+    spare: u8,
+    #[tlv_config(format = "V", bits = 4)]
+    high: u8,
+    // AMF pointer and AMF set ID of a 5G-S-TMSI share two octets
+    #[tlv_config(format = "V", bits = 6)]
+    pointer: u8,
+    #[tlv_config(format = "V", bits = 10)]
+    set_id: u16,
+}
+
+#[test]
+fn test_bit_fields() {
+    let bit_fields = BitFieldStruct {
+        low: 0b101,
+        spare: 0,
+        high: 0xA,
+        pointer: 0x01,
+        set_id: 0x3FF,
+    };
+    let mut bytes = BytesMut::with_capacity(8);
+    let len = bit_fields.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &[0xA5, 0xFF, 0xC1]);
+    assert_eq!(len, 3);
+    let decoded = BitFieldStruct::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(bit_fields, decoded);
+
+    let mut bytes = Bytes::from_static(&[0xAD, 0xFF, 0xC1]);
+    assert!(matches!(
+        BitFieldStruct::decode(3, &mut bytes),
+        Err(TlvError::NonZeroSpare { field: "spare" })
+    ));
+    let mut bytes = Bytes::from_static(&[0xAD, 0xFF, 0xC1]);
+    let decoded = BitFieldStruct::decode_with_options(3, &mut bytes, &DecodeOptions::lenient()).unwrap();
+    assert_eq!(decoded.spare, 1);
+
+    let too_wide = BitFieldStruct {
+        high: 0x10,
+        ..bit_fields
+    };
+    let mut bytes = BytesMut::with_capacity(8);
+    assert!(matches!(
+        too_wide.encode(&mut bytes),
+        Err(TlvError::InvalidValue { field: "high" })
+    ));
+    // across an octet boundary as well, the octets before it are already written
+    let too_wide = BitFieldStruct {
+        set_id: 0x400,
+        ..bit_fields
+    };
+    let mut bytes = BytesMut::with_capacity(8);
+    assert!(matches!(
+        too_wide.encode(&mut bytes),
+        Err(TlvError::InvalidValue { field: "set_id" })
+    ));
+    assert_eq!(bytes.as_ref(), &[0xA5]);
+}
+
+// A value whose coding is picked by a field decoded before it
+#[derive(Debug, PartialEq)]
+pub enum Reading {
+    Counter(u16),
+    Raw(Vec<u8>),
+}
+
+impl TlvEncode for Reading {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        match self {
+            Reading::Counter(value) => {
+                bytes.put_u16(*value);
+                Ok(2)
+            }
+            Reading::Raw(value) => value.encode(bytes),
+        }
+    }
+}
+
+impl TlvEncodeWith<u8> for Reading {
+    fn check_with(&self, context: &u8) -> Result<(), TlvError> {
+        match (self, context) {
+            (Reading::Counter(_), 0) | (Reading::Raw(_), 1..) => Ok(()),
+            _ => Err(TlvError::InvalidValue { field: "reading" }),
+        }
+    }
+}
+
+impl TlvDecodeWith<u8> for Reading {
+    fn decode_with(
+        length: usize,
+        bytes: &mut Bytes,
+        context: &u8,
+        _options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        match context {
+            0 if length == 2 && bytes.remaining() >= 2 => Ok(Reading::Counter(bytes.get_u16())),
+            0 => Err(TlvError::InvalidLength {
+                field: "reading",
+                length,
+            }),
+            _ => Ok(Reading::Raw(Vec::decode(length, bytes)?)),
+        }
+    }
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct DependentStruct {
+    #[tlv_config(format = "V", length = 1)]
+    kind: u8,
+    #[tlv_config(tag = 1, length_bytes_format = 1, format = "TLV", depends_on = "kind")]
+    reading: Reading,
+    #[tlv_config(tag = 0x20, tag_bytes_format = 1, length = 1, format = "TV")]
+    extra_kind: Option<u8>,
+    #[tlv_config(tag = 0x30, length_bytes_format = 1, format = "TLV", depends_on = "extra_kind")]
+    extra: Option<Reading>,
+}
+
+#[test]
+fn test_depends_on() {
+    for (wire, expected) in [
+        (
+            &[0, 1, 2, 0x12, 0x34][..],
+            DependentStruct {
+                kind: 0,
+                reading: Reading::Counter(0x1234),
+                extra_kind: None,
+                extra: None,
+            },
+        ),
+        (
+            &[1, 1, 2, 0x12, 0x34, 0x20, 0, 0x30, 2, 0x56, 0x78][..],
+            DependentStruct {
+                kind: 1,
+                reading: Reading::Raw(vec![0x12, 0x34]),
+                extra_kind: Some(0),
+                extra: Some(Reading::Counter(0x5678)),
+            },
+        ),
+    ] {
+        let mut bytes = BytesMut::with_capacity(16);
+        let len = expected.encode(&mut bytes).unwrap();
+        assert_eq!(bytes.as_ref(), wire);
+        assert_eq!(len, wire.len());
+        let decoded = DependentStruct::decode(len, &mut bytes.freeze()).unwrap();
+        assert_eq!(expected, decoded);
+    }
+
+    // The optional value without the optional field selecting its coding
+    let mut bytes = Bytes::from_static(&[0, 1, 2, 0x12, 0x34, 0x30, 2, 0x56, 0x78]);
+    assert!(matches!(
+        DependentStruct::decode(9, &mut bytes),
+        Err(TlvError::MissingIe { field: "extra_kind" })
+    ));
+
+    // A value which does not match the field selecting its coding is not written
+    let mismatch = DependentStruct {
+        kind: 1,
+        reading: Reading::Counter(0x1234),
+        extra_kind: None,
+        extra: None,
+    };
+    let mut bytes = BytesMut::with_capacity(16);
+    assert!(matches!(
+        mismatch.encode(&mut bytes),
+        Err(TlvError::InvalidValue { field: "reading" })
+    ));
+    let missing = DependentStruct {
+        kind: 0,
+        reading: Reading::Counter(0x1234),
+        extra_kind: None,
+        extra: Some(Reading::Counter(0x5678)),
+    };
+    let mut bytes = BytesMut::with_capacity(16);
+    assert!(matches!(
+        missing.encode(&mut bytes),
+        Err(TlvError::MissingIe { field: "extra_kind" })
+    ));
+}
+
+// GTPv2-C IEs, the two F-TEIDs of a Create Session Request only differ in the instance
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct GtpBearerContext {
+    #[tlv_config(tag = 73, instance = 0, min_length = 1, max_length = 1)]
+    eps_bearer_id: u8,
+    #[tlv_config(tag = 87, instance = 2)]
+    s5_s8_u_sgw_f_teid: Option<Vec<u8>>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct CreateSessionRequest {
+    #[tlv_config(tag = 1, instance = 0)]
+    imsi: Option<Vec<u8>>,
+    #[tlv_config(tag = 87, instance = 0)]
+    sender_f_teid: Option<Vec<u8>>,
+    #[tlv_config(tag = 87, instance = 1)]
+    pgw_s5_s8_f_teid: Option<Vec<u8>>,
+    // Bearer Contexts to be created, one IE per bearer
+    #[tlv_config(tag = 93, instance = 0, repeated)]
+    bearer_contexts: Vec<GtpBearerContext>,
+}
+
+const CREATE_SESSION_REQUEST: &[u8] = &[
+    0x01, 0x00, 0x08, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0xf1, // IMSI
+    0x57, 0x00, 0x09, 0x00, 0x8a, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x00, 0x00, 0x01, // S11 MME
+    0x57, 0x00, 0x09, 0x01, 0x87, 0x00, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x02, // S5/S8 PGW
+    0x5d, 0x00, 0x05, 0x00, 0x49, 0x00, 0x01, 0x00, 0x05, // Bearer context, EBI 5
+];
+
+fn create_session_request() -> CreateSessionRequest {
+    CreateSessionRequest {
+        imsi: Some(vec![0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0xf1]),
+        sender_f_teid: Some(vec![0x8a, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x00, 0x00, 0x01]),
+        pgw_s5_s8_f_teid: Some(vec![0x87, 0x00, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x02]),
+        bearer_contexts: vec![GtpBearerContext {
+            eps_bearer_id: 5,
+            s5_s8_u_sgw_f_teid: None,
+        }],
+    }
+}
+
+#[test]
+fn test_instance() {
+    let expected = create_session_request();
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), CREATE_SESSION_REQUEST);
+    assert_eq!(len, CREATE_SESSION_REQUEST.len());
+    let decoded = CreateSessionRequest::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(expected, decoded);
+
+    // Only the sender F-TEID
+    let mut bytes = Bytes::copy_from_slice(&CREATE_SESSION_REQUEST[12..25]);
+    let decoded = CreateSessionRequest::decode(13, &mut bytes).unwrap();
+    assert_eq!(decoded.sender_f_teid, expected.sender_f_teid);
+    assert_eq!(decoded.pgw_s5_s8_f_teid, None);
+}
+
+#[test]
+fn test_repeated_bearer_contexts() {
+    let expected = CreateSessionRequest {
+        bearer_contexts: vec![
+            GtpBearerContext {
+                eps_bearer_id: 5,
+                s5_s8_u_sgw_f_teid: None,
+            },
+            GtpBearerContext {
+                eps_bearer_id: 6,
+                s5_s8_u_sgw_f_teid: Some(vec![0x04, 0x00, 0x00, 0x00, 0x02, 0x0a, 0x00, 0x00, 0x03]),
+            },
+        ],
+        ..create_session_request()
+    };
+    let wire = [
+        CREATE_SESSION_REQUEST,
+        &[
+            0x5d, 0x00, 0x12, 0x00, // Bearer context
+            0x49, 0x00, 0x01, 0x00, 0x06, // EBI 6
+            0x57, 0x00, 0x09, 0x02, 0x04, 0x00, 0x00, 0x00, 0x02, 0x0a, 0x00, 0x00, 0x03, // S5/S8 SGW
+        ],
+    ]
+    .concat();
+    let mut bytes = BytesMut::with_capacity(128);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    assert_eq!(CreateSessionRequest::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // None at all
+    let wire = &CREATE_SESSION_REQUEST[..38];
+    let decoded = CreateSessionRequest::decode(wire.len(), &mut Bytes::copy_from_slice(wire)).unwrap();
+    assert!(decoded.bearer_contexts.is_empty());
+}
+
+#[test]
+fn test_instance_unknown() {
+    // An F-TEID with instance 3 is not part of the message
+    let mut wire = CREATE_SESSION_REQUEST.to_vec();
+    wire[28] = 0x03;
+    let mut bytes = Bytes::copy_from_slice(&wire);
+    assert!(matches!(
+        CreateSessionRequest::decode(wire.len(), &mut bytes),
+        Err(TlvError::UnknownTag { tag: 0x57 })
+    ));
+    let mut bytes = Bytes::copy_from_slice(&wire);
+    let decoded = CreateSessionRequest::decode_with_options(
+        wire.len(),
+        &mut bytes,
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(
+        decoded,
+        CreateSessionRequest {
+            pgw_s5_s8_f_teid: None,
+            ..create_session_request()
+        }
+    );
+
+    // Spare bits next to the instance, and a wrong instance of a mandatory IE
+    let mut wire = CREATE_SESSION_REQUEST.to_vec();
+    wire[15] = 0x10;
+    assert!(matches!(
+        CreateSessionRequest::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::NonZeroSpare {
+            field: "sender_f_teid"
+        })
+    ));
+    let mut bytes = Bytes::from_static(&[0x49, 0x00, 0x01, 0x01, 0x05]);
+    assert!(matches!(
+        GtpBearerContext::decode(5, &mut bytes),
+        Err(TlvError::TagMismatch {
+            expected: 0,
+            found: 1,
+            field: "eps_bearer_id"
+        })
+    ));
+}
+
+// PFCP IEs have 2 octet types and lengths, grouped IEs are nested structs. Type 0x8001 is
+// enterprise specific, the enterprise ID follows the length.
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct Pdi {
+    #[tlv_config(tag = 20, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV")]
+    source_interface: u8,
+    #[tlv_config(tag = 21, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV")]
+    local_f_teid: Option<Vec<u8>>,
+    #[tlv_config(
+        tag = 0x8001,
+        tag_bytes_format = 2,
+        length_bytes_format = 2,
+        format = "TLV",
+        enterprise_id = 18681
+    )]
+    vendor_specific: Option<Vec<u8>>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct CreatePdr {
+    #[tlv_config(tag = 56, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV")]
+    pdr_id: Vec<u8>,
+    #[tlv_config(tag = 29, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV")]
+    precedence: Vec<u8>,
+    #[tlv_config(tag = 2, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV")]
+    pdi: Pdi,
+    #[tlv_config(tag = 108, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV")]
+    far_id: Option<Vec<u8>>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct SessionEstablishmentRequest {
+    #[tlv_config(tag = 1, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV")]
+    create_pdr: Option<CreatePdr>,
+}
+
+const PDI_SOURCE_INTERFACE: &[u8] = &[0x00, 0x14, 0x00, 0x01, 0x00];
+const PDI_F_TEID: &[u8] = &[
+    0x00, 0x15, 0x00, 0x09, 0x01, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x00, 0x00, 0x01,
+];
+// The length counts the enterprise ID 18681
+const PDI_VENDOR_SPECIFIC: &[u8] = &[0x80, 0x01, 0x00, 0x04, 0x48, 0xf9, 0xab, 0xcd];
+
+fn session_establishment_request(pdi: &[u8]) -> Vec<u8> {
+    let create_pdr = [
+        &[0x00, 0x38, 0x00, 0x02, 0x00, 0x01][..],
+        &[0x00, 0x1d, 0x00, 0x04, 0x00, 0x00, 0x00, 0xff],
+        &[0x00, 0x02],
+        &(pdi.len() as u16).to_be_bytes(),
+        pdi,
+        &[0x00, 0x6c, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01],
+    ]
+    .concat();
+    [
+        &[0x00, 0x01][..],
+        &(create_pdr.len() as u16).to_be_bytes(),
+        &create_pdr,
+    ]
+    .concat()
+}
+
+fn create_pdr(pdi: Pdi) -> SessionEstablishmentRequest {
+    SessionEstablishmentRequest {
+        create_pdr: Some(CreatePdr {
+            pdr_id: vec![0x00, 0x01],
+            precedence: vec![0x00, 0x00, 0x00, 0xff],
+            pdi,
+            far_id: Some(vec![0x00, 0x00, 0x00, 0x01]),
+        }),
+    }
+}
+
+#[test]
+fn test_pfcp_grouped_ies() {
+    let wire = session_establishment_request(
+        &[PDI_SOURCE_INTERFACE, PDI_F_TEID, PDI_VENDOR_SPECIFIC].concat(),
+    );
+    let expected = create_pdr(Pdi {
+        source_interface: 0,
+        local_f_teid: Some(vec![0x01, 0x00, 0x00, 0x00, 0x01, 0x0a, 0x00, 0x00, 0x01]),
+        vendor_specific: Some(vec![0xab, 0xcd]),
+    });
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    let decoded = SessionEstablishmentRequest::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(expected, decoded);
+}
+
+#[test]
+fn test_pfcp_unknown_ies() {
+    // A type unknown to the PDI, and the enterprise type of another enterprise
+    for (unknown, tag) in [
+        (&[0x00, 0x99, 0x00, 0x01, 0xff][..], 0x99),
+        (&[0x80, 0x01, 0x00, 0x03, 0x12, 0x34, 0xff][..], 0x8001),
+    ] {
+        let wire = session_establishment_request(&[PDI_SOURCE_INTERFACE, unknown].concat());
+        let mut bytes = Bytes::copy_from_slice(&wire);
+        let result = SessionEstablishmentRequest::decode(wire.len(), &mut bytes);
+        assert!(
+            matches!(result, Err(TlvError::UnknownTag { tag: found }) if found == tag),
+            "{result:?}"
+        );
+        let mut bytes = Bytes::copy_from_slice(&wire);
+        let decoded = SessionEstablishmentRequest::decode_with_options(
+            wire.len(),
+            &mut bytes,
+            &DecodeOptions::lenient(),
+        )
+        .unwrap();
+        assert_eq!(decoded, create_pdr(Pdi::default()));
+    }
+}
+
+// Diameter AVPs of an S6a Update-Location-Request, Supported-Features is grouped
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct SupportedFeatures {
+    #[tlv_config(format = "AVP", tag = 266, avp_flags = 0x40)]
+    vendor_id: Vec<u8>,
+    #[tlv_config(format = "AVP", tag = 629, vendor_id = 10415)]
+    feature_list_id: Vec<u8>,
+    #[tlv_config(format = "AVP", tag = 630, vendor_id = 10415)]
+    feature_list: Vec<u8>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct UpdateLocationRequest {
+    #[tlv_config(format = "AVP", tag = 263, avp_flags = 0x40)]
+    session_id: Vec<u8>,
+    #[tlv_config(format = "AVP", tag = 264, avp_flags = 0x40)]
+    origin_host: Vec<u8>,
+    #[tlv_config(format = "AVP", tag = 1405, vendor_id = 10415, avp_flags = 0x40)]
+    ulr_flags: Option<Vec<u8>>,
+    #[tlv_config(format = "AVP", tag = 628, vendor_id = 10415)]
+    supported_features: Option<SupportedFeatures>,
+}
+
+fn avp(code: u32, flags: u8, vendor_id: Option<u32>, data: &[u8]) -> Vec<u8> {
+    let header = if vendor_id.is_some() { 12 } else { 8 };
+    let mut bytes = BytesMut::new();
+    bytes.put_u32(code);
+    bytes.put_u8(flags);
+    bytes.put_uint((header + data.len()) as u64, 3);
+    if let Some(vendor_id) = vendor_id {
+        bytes.put_u32(vendor_id);
+    }
+    bytes.put_slice(data);
+    bytes.put_bytes(0, (4 - data.len() % 4) % 4);
+    bytes.to_vec()
+}
+
+fn update_location_request_wire() -> Vec<u8> {
+    let supported_features = [
+        avp(266, 0x40, None, &[0x00, 0x00, 0x28, 0xaf]),
+        avp(629, 0x80, Some(10415), &[0x00, 0x00, 0x00, 0x01]),
+        avp(630, 0x80, Some(10415), &[0x1c, 0x00, 0x00, 0x00]),
+    ]
+    .concat();
+    [
+        avp(263, 0x40, None, b"s6a;1"),
+        avp(264, 0x40, None, b"mme.epc"),
+        avp(1405, 0xc0, Some(10415), &[0x00, 0x00, 0x00, 0x22]),
+        avp(628, 0x80, Some(10415), &supported_features),
+    ]
+    .concat()
+}
+
+fn update_location_request() -> UpdateLocationRequest {
+    UpdateLocationRequest {
+        session_id: b"s6a;1".to_vec(),
+        origin_host: b"mme.epc".to_vec(),
+        ulr_flags: Some(vec![0x00, 0x00, 0x00, 0x22]),
+        supported_features: Some(SupportedFeatures {
+            vendor_id: vec![0x00, 0x00, 0x28, 0xaf],
+            feature_list_id: vec![0x00, 0x00, 0x00, 0x01],
+            feature_list: vec![0x1c, 0x00, 0x00, 0x00],
+        }),
+    }
+}
+
+#[test]
+fn test_avp() {
+    let wire = update_location_request_wire();
+    let expected = update_location_request();
+    let mut bytes = BytesMut::with_capacity(128);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    let decoded = UpdateLocationRequest::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(expected, decoded);
+
+    // The padding of the session ID is not zero
+    let mut wire = update_location_request_wire();
+    wire[13] = 0xff;
+    assert!(matches!(
+        UpdateLocationRequest::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::NonZeroSpare { field: "session_id" })
+    ));
+}
+
+#[test]
+fn test_avp_unknown() {
+    let mandatory = [avp(263, 0x40, None, b"s6a;1"), avp(264, 0x40, None, b"mme.epc")].concat();
+    let expected = UpdateLocationRequest {
+        ulr_flags: None,
+        supported_features: None,
+        ..update_location_request()
+    };
+    // An unknown code, and Supported-Features without the vendor. The last AVP is not padded.
+    for (unknown, code) in [
+        (avp(999, 0x00, None, &[0x01]), 999),
+        (avp(628, 0x00, None, &[0x00, 0x00, 0x00, 0x22]), 628),
+        (avp(999, 0x00, None, &[0x01])[..9].to_vec(), 999),
+    ] {
+        let wire = [&mandatory[..], &unknown].concat();
+        let mut bytes = Bytes::copy_from_slice(&wire);
+        let result = UpdateLocationRequest::decode(wire.len(), &mut bytes);
+        assert!(
+            matches!(result, Err(TlvError::UnknownTag { tag }) if tag == code),
+            "{result:?}"
+        );
+        let mut bytes = Bytes::copy_from_slice(&wire);
+        let decoded = UpdateLocationRequest::decode_with_options(
+            wire.len(),
+            &mut bytes,
+            &DecodeOptions::lenient(),
+        )
+        .unwrap();
+        assert_eq!(decoded, expected);
+    }
+
+    // Unknown AVPs with the M flag, e.g. ULR-Flags without the vendor, fail in both modes
+    for (unknown, code) in [
+        (avp(999, 0x40, None, &[0x01]), 999),
+        (avp(1405, 0x40, None, &[0x00, 0x00, 0x00, 0x22]), 1405),
+    ] {
+        let wire = [&mandatory[..], &unknown].concat();
+        for options in [DecodeOptions::strict(), DecodeOptions::lenient()] {
+            let mut bytes = Bytes::copy_from_slice(&wire);
+            let result = UpdateLocationRequest::decode_with_options(wire.len(), &mut bytes, &options);
+            assert!(
+                matches!(result, Err(TlvError::UnknownTag { tag }) if tag == code),
+                "{result:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_avp_unordered() {
+    // AVPs may come in any order, RFC 6733 section 3
+    let mut wire = update_location_request_wire();
+    let ulr_flags = wire.drain(32..48).collect::<Vec<u8>>();
+    wire.extend_from_slice(&ulr_flags);
+    let decoded = UpdateLocationRequest::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)).unwrap();
+    assert_eq!(decoded, update_location_request());
+}
+
+// LLDP TLVs have a 7 bit type and a 9 bit length, the organizationally specific ones (type
+// 127) are told apart by the OUI and subtype after the header
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct Lldpdu {
+    #[tlv_config(tag = 1, tag_bits = 7, length_bits = 9, format = "TLV")]
+    chassis_id: Vec<u8>,
+    #[tlv_config(tag = 2, tag_bits = 7, length_bits = 9, format = "TLV")]
+    port_id: Vec<u8>,
+    #[tlv_config(tag = 3, tag_bits = 7, length_bits = 9, format = "TLV")]
+    time_to_live: Vec<u8>,
+    #[tlv_config(tag = 5, tag_bits = 7, length_bits = 9, format = "TLV")]
+    system_name: Option<Vec<u8>>,
+    #[tlv_config(tag = 127, tag_bits = 7, length_bits = 9, format = "TLV", oui = 0x0080C2, subtype = 1)]
+    port_vlan_id: Option<Vec<u8>>,
+    #[tlv_config(tag = 127, tag_bits = 7, length_bits = 9, format = "TLV", oui = 0x00120F, subtype = 1)]
+    mac_phy_configuration: Option<Vec<u8>>,
+    #[tlv_config(tag = 0, tag_bits = 7, length_bits = 9, format = "TLV", end)]
+    end: bool,
+}
+
+const LLDPDU_MANDATORY: &[u8] = &[
+    0x02, 0x07, 0x04, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // Chassis ID, MAC address
+    0x04, 0x05, 0x05, 0x65, 0x74, 0x68, 0x30, // Port ID, interface name "eth0"
+    0x06, 0x02, 0x00, 0x78, // TTL 120 s
+];
+const LLDPDU_OPTIONAL: &[u8] = &[
+    0x0a, 0x03, 0x73, 0x77, 0x31, // System name "sw1"
+    0xfe, 0x06, 0x00, 0x80, 0xc2, 0x01, 0x00, 0x64, // Port VLAN ID 100
+    0xfe, 0x09, 0x00, 0x12, 0x0f, 0x01, 0x03, 0x6c, 0x00, 0x00, 0x10, // MAC/PHY
+    0x00, 0x00, // End of LLDPDU
+];
+
+fn lldpdu() -> Lldpdu {
+    Lldpdu {
+        chassis_id: vec![0x04, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55],
+        port_id: b"\x05eth0".to_vec(),
+        time_to_live: vec![0x00, 0x78],
+        system_name: Some(b"sw1".to_vec()),
+        port_vlan_id: Some(vec![0x00, 0x64]),
+        mac_phy_configuration: Some(vec![0x03, 0x6c, 0x00, 0x00, 0x10]),
+        end: true,
+    }
+}
+
+#[test]
+fn test_packed_header() {
+    let wire = [LLDPDU_MANDATORY, LLDPDU_OPTIONAL].concat();
+    let expected = lldpdu();
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    let decoded = Lldpdu::decode(len, &mut bytes.freeze()).unwrap();
+    assert_eq!(expected, decoded);
+
+    // 9 bits of length
+    let too_long = Lldpdu {
+        system_name: Some(vec![0x61; 512]),
+        ..lldpdu()
+    };
+    let mut bytes = BytesMut::with_capacity(1024);
+    assert!(matches!(
+        too_long.encode(&mut bytes),
+        Err(TlvError::InvalidLength {
+            field: "system_name",
+            length: 512
+        })
+    ));
+}
+
+#[test]
+fn test_packed_header_end() {
+    // A minimal Ethernet frame of 64 octets leaves 46 for the LLDPDU, zeros pad it after the
+    // End of LLDPDU
+    let mut wire = [LLDPDU_MANDATORY, &[0x00, 0x00]].concat();
+    wire.resize(46, 0x00);
+    let expected = Lldpdu {
+        system_name: None,
+        port_vlan_id: None,
+        mac_phy_configuration: None,
+        ..lldpdu()
+    };
+    let decoded = Lldpdu::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)).unwrap();
+    assert_eq!(decoded, expected);
+    let mut bytes = BytesMut::new();
+    expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..22]);
+
+    // Anything but padding after the end is rejected in strict mode only
+    wire[30] = 0x0a;
+    assert!(matches!(
+        Lldpdu::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::TrailingBytes {
+            field: "end",
+            remaining: 24
+        })
+    ));
+    let decoded = Lldpdu::decode_with_options(
+        wire.len(),
+        &mut Bytes::copy_from_slice(&wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(decoded, expected);
+
+    // The End of LLDPDU has no value
+    let wire = [LLDPDU_MANDATORY, &[0x00, 0x01, 0x00]].concat();
+    assert!(matches!(
+        Lldpdu::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::InvalidLength {
+            field: "end",
+            length: 1
+        })
+    ));
+}
+
+#[test]
+fn test_packed_header_unknown() {
+    // Another 802.1 subtype, and a TLV type the struct does not declare
+    for (unknown, tag) in [
+        (&[0xfe, 0x07, 0x00, 0x80, 0xc2, 0x03, 0x00, 0x64, 0x00][..], 127),
+        (&[0x10, 0x01, 0xff][..], 8),
+    ] {
+        let wire = [LLDPDU_MANDATORY, unknown, &[0x00, 0x00]].concat();
+        let result = Lldpdu::decode(wire.len(), &mut Bytes::copy_from_slice(&wire));
+        assert!(
+            matches!(result, Err(TlvError::UnknownTag { tag: found }) if found == tag),
+            "{result:?}"
+        );
+        let decoded = Lldpdu::decode_with_options(
+            wire.len(),
+            &mut Bytes::copy_from_slice(&wire),
+            &DecodeOptions::lenient(),
+        )
+        .unwrap();
+        assert_eq!(
+            decoded,
+            Lldpdu {
+                system_name: None,
+                port_vlan_id: None,
+                mac_phy_configuration: None,
+                ..lldpdu()
+            }
+        );
+    }
+}
+
+// BGP path attributes, RFC 4271 section 4.3, the Extended Length flag (0x10) selects a 2
+// octet length. Only the Optional and Transitive flags tell a wrong attribute, the Partial
+// flag may be set on the way and the low bits are ignored on receipt.
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct PathAttributes {
+    #[tlv_config(tag = 1, format = "TLV", flags = 0x40, flags_mask = 0xC0, extended_length = 0x10)]
+    origin: Vec<u8>,
+    #[tlv_config(tag = 2, format = "TLV", flags = 0x40, flags_mask = 0xC0, extended_length = 0x10)]
+    as_path: Vec<u8>,
+    #[tlv_config(tag = 3, format = "TLV", flags = 0x40, flags_mask = 0xC0, extended_length = 0x10)]
+    next_hop: Vec<u8>,
+    #[tlv_config(tag = 4, format = "TLV", flags = 0x80, flags_mask = 0xC0, extended_length = 0x10)]
+    multi_exit_disc: Option<Vec<u8>>,
+    #[tlv_config(tag = 8, format = "TLV", flags = 0xC0, flags_mask = 0xC0, extended_length = 0x10)]
+    communities: Option<Vec<u8>>,
+}
+
+const PATH_ATTRIBUTES_MANDATORY: &[u8] = &[
+    0x40, 0x01, 0x01, 0x00, // ORIGIN IGP
+    0x40, 0x02, 0x06, 0x02, 0x01, 0x00, 0x00, 0xfd, 0xe8, // AS_PATH 65000
+    0x40, 0x03, 0x04, 0xc0, 0x00, 0x02, 0x01, // NEXT_HOP 192.0.2.1
+];
+
+fn path_attributes() -> PathAttributes {
+    PathAttributes {
+        origin: vec![0x00],
+        as_path: vec![0x02, 0x01, 0x00, 0x00, 0xfd, 0xe8],
+        next_hop: vec![0xc0, 0x00, 0x02, 0x01],
+        multi_exit_disc: Some(vec![0x00, 0x00, 0x00, 0x64]),
+        communities: None,
+    }
+}
+
+#[test]
+fn test_extended_length() {
+    let med: &[u8] = &[0x80, 0x04, 0x04, 0x00, 0x00, 0x00, 0x64];
+    let wire = [PATH_ATTRIBUTES_MANDATORY, med].concat();
+    let expected = path_attributes();
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    assert_eq!(PathAttributes::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // 70 communities do not fit a 1 octet length
+    let communities: Vec<u8> = (0..70u32).flat_map(|n| (0xfde8_0000 | n).to_be_bytes()).collect();
+    let expected = PathAttributes {
+        communities: Some(communities.clone()),
+        ..path_attributes()
+    };
+    let wire = [
+        PATH_ATTRIBUTES_MANDATORY,
+        med,
+        &[0xd0, 0x08, 0x01, 0x18],
+        &communities,
+    ]
+    .concat();
+    let mut bytes = BytesMut::with_capacity(512);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    assert_eq!(PathAttributes::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // The flag may be set for a short value as well, it is dropped when encoding again
+    let wire = [
+        PATH_ATTRIBUTES_MANDATORY,
+        &[0x90, 0x04, 0x00, 0x04, 0x00, 0x00, 0x00, 0x64],
+    ]
+    .concat();
+    let decoded = PathAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)).unwrap();
+    assert_eq!(decoded, path_attributes());
+    let mut bytes = BytesMut::with_capacity(64);
+    decoded.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &[PATH_ATTRIBUTES_MANDATORY, med].concat()[..]);
+}
+
+#[test]
+fn test_extended_length_invalid() {
+    // ORIGIN sent as an optional attribute
+    let mut wire = PATH_ATTRIBUTES_MANDATORY.to_vec();
+    wire[0] = 0x80;
+    let result = PathAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire));
+    assert!(matches!(
+        result,
+        Err(TlvError::TagMismatch {
+            expected: 0x40,
+            found: 0x80,
+            field: "origin"
+        })
+    ));
+    let decoded = PathAttributes::decode_with_options(
+        wire.len(),
+        &mut Bytes::copy_from_slice(&wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(decoded.origin, vec![0x00]);
+
+    // An unknown optional transitive attribute with the Extended Length flag
+    let wire = [
+        PATH_ATTRIBUTES_MANDATORY,
+        &[0xd0, 0x20, 0x00, 0x02, 0xaa, 0xbb],
+    ]
+    .concat();
+    assert!(matches!(
+        PathAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::UnknownTag { tag: 0x20 })
+    ));
+    let decoded = PathAttributes::decode_with_options(
+        wire.len(),
+        &mut Bytes::copy_from_slice(&wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(
+        decoded,
+        PathAttributes {
+            multi_exit_disc: None,
+            ..path_attributes()
+        }
+    );
+
+    // COMMUNITIES with the Partial flag, set by a speaker which did not know the attribute,
+    // and a low bit set. The flags are sent as configured again.
+    let communities: &[u8] = &[0xfd, 0xe8, 0x00, 0x01];
+    let wire = [PATH_ATTRIBUTES_MANDATORY, &[0xe1, 0x08, 0x04], communities].concat();
+    let expected = PathAttributes {
+        multi_exit_disc: None,
+        communities: Some(communities.to_vec()),
+        ..path_attributes()
+    };
+    assert_eq!(
+        PathAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)).unwrap(),
+        expected
+    );
+    let mut bytes = BytesMut::new();
+    expected.encode(&mut bytes).unwrap();
+    assert_eq!(
+        bytes.as_ref(),
+        &[PATH_ATTRIBUTES_MANDATORY, &[0xc0, 0x08, 0x04], communities].concat()[..]
+    );
+
+    let too_long = PathAttributes {
+        communities: Some(vec![0; 0x10000]),
+        ..path_attributes()
+    };
+    let mut bytes = BytesMut::new();
+    assert!(matches!(
+        too_long.encode(&mut bytes),
+        Err(TlvError::InvalidLength {
+            field: "communities",
+            length: 0x10000
+        })
+    ));
+}
+
+// Lengths in 4 octet units, the first two counting their header like a GTP-U extension header
+// and an EAP-AKA attribute
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct ScaledLengthStruct {
+    #[tlv_config(format = "LV", length_unit = 4, length_includes_header)]
+    extension_header: Vec<u8>,
+    #[tlv_config(tag = 7, format = "TLV", length_unit = 4, length_includes_header)]
+    counter: u8,
+    #[tlv_config(tag = 9, format = "TLV", length_unit = 4)]
+    data: Option<Vec<u8>>,
+}
+
+#[test]
+fn test_length_unit() {
+    let wire: &[u8] = &[
+        0x01, 0x01, 0x02, 0x00, // contents and the next extension header type
+        0x07, 0x01, 0x05, 0x00, // one octet of value, one of padding
+        0x09, 0x01, 0xaa, 0xbb, 0xcc, 0xdd,
+    ];
+    let expected = ScaledLengthStruct {
+        extension_header: vec![0x01, 0x02, 0x00],
+        counter: 5,
+        data: Some(vec![0xaa, 0xbb, 0xcc, 0xdd]),
+    };
+    let mut bytes = BytesMut::with_capacity(16);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), wire);
+    assert_eq!(len, wire.len());
+    assert_eq!(ScaledLengthStruct::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // Values are padded with zeros, the padding comes back as part of a `Vec<u8>`
+    let unaligned = ScaledLengthStruct {
+        data: Some(vec![0xaa]),
+        ..expected
+    };
+    let mut bytes = BytesMut::with_capacity(16);
+    unaligned.encode(&mut bytes).unwrap();
+    assert_eq!(&bytes[8..], &[0x09, 0x01, 0xaa, 0x00, 0x00, 0x00]);
+
+    let too_long = ScaledLengthStruct {
+        extension_header: vec![0; 0x400],
+        ..Default::default()
+    };
+    let mut bytes = BytesMut::new();
+    assert!(matches!(
+        too_long.encode(&mut bytes),
+        Err(TlvError::InvalidLength {
+            field: "extension_header",
+            length: 0x400
+        })
+    ));
+}
+
+#[test]
+fn test_length_unit_invalid() {
+    let decode = |wire: &[u8], options: &DecodeOptions| {
+        ScaledLengthStruct::decode_with_options(wire.len(), &mut Bytes::copy_from_slice(wire), options)
+    };
+    let strict = DecodeOptions::strict();
+    let lenient = DecodeOptions::lenient();
+
+    // Padding that is not zero
+    let wire: &[u8] = &[0x01, 0x01, 0x02, 0x00, 0x07, 0x01, 0x05, 0xff];
+    assert!(matches!(
+        decode(wire, &strict),
+        Err(TlvError::NonZeroSpare { field: "counter" })
+    ));
+    assert_eq!(decode(wire, &lenient).unwrap().counter, 5);
+
+    // More than a unit after the value
+    let wire: &[u8] = &[
+        0x01, 0x01, 0x02, 0x00, 0x07, 0x02, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert!(matches!(
+        decode(wire, &strict),
+        Err(TlvError::TrailingBytes {
+            field: "counter",
+            remaining: 5
+        })
+    ));
+    assert_eq!(decode(wire, &lenient).unwrap().counter, 5);
+
+    // A length not covering the header
+    let wire: &[u8] = &[0x00, 0x07, 0x01, 0x05, 0x00];
+    assert!(matches!(
+        decode(wire, &lenient),
+        Err(TlvError::InvalidLength {
+            field: "extension_header",
+            length: 0
+        })
+    ));
+
+    // An unknown optional TLV is skipped with the scaled length
+    let wire: &[u8] = &[
+        0x01, 0x01, 0x02, 0x00, 0x07, 0x01, 0x05, 0x00, 0x0a, 0x02, 0x01, 0x02, 0x03, 0x04,
+        0x05, 0x06, 0x07, 0x08,
+    ];
+    assert!(matches!(
+        decode(wire, &strict),
+        Err(TlvError::UnknownTag { tag: 0x0a })
+    ));
+    assert_eq!(decode(wire, &lenient).unwrap().data, None);
+}
+
+// RADIUS attributes, RFC 2865 section 5, the length counts the type and length octets
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct ThreeGppAttributes {
+    #[tlv_config(tag = 1, format = "TLV", length_includes_header)]
+    imsi: Option<Vec<u8>>,
+    #[tlv_config(tag = 2, format = "TLV", length_includes_header)]
+    charging_id: Option<Vec<u8>>,
+}
+
+// Vendor sub-attributes with a 2 octet type and a 2 octet length
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct LucentAttributes {
+    #[tlv_config(tag = 1, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV", length_includes_header)]
+    max_shared_users: Option<Vec<u8>>,
+    #[tlv_config(tag = 2, tag_bytes_format = 2, length_bytes_format = 2, format = "TLV", length_includes_header)]
+    uu_info: Option<Vec<u8>>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct RadiusAttributes {
+    #[tlv_config(tag = 1, format = "TLV", length_includes_header)]
+    user_name: Vec<u8>,
+    #[tlv_config(tag = 26, format = "TLV", length_includes_header, vendor_id = 10415)]
+    three_gpp: Option<ThreeGppAttributes>,
+    #[tlv_config(tag = 26, format = "TLV", length_includes_header, vendor_id = 4846)]
+    lucent: Option<LucentAttributes>,
+    #[tlv_config(tag = 79, format = "TLV", length_includes_header, fragmented)]
+    eap_message: Option<Vec<u8>>,
+    #[tlv_config(tag = 241, format = "TLV", length_includes_header, extended_type = 2)]
+    extended: Option<Vec<u8>>,
+    #[tlv_config(tag = 245, format = "TLV", length_includes_header, extended_type = 1, fragmented)]
+    long_extended: Option<Vec<u8>>,
+}
+
+const RADIUS_USER_NAME: &[u8] = &[0x01, 0x05, 0x62, 0x6f, 0x62];
+
+#[test]
+fn test_radius_attributes() {
+    let wire: &[u8] = &[
+        0x01, 0x05, 0x62, 0x6f, 0x62, // User-Name "bob"
+        0x1a, 0x0d, 0x00, 0x00, 0x28, 0xaf, 0x01, 0x07, 0x30, 0x30, 0x31, 0x30, 0x31, // 3GPP-IMSI
+        0x1a, 0x10, 0x00, 0x00, 0x12, 0xee, 0x00, 0x01, 0x00, 0x05, 0xaa, 0x00, 0x02, 0x00, 0x05,
+        0xbb, // Lucent
+        0x4f, 0x04, 0x02, 0x01, // EAP-Message
+        0xf1, 0x04, 0x02, 0xcc, // Extended-Type 241.2
+        0xf5, 0x05, 0x01, 0x00, 0xdd, // Long-Extended-Type 245.1
+    ];
+    let expected = RadiusAttributes {
+        user_name: b"bob".to_vec(),
+        three_gpp: Some(ThreeGppAttributes {
+            imsi: Some(b"00101".to_vec()),
+            charging_id: None,
+        }),
+        lucent: Some(LucentAttributes {
+            max_shared_users: Some(vec![0xaa]),
+            uu_info: Some(vec![0xbb]),
+        }),
+        eap_message: Some(vec![0x02, 0x01]),
+        extended: Some(vec![0xcc]),
+        long_extended: Some(vec![0xdd]),
+    };
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), wire);
+    assert_eq!(len, wire.len());
+    assert_eq!(RadiusAttributes::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // Vendor-Specific attributes of the same vendor are one value
+    let wire: &[u8] = &[
+        0x01, 0x05, 0x62, 0x6f, 0x62, 0x1a, 0x0b, 0x00, 0x00, 0x12, 0xee, 0x00, 0x01, 0x00, 0x05,
+        0xaa, 0x1a, 0x0b, 0x00, 0x00, 0x12, 0xee, 0x00, 0x02, 0x00, 0x05, 0xbb,
+    ];
+    assert_eq!(
+        RadiusAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(wire)).unwrap(),
+        RadiusAttributes {
+            user_name: b"bob".to_vec(),
+            lucent: expected.lucent,
+            ..Default::default()
+        }
+    );
+}
+
+// Attributes of the Access-Request and Access-Accept of the Telnet login example of RFC 2865
+// section 7.1
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct RadiusLoginAttributes {
+    #[tlv_config(tag = 1, format = "TLV", length_includes_header)]
+    user_name: Option<Vec<u8>>,
+    #[tlv_config(tag = 2, format = "TLV", length_includes_header)]
+    user_password: Option<Vec<u8>>,
+    #[tlv_config(tag = 4, format = "TLV", length_includes_header)]
+    nas_ip_address: Option<Vec<u8>>,
+    #[tlv_config(tag = 5, format = "TLV", length_includes_header)]
+    nas_port: Option<Vec<u8>>,
+    #[tlv_config(tag = 6, format = "TLV", length_includes_header)]
+    service_type: Option<Vec<u8>>,
+    #[tlv_config(tag = 15, format = "TLV", length_includes_header)]
+    login_service: Option<Vec<u8>>,
+    #[tlv_config(tag = 14, format = "TLV", length_includes_header)]
+    login_ip_host: Option<Vec<u8>>,
+}
+
+#[test]
+fn test_radius_rfc2865_example() {
+    let access_request: &[u8] = &[
+        0x01, 0x06, 0x6e, 0x65, 0x6d, 0x6f, 0x02, 0x12, 0x0d, 0xbe, 0x70, 0x8d, 0x93, 0xd4, 0x13,
+        0xce, 0x31, 0x96, 0xe4, 0x3f, 0x78, 0x2a, 0x0a, 0xee, 0x04, 0x06, 0xc0, 0xa8, 0x01, 0x10,
+        0x05, 0x06, 0x00, 0x00, 0x00, 0x03,
+    ];
+    let access_accept: &[u8] = &[
+        0x06, 0x06, 0x00, 0x00, 0x00, 0x01, 0x0f, 0x06, 0x00, 0x00, 0x00, 0x00, 0x0e, 0x06, 0xc0,
+        0xa8, 0x01, 0x03,
+    ];
+    for (wire, expected) in [
+        (
+            access_request,
+            RadiusLoginAttributes {
+                user_name: Some(b"nemo".to_vec()),
+                // "arctangent" hidden with the shared secret "xyzzy5461"
+                user_password: Some(access_request[8..24].to_vec()),
+                nas_ip_address: Some(vec![192, 168, 1, 16]),
+                nas_port: Some(vec![0, 0, 0, 3]),
+                ..Default::default()
+            },
+        ),
+        (
+            access_accept,
+            RadiusLoginAttributes {
+                // Login
+                service_type: Some(vec![0, 0, 0, 1]),
+                // Telnet
+                login_service: Some(vec![0, 0, 0, 0]),
+                login_ip_host: Some(vec![192, 168, 1, 3]),
+                ..Default::default()
+            },
+        ),
+    ] {
+        let decoded =
+            RadiusLoginAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(wire)).unwrap();
+        assert_eq!(decoded, expected);
+        let mut bytes = BytesMut::with_capacity(64);
+        assert_eq!(expected.encode(&mut bytes).unwrap(), wire.len());
+        assert_eq!(bytes.as_ref(), wire);
+    }
+}
+
+#[test]
+fn test_radius_vendor_specific_per_sub_attribute() {
+    // 3GPP sends one sub-attribute per Vendor-Specific attribute, TS 29.061 section 16.4.7,
+    // and other attributes may sit in between
+    let wire = [
+        RADIUS_USER_NAME,
+        &[0x1a, 0x0d, 0x00, 0x00, 0x28, 0xaf, 0x01, 0x07, 0x30, 0x30, 0x31, 0x30, 0x31], // 3GPP-IMSI
+        &[0x1a, 0x0c, 0x00, 0x00, 0x28, 0xaf, 0x02, 0x06, 0x00, 0x00, 0x00, 0x07], // 3GPP-Charging-Id
+        &[0x4f, 0x04, 0x02, 0x01], // EAP-Message
+        &[0x1a, 0x0b, 0x00, 0x00, 0x12, 0xee, 0x00, 0x02, 0x00, 0x05, 0xbb], // Lucent
+        &[0x1a, 0x0b, 0x00, 0x00, 0x12, 0xee, 0x00, 0x01, 0x00, 0x05, 0xaa], // Lucent
+    ]
+    .concat();
+    let expected = RadiusAttributes {
+        user_name: b"bob".to_vec(),
+        three_gpp: Some(ThreeGppAttributes {
+            imsi: Some(b"00101".to_vec()),
+            charging_id: Some(vec![0x00, 0x00, 0x00, 0x07]),
+        }),
+        lucent: Some(LucentAttributes {
+            max_shared_users: Some(vec![0xaa]),
+            uu_info: Some(vec![0xbb]),
+        }),
+        eap_message: Some(vec![0x02, 0x01]),
+        ..Default::default()
+    };
+    let decoded = RadiusAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)).unwrap();
+    assert_eq!(decoded, expected);
+
+    // A sub-attribute repeated across two Vendor-Specific attributes is still a duplicate
+    let wire = [
+        RADIUS_USER_NAME,
+        &[0x1a, 0x0d, 0x00, 0x00, 0x28, 0xaf, 0x01, 0x07, 0x30, 0x30, 0x31, 0x30, 0x31],
+        &[0x1a, 0x0d, 0x00, 0x00, 0x28, 0xaf, 0x01, 0x07, 0x30, 0x30, 0x31, 0x30, 0x32],
+    ]
+    .concat();
+    assert!(matches!(
+        RadiusAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::DuplicateIe { field: "imsi" })
+    ));
+}
+
+#[test]
+fn test_radius_fragmentation() {
+    let eap_message: Vec<u8> = (0..300u32).map(|n| n as u8).collect();
+    let expected = RadiusAttributes {
+        user_name: b"bob".to_vec(),
+        eap_message: Some(eap_message.clone()),
+        long_extended: Some(eap_message.clone()),
+        ..Default::default()
+    };
+    // 253 and 47 octets of EAP-Message, 251 and 49 octets with the More flag on the first
+    let wire = [
+        RADIUS_USER_NAME,
+        &[0x4f, 0xff],
+        &eap_message[..253],
+        &[0x4f, 0x31],
+        &eap_message[253..],
+        &[0xf5, 0xff, 0x01, 0x80],
+        &eap_message[..251],
+        &[0xf5, 0x35, 0x01, 0x00],
+        &eap_message[251..],
+    ]
+    .concat();
+    let mut bytes = BytesMut::with_capacity(1024);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    assert_eq!(RadiusAttributes::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // An empty value is one attribute
+    let empty = RadiusAttributes {
+        user_name: b"bob".to_vec(),
+        eap_message: Some(vec![]),
+        ..Default::default()
+    };
+    let mut bytes = BytesMut::with_capacity(16);
+    empty.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &[RADIUS_USER_NAME, &[0x4f, 0x02]].concat()[..]);
+    assert_eq!(RadiusAttributes::decode(bytes.len(), &mut bytes.freeze()).unwrap(), empty);
+}
+
+#[test]
+fn test_radius_invalid() {
+    // Vendor-Specific attribute of an unknown vendor
+    let wire = [RADIUS_USER_NAME, &[0x1a, 0x08, 0x00, 0x00, 0x00, 0x09, 0x01, 0x02]].concat();
+    assert!(matches!(
+        RadiusAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::UnknownTag { tag: 26 })
+    ));
+    let decoded = RadiusAttributes::decode_with_options(
+        wire.len(),
+        &mut Bytes::copy_from_slice(&wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(decoded.user_name, b"bob".to_vec());
+
+    // The last fragment of a Long Extended Type is missing
+    let wire = [RADIUS_USER_NAME, &[0xf5, 0x05, 0x01, 0x80, 0xdd]].concat();
+    assert!(matches!(
+        RadiusAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::InvalidLength {
+            field: "long_extended",
+            ..
+        })
+    ));
+
+    // Reserved flags of a Long Extended Type
+    let wire = [RADIUS_USER_NAME, &[0xf5, 0x05, 0x01, 0x01, 0xdd]].concat();
+    assert!(matches!(
+        RadiusAttributes::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)),
+        Err(TlvError::NonZeroSpare {
+            field: "long_extended"
+        })
+    ));
+}
+
+// DHCP options, RFC 2132, ending with the End option and padded with the Pad option. Long
+// options are split into several instances, RFC 3396. Options come in any order.
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct DhcpOptions {
+    #[tlv_config(tag = 53, format = "TLV")]
+    message_type: Option<u8>,
+    #[tlv_config(tag = 54, format = "TLV")]
+    server_identifier: Option<Vec<u8>>,
+    #[tlv_config(tag = 51, format = "TLV")]
+    lease_time: Option<Vec<u8>>,
+    #[tlv_config(tag = 1, format = "TLV")]
+    subnet_mask: Option<Vec<u8>>,
+    #[tlv_config(tag = 3, format = "TLV", fragmented)]
+    router: Option<Vec<u8>>,
+    #[tlv_config(tag = 6, format = "TLV", fragmented)]
+    domain_name_server: Option<Vec<u8>>,
+    #[tlv_config(tag = 121, format = "TLV", fragmented)]
+    classless_static_route: Option<Vec<u8>>,
+    #[tlv_config(tag = 255, format = "T", end, pad = 0)]
+    end: bool,
+}
+
+const DHCP_ACK_OPTIONS: &[u8] = &[
+    0x35, 0x01, 0x05, // DHCPACK
+    0x36, 0x04, 0xc0, 0xa8, 0x00, 0x01, // Server identifier
+    0x33, 0x04, 0x00, 0x00, 0x0e, 0x10, // Lease time
+    0x01, 0x04, 0xff, 0xff, 0xff, 0x00, // Subnet mask
+    0x03, 0x04, 0xc0, 0xa8, 0x00, 0x01, // Router
+    0x06, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x04, 0x04, // DNS servers
+    0xff, // End
+];
+
+fn dhcp_ack_options() -> DhcpOptions {
+    DhcpOptions {
+        message_type: Some(5),
+        server_identifier: Some(vec![0xc0, 0xa8, 0x00, 0x01]),
+        lease_time: Some(vec![0x00, 0x00, 0x0e, 0x10]),
+        subnet_mask: Some(vec![0xff, 0xff, 0xff, 0x00]),
+        router: Some(vec![0xc0, 0xa8, 0x00, 0x01]),
+        domain_name_server: Some(vec![0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x04, 0x04]),
+        classless_static_route: None,
+        end: true,
+    }
+}
+
+#[test]
+fn test_dhcp_options() {
+    let expected = dhcp_ack_options();
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), DHCP_ACK_OPTIONS);
+    assert_eq!(len, DHCP_ACK_OPTIONS.len());
+    assert_eq!(DhcpOptions::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // Pad options anywhere, and after the end up to the end of the options field
+    let wire = [
+        &[0x00, 0x00][..],
+        &DHCP_ACK_OPTIONS[..9],
+        &[0x00],
+        &DHCP_ACK_OPTIONS[9..],
+        &[0x00; 8],
+    ]
+    .concat();
+    let mut bytes = Bytes::copy_from_slice(&wire);
+    assert_eq!(DhcpOptions::decode(wire.len(), &mut bytes).unwrap(), expected);
+    assert!(!bytes.has_remaining());
+
+    // 300 octets of routes in two instances
+    let routes: Vec<u8> = (0..300u32).map(|n| n as u8).collect();
+    let expected = DhcpOptions {
+        classless_static_route: Some(routes.clone()),
+        ..dhcp_ack_options()
+    };
+    let wire = [
+        &DHCP_ACK_OPTIONS[..DHCP_ACK_OPTIONS.len() - 1],
+        &[0x79, 0xff],
+        &routes[..255],
+        &[0x79, 0x2d],
+        &routes[255..],
+        &[0xff],
+    ]
+    .concat();
+    let mut bytes = BytesMut::with_capacity(512);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(DhcpOptions::decode(len, &mut bytes.freeze()).unwrap(), expected);
+}
+
+// Sub-options of the DHCP Relay Agent Information option, RFC 3046. Unlike the options
+// they have no End.
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct RelayAgentInformation {
+    #[tlv_config(tag = 1, format = "TLV")]
+    circuit_id: Option<Vec<u8>>,
+    #[tlv_config(tag = 2, format = "TLV")]
+    remote_id: Option<Vec<u8>>,
+}
+
+// The options of the DISCOVER in the dhcp.pcap sample capture of Wireshark, as in the tests
+// of smoltcp (src/wire/dhcpv4.rs)
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct DhcpDiscoverOptions {
+    #[tlv_config(tag = 53, format = "TLV")]
+    message_type: Option<u8>,
+    #[tlv_config(tag = 61, format = "TLV")]
+    client_identifier: Option<Vec<u8>>,
+    #[tlv_config(tag = 50, format = "TLV")]
+    requested_ip_address: Option<Vec<u8>>,
+    #[tlv_config(tag = 57, format = "TLV")]
+    maximum_message_size: Option<Vec<u8>>,
+    #[tlv_config(tag = 55, format = "TLV")]
+    parameter_request_list: Option<Vec<u8>>,
+    #[tlv_config(tag = 255, format = "T", end, pad = 0)]
+    end: bool,
+}
+
+#[test]
+fn test_dhcp_captured_options() {
+    let wire: &[u8] = &[
+        0x35, 0x01, 0x01, 0x3d, 0x07, 0x01, 0x00, 0x0b, 0x82, 0x01, 0xfc, 0x42, 0x32, 0x04, 0x00,
+        0x00, 0x00, 0x00, 0x39, 0x02, 0x05, 0xdc, 0x37, 0x04, 0x01, 0x03, 0x06, 0x2a, 0xff, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let expected = DhcpDiscoverOptions {
+        message_type: Some(1),
+        client_identifier: Some(vec![0x01, 0x00, 0x0b, 0x82, 0x01, 0xfc, 0x42]),
+        requested_ip_address: Some(vec![0x00, 0x00, 0x00, 0x00]),
+        maximum_message_size: Some(vec![0x05, 0xdc]),
+        parameter_request_list: Some(vec![0x01, 0x03, 0x06, 0x2a]),
+        end: true,
+    };
+    let decoded = DhcpDiscoverOptions::decode(wire.len(), &mut Bytes::copy_from_slice(wire)).unwrap();
+    assert_eq!(decoded, expected);
+    let mut bytes = BytesMut::with_capacity(64);
+    expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..29]);
+
+    // The options of an ACK from the same tests, padded with zeros after the End option
+    let wire = [
+        &[
+            0x35, 0x01, 0x05, 0x36, 0x04, 0x0a, 0x22, 0x10, 0x0a, 0x33, 0x04, 0x00, 0x00, 0x02,
+            0x56, 0x01, 0x04, 0xff, 0xff, 0xff, 0x00, 0x03, 0x04, 0x0a, 0x22, 0x10, 0x0a, 0xff,
+        ][..],
+        &[0x00; 32],
+    ]
+    .concat();
+    let expected = DhcpOptions {
+        message_type: Some(5),
+        server_identifier: Some(vec![0x0a, 0x22, 0x10, 0x0a]),
+        lease_time: Some(vec![0x00, 0x00, 0x02, 0x56]),
+        subnet_mask: Some(vec![0xff, 0xff, 0xff, 0x00]),
+        router: Some(vec![0x0a, 0x22, 0x10, 0x0a]),
+        domain_name_server: None,
+        classless_static_route: None,
+        end: true,
+    };
+    let decoded = DhcpOptions::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)).unwrap();
+    assert_eq!(decoded, expected);
+    let mut bytes = BytesMut::with_capacity(64);
+    expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..28]);
+}
+
+#[test]
+fn test_dhcp_sub_options_unknown() {
+    // A Virtual Subnet Selection sub-option, RFC 6607, its code has bit 8 set but it is a
+    // TLV like the others
+    let wire: &[u8] = &[
+        0x01, 0x04, 0x00, 0x01, 0x00, 0x02, // Circuit ID
+        0x97, 0x05, 0x00, 0x76, 0x72, 0x66, 0x31, // VSS
+        0x02, 0x02, 0xab, 0xcd, // Remote ID
+    ];
+    let decoded = RelayAgentInformation::decode_with_options(
+        wire.len(),
+        &mut Bytes::from_static(wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(
+        decoded,
+        RelayAgentInformation {
+            circuit_id: Some(vec![0x00, 0x01, 0x00, 0x02]),
+            remote_id: Some(vec![0xab, 0xcd]),
+        }
+    );
+}
+
+#[test]
+fn test_dhcp_split_option() {
+    // RFC 3396 section 7: all instances of an option are joined in order, whatever sits
+    // between them
+    let wire: &[u8] = &[
+        0x03, 0x04, 0xc0, 0xa8, 0x00, 0x01, // Router, first part
+        0x00, // Pad
+        0x33, 0x04, 0x00, 0x00, 0x0e, 0x10, // Lease time
+        0x03, 0x04, 0xc0, 0xa8, 0x00, 0x02, // Router, second part
+        0x35, 0x01, 0x05, // DHCPACK
+        0xff, // End
+    ];
+    assert_eq!(
+        DhcpOptions::decode(wire.len(), &mut Bytes::from_static(wire)).unwrap(),
+        DhcpOptions {
+            message_type: Some(5),
+            lease_time: Some(vec![0x00, 0x00, 0x0e, 0x10]),
+            router: Some(vec![0xc0, 0xa8, 0x00, 0x01, 0xc0, 0xa8, 0x00, 0x02]),
+            end: true,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_dhcp_options_invalid() {
+    let decode = |wire: &[u8], options: &DecodeOptions| {
+        DhcpOptions::decode_with_options(wire.len(), &mut Bytes::copy_from_slice(wire), options)
+    };
+
+    // Something else than padding after the end
+    let wire = [DHCP_ACK_OPTIONS, &[0x00, 0x01, 0x04, 0x00, 0x00, 0x00, 0x00]].concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::strict()),
+        Err(TlvError::TrailingBytes {
+            field: "end",
+            remaining: 7
+        })
+    ));
+    assert_eq!(
+        decode(&wire, &DecodeOptions::lenient()).unwrap(),
+        dhcp_ack_options()
+    );
+
+    // Unknown options are plain TLVs, even 0x7d which TS 24.007 would take for TLV-E
+    let wire = [
+        &DHCP_ACK_OPTIONS[..3],
+        &[0x7d, 0x02, 0xaa, 0xbb, 0x8a, 0x01, 0xcc],
+        &[0xff],
+    ]
+    .concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::strict()),
+        Err(TlvError::UnknownTag { tag: 0x7d })
+    ));
+    assert_eq!(
+        decode(&wire, &DecodeOptions::lenient()).unwrap(),
+        DhcpOptions {
+            message_type: Some(5),
+            end: true,
+            ..Default::default()
+        }
+    );
+}
+
+// The proprietary information of an FCP template, TS 102 221 section 11.1.1.4.6
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct ProprietaryInformation {
+    #[tlv_config(tag = 0, class = "context", format = "BER")]
+    uicc_characteristics: Option<Vec<u8>>,
+}
+
+// The FCP template of an EF, TS 102 221 section 11.1.1.3.2
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+pub struct FcpTemplate {
+    #[tlv_config(tag = 2, class = "context", format = "BER", min_length = 2, max_length = 5)]
+    file_descriptor: Option<Vec<u8>>,
+    #[tlv_config(tag = 3, class = "context", format = "BER", min_length = 2, max_length = 2)]
+    file_identifier: Option<Vec<u8>>,
+    #[tlv_config(tag = 5, class = "context", constructed, format = "BER")]
+    proprietary_information: Option<ProprietaryInformation>,
+    #[tlv_config(tag = 10, class = "context", format = "BER")]
+    life_cycle_status: Option<u8>,
+    #[tlv_config(tag = 11, class = "context", format = "BER")]
+    security_attributes: Option<Vec<u8>>,
+    #[tlv_config(tag = 0, class = "context", format = "BER")]
+    file_size: Option<Vec<u8>>,
+    #[tlv_config(tag = 8, class = "context", format = "BER")]
+    short_file_identifier: Option<Vec<u8>>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct FcpResponse {
+    #[tlv_config(tag = 2, class = "application", constructed, format = "BER")]
+    fcp_template: FcpTemplate,
+}
+
+// A home network public key of EF SUCI_Calc_Info, TS 31.102 section 4.4.11.8
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct HomeNetworkPublicKey {
+    #[tlv_config(tag = 0, class = "context", format = "BER")]
+    identifier: Option<u8>,
+    #[tlv_config(tag = 1, class = "context", format = "BER")]
+    public_key: Option<Vec<u8>>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct SuciCalculationInfo {
+    #[tlv_config(tag = 0, class = "context", constructed, format = "BER")]
+    protection_schemes: Vec<u8>,
+    #[tlv_config(tag = 1, class = "context", constructed, format = "BER")]
+    home_network_public_key: Option<HomeNetworkPublicKey>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct HighTagStruct {
+    #[tlv_config(tag = 31, class = "context", format = "BER")]
+    low: Option<Vec<u8>>,
+    #[tlv_config(tag = 200, class = "private", constructed, format = "BER")]
+    high: Option<Vec<u8>>,
+}
+
+// SELECT response of EF IMSI
+const FCP_RESPONSE: &[u8] = &[
+    0x62, 0x1c, // FCP template
+    0x82, 0x02, 0x41, 0x21, // File descriptor
+    0x83, 0x02, 0x6f, 0x07, // File identifier
+    0xa5, 0x03, 0x80, 0x01, 0x71, // Proprietary information
+    0x8a, 0x01, 0x05, // Life cycle status
+    0x8b, 0x03, 0x6f, 0x06, 0x02, // Security attributes
+    0x80, 0x02, 0x00, 0x09, // File size
+    0x88, 0x01, 0x38, // Short file identifier
+];
+
+fn fcp_response() -> FcpResponse {
+    FcpResponse {
+        fcp_template: FcpTemplate {
+            file_descriptor: Some(vec![0x41, 0x21]),
+            file_identifier: Some(vec![0x6f, 0x07]),
+            proprietary_information: Some(ProprietaryInformation {
+                uicc_characteristics: Some(vec![0x71]),
+            }),
+            life_cycle_status: Some(0x05),
+            security_attributes: Some(vec![0x6f, 0x06, 0x02]),
+            file_size: Some(vec![0x00, 0x09]),
+            short_file_identifier: Some(vec![0x38]),
+        },
+    }
+}
+
+#[test]
+fn test_ber() {
+    let expected = fcp_response();
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), FCP_RESPONSE);
+    assert_eq!(len, FCP_RESPONSE.len());
+    assert_eq!(FcpResponse::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // Protection schemes null and profile A, the key of profile A
+    let public_key: Vec<u8> = (0..32).collect();
+    let expected = SuciCalculationInfo {
+        protection_schemes: vec![0x00, 0x00, 0x01, 0x01],
+        home_network_public_key: Some(HomeNetworkPublicKey {
+            identifier: Some(0x01),
+            public_key: Some(public_key.clone()),
+        }),
+    };
+    let wire = [
+        &[0xa0, 0x04, 0x00, 0x00, 0x01, 0x01][..],
+        &[0xa1, 0x25, 0x80, 0x01, 0x01, 0x81, 0x20],
+        &public_key,
+    ]
+    .concat();
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(SuciCalculationInfo::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // High tag numbers, and lengths in the long form
+    let expected = HighTagStruct {
+        low: Some(vec![0x11; 200]),
+        high: Some(vec![0x22; 300]),
+    };
+    let wire = [
+        &[0x9f, 0x1f, 0x81, 0xc8][..],
+        &[0x11; 200],
+        &[0xff, 0x81, 0x48, 0x82, 0x01, 0x2c],
+        &[0x22; 300],
+    ]
+    .concat();
+    let mut bytes = BytesMut::with_capacity(512);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(len, wire.len());
+    assert_eq!(HighTagStruct::decode(len, &mut bytes.freeze()).unwrap(), expected);
+}
+
+// The examples of X.690 section 8.9.3, a SEQUENCE of an IA5String and a BOOLEAN
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct NameOk {
+    #[tlv_config(tag = 22, format = "BER")]
+    name: Vec<u8>,
+    #[tlv_config(tag = 1, format = "BER")]
+    ok: u8,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct NameOkSequence {
+    #[tlv_config(tag = 16, constructed, format = "BER")]
+    sequence: NameOk,
+}
+
+// and of section 8.14.3, the VisibleString "Jones" tagged implicitly and explicitly
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct TaggedType1 {
+    #[tlv_config(tag = 26, format = "BER")]
+    value: Vec<u8>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct TaggedType2 {
+    #[tlv_config(tag = 3, class = "application", format = "BER")]
+    value: Vec<u8>,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct TaggedType3 {
+    #[tlv_config(tag = 2, class = "context", constructed, format = "BER")]
+    value: TaggedType2,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct TaggedType4 {
+    #[tlv_config(tag = 7, class = "application", constructed, format = "BER")]
+    value: TaggedType2,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq)]
+pub struct TaggedType5 {
+    #[tlv_config(tag = 2, class = "context", format = "BER")]
+    value: Vec<u8>,
+}
+
+fn check_ber<T: TlvEncode + TlvDecode + PartialEq + std::fmt::Debug>(value: T, wire: &[u8]) {
+    let mut bytes = BytesMut::with_capacity(16);
+    assert_eq!(value.encode(&mut bytes).unwrap(), wire.len());
+    assert_eq!(bytes.as_ref(), wire);
+    assert_eq!(T::decode(wire.len(), &mut Bytes::copy_from_slice(wire)).unwrap(), value);
+}
+
+#[test]
+fn test_ber_x690_examples() {
+    check_ber(
+        NameOkSequence {
+            sequence: NameOk {
+                name: b"Smith".to_vec(),
+                ok: 0xff,
+            },
+        },
+        &[0x30, 0x0a, 0x16, 0x05, 0x53, 0x6d, 0x69, 0x74, 0x68, 0x01, 0x01, 0xff],
+    );
+
+    let jones = b"Jones".to_vec();
+    let type2 = || TaggedType2 {
+        value: jones.clone(),
+    };
+    check_ber(
+        TaggedType1 {
+            value: jones.clone(),
+        },
+        &[0x1a, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73],
+    );
+    check_ber(type2(), &[0x43, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73]);
+    check_ber(
+        TaggedType3 { value: type2() },
+        &[0xa2, 0x07, 0x43, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73],
+    );
+    check_ber(
+        TaggedType4 { value: type2() },
+        &[0x67, 0x07, 0x43, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73],
+    );
+    check_ber(
+        TaggedType5 {
+            value: jones.clone(),
+        },
+        &[0x82, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73],
+    );
+
+    // The long form of a length of 201, X.690 section 8.1.3.5
+    let wire = [&[0x82, 0x81, 0xc9][..], &[0x61; 201]].concat();
+    check_ber(TaggedType5 { value: vec![0x61; 201] }, &wire);
+}
+
+#[test]
+fn test_ber_invalid() {
+    let decode = |wire: &[u8], options: &DecodeOptions| {
+        FcpResponse::decode_with_options(wire.len(), &mut Bytes::copy_from_slice(wire), options)
+    };
+
+    // A length in more octets than needed is BER but not DER
+    let wire = [&[0x62, 0x81, 0x1c][..], &FCP_RESPONSE[2..]].concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::strict()),
+        Err(TlvError::InvalidLength {
+            field: "fcp_template",
+            length: 0x1c
+        })
+    ));
+    assert_eq!(decode(&wire, &DecodeOptions::lenient()).unwrap(), fcp_response());
+
+    // So are indefinite lengths, nested here
+    let wire = [
+        &[0x62, 0x80][..],
+        &FCP_RESPONSE[2..10],
+        &[0xa5, 0x80, 0x80, 0x01, 0x71, 0x00, 0x00],
+        &FCP_RESPONSE[15..],
+        &[0x00, 0x00],
+    ]
+    .concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::strict()),
+        Err(TlvError::InvalidLength {
+            field: "fcp_template",
+            length: 0x1e
+        })
+    ));
+    let mut bytes = Bytes::copy_from_slice(&wire);
+    let decoded =
+        FcpResponse::decode_with_options(wire.len(), &mut bytes, &DecodeOptions::lenient());
+    assert_eq!(decoded.unwrap(), fcp_response());
+    assert!(!bytes.has_remaining());
+
+    // but not for a primitive element
+    let wire = [&[0x62, 0x80, 0x82, 0x80, 0x41, 0x21, 0x00, 0x00][..], &[0x00, 0x00]].concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::lenient()),
+        Err(TlvError::InvalidLength { field: "fcp_template", .. })
+    ));
+
+    // A primitive element where the constructed one is expected
+    let wire = [&[0x42][..], &FCP_RESPONSE[1..]].concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::strict()),
+        Err(TlvError::TagMismatch {
+            expected: 0x62,
+            found: 0x42,
+            field: "fcp_template"
+        })
+    ));
+
+    // A PIN status template DO is not modelled, it is skipped in lenient mode only
+    let wire = [
+        &[0x62, 0x13][..],
+        &FCP_RESPONSE[2..10],
+        &[0xc6, 0x06, 0x90, 0x01, 0x40, 0x83, 0x01, 0x01],
+        &[0x8a, 0x01, 0x05],
+    ]
+    .concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::strict()),
+        Err(TlvError::UnknownTag { tag: 0xc6 })
+    ));
+    assert_eq!(
+        decode(&wire, &DecodeOptions::lenient()).unwrap().fcp_template,
+        FcpTemplate {
+            file_descriptor: Some(vec![0x41, 0x21]),
+            file_identifier: Some(vec![0x6f, 0x07]),
+            life_cycle_status: Some(0x05),
+            ..Default::default()
+        }
+    );
+}
+
+// Elements of an 802.11 Beacon, IEEE 802.11-2020 section 9.4.2. Element 255 is told apart by
+// its Element ID Extension, Vendor Specific elements by their OUI and type. Vendor Specific
+// elements may come anywhere, hence unordered.
+#[derive(TlvEncode, TlvDecode, Debug, PartialEq, Default)]
+#[tlv_config(unordered)]
+pub struct BeaconElements {
+    #[tlv_config(tag = 0, format = "TLV")]
+    ssid: Option<Vec<u8>>,
+    #[tlv_config(tag = 1, format = "TLV")]
+    supported_rates: Option<Vec<u8>>,
+    #[tlv_config(tag = 48, format = "TLV")]
+    rsn: Option<Vec<u8>>,
+    #[tlv_config(tag = 107, format = "TLV")]
+    interworking: Option<Vec<u8>>,
+    #[tlv_config(tag = 191, format = "TLV")]
+    vht_capabilities: Option<Vec<u8>>,
+    #[tlv_config(tag = 255, format = "TLV", extended_type = 35)]
+    he_capabilities: Option<Vec<u8>>,
+    #[tlv_config(tag = 255, format = "TLV", extended_type = 36)]
+    he_operation: Option<Vec<u8>>,
+    #[tlv_config(tag = 221, format = "TLV", oui = 0x0050F2, subtype = 4)]
+    wps: Option<Vec<u8>>,
+    #[tlv_config(tag = 221, format = "TLV", oui = 0x506F9A, subtype = 0x10)]
+    hotspot20_indication: Option<Vec<u8>>,
+}
+
+const BEACON_ELEMENTS: &[u8] = &[
+    0x00, 0x04, 0x77, 0x69, 0x66, 0x69, // SSID
+    0x01, 0x04, 0x82, 0x84, 0x8b, 0x96, // Supported rates
+    0x30, 0x14, 0x01, 0x00, 0x00, 0x0f, 0xac, 0x04, 0x01, 0x00, 0x00, 0x0f, 0xac, 0x04, 0x01,
+    0x00, 0x00, 0x0f, 0xac, 0x02, 0x00, 0x00, // RSN
+    0x6b, 0x01, 0x02, // Interworking
+    0xbf, 0x04, 0x91, 0x59, 0x82, 0x0f, // VHT capabilities
+    0xff, 0x05, 0x23, 0x01, 0x02, 0x03, 0x04, // HE capabilities
+    0xff, 0x04, 0x24, 0x05, 0x06, 0x07, // HE operation
+    0xdd, 0x09, 0x00, 0x50, 0xf2, 0x04, 0x10, 0x4a, 0x00, 0x01, 0x10, // WPS
+    0xdd, 0x05, 0x50, 0x6f, 0x9a, 0x10, 0x00, // Hotspot 2.0 indication
+];
+
+fn beacon_elements() -> BeaconElements {
+    BeaconElements {
+        ssid: Some(b"wifi".to_vec()),
+        supported_rates: Some(vec![0x82, 0x84, 0x8b, 0x96]),
+        rsn: Some(BEACON_ELEMENTS[14..34].to_vec()),
+        interworking: Some(vec![0x02]),
+        vht_capabilities: Some(vec![0x91, 0x59, 0x82, 0x0f]),
+        he_capabilities: Some(vec![0x01, 0x02, 0x03, 0x04]),
+        he_operation: Some(vec![0x05, 0x06, 0x07]),
+        wps: Some(vec![0x10, 0x4a, 0x00, 0x01, 0x10]),
+        hotspot20_indication: Some(vec![0x00]),
+    }
+}
+
+#[test]
+fn test_element_id_extension() {
+    let expected = beacon_elements();
+    let mut bytes = BytesMut::with_capacity(128);
+    let len = expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), BEACON_ELEMENTS);
+    assert_eq!(len, BEACON_ELEMENTS.len());
+    assert_eq!(BeaconElements::decode(len, &mut bytes.freeze()).unwrap(), expected);
+
+    // The extension and the OUI and type are counted by the length
+    let expected = BeaconElements {
+        he_operation: Some(vec![]),
+        wps: Some(vec![]),
+        ..Default::default()
+    };
+    let wire = [0xff, 0x01, 0x24, 0xdd, 0x04, 0x00, 0x50, 0xf2, 0x04];
+    let mut bytes = BytesMut::new();
+    expected.encode(&mut bytes).unwrap();
+    assert_eq!(bytes.as_ref(), &wire[..]);
+    assert_eq!(BeaconElements::decode(wire.len(), &mut bytes.freeze()).unwrap(), expected);
+
+    // Vendor Specific elements in front of the others, as access points send them
+    let wire = [0xdd, 0x04, 0x00, 0x50, 0xf2, 0x04, 0xff, 0x01, 0x24];
+    assert_eq!(
+        BeaconElements::decode(wire.len(), &mut Bytes::copy_from_slice(&wire)).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_element_id_extension_unknown() {
+    let decode = |wire: &[u8], options: &DecodeOptions| {
+        BeaconElements::decode_with_options(wire.len(), &mut Bytes::copy_from_slice(wire), options)
+    };
+
+    // An unknown extension, a WPA element, a VHT operation element and an extended
+    // capabilities element, all skipped as TLVs whatever TS 24.007 would make of their IDs
+    let unknown: [&[u8]; 4] = [
+        &[0xff, 0x03, 0x6c, 0xaa, 0xbb],
+        &[0xdd, 0x06, 0x00, 0x50, 0xf2, 0x01, 0x01, 0x00],
+        &[0xc0, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00],
+        // Extended capabilities, a TLV-E IEI for TS 24.007
+        &[0x7f, 0x08, 0x04, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x40],
+    ];
+    for (element, tag) in unknown.into_iter().zip([0xff, 0xdd, 0xc0, 0x7f]) {
+        let wire = [&BEACON_ELEMENTS[..6], element, &BEACON_ELEMENTS[6..]].concat();
+        assert!(matches!(
+            decode(&wire, &DecodeOptions::strict()),
+            Err(TlvError::UnknownTag { tag: found }) if found == tag
+        ));
+        assert_eq!(
+            decode(&wire, &DecodeOptions::lenient()).unwrap(),
+            beacon_elements()
+        );
+    }
+
+    // An extension element too short for its Element ID Extension
+    let wire = [&BEACON_ELEMENTS[..6], &[0xff, 0x00]].concat();
+    assert!(matches!(
+        decode(&wire, &DecodeOptions::strict()),
+        Err(TlvError::UnknownTag { tag: 0xff })
+    ));
+}
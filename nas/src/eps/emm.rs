@@ -0,0 +1,314 @@
+// EPS mobility management messages, TS 24.301 section 8.2. The optional IEs are declared
+// in the order of the message tables.
+use crate::ie::{GprsTimer, GprsTimer2, GprsTimer3};
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+/// Message types of TS 24.301 section 9.8
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub enum EmmMessageBody {
+    #[tlv_config(tag = 0x41)]
+    AttachRequest(AttachRequest),
+    #[tlv_config(tag = 0x42)]
+    AttachAccept(AttachAccept),
+    #[tlv_config(tag = 0x43)]
+    AttachComplete(AttachComplete),
+    #[tlv_config(tag = 0x44)]
+    AttachReject(AttachReject),
+    #[tlv_config(tag = 0x45)]
+    DetachRequestUeOriginating(DetachRequestUeOriginating),
+    #[tlv_config(tag = 0x46)]
+    DetachAcceptUeOriginating,
+    #[tlv_config(tag = 0x4B)]
+    TrackingAreaUpdateReject(TrackingAreaUpdateReject),
+    #[tlv_config(tag = 0x4E)]
+    ServiceReject(ServiceReject),
+    #[tlv_config(tag = 0x52)]
+    AuthenticationRequest(AuthenticationRequest),
+    #[tlv_config(tag = 0x53)]
+    AuthenticationResponse(AuthenticationResponse),
+    #[tlv_config(tag = 0x54)]
+    AuthenticationReject,
+    #[tlv_config(tag = 0x55)]
+    IdentityRequest(IdentityRequest),
+    #[tlv_config(tag = 0x56)]
+    IdentityResponse(IdentityResponse),
+    #[tlv_config(tag = 0x5C)]
+    AuthenticationFailure(AuthenticationFailure),
+    #[tlv_config(tag = 0x5D)]
+    SecurityModeCommand(SecurityModeCommand),
+    #[tlv_config(tag = 0x5E)]
+    SecurityModeComplete(SecurityModeComplete),
+    #[tlv_config(tag = 0x5F)]
+    SecurityModeReject(SecurityModeReject),
+    #[tlv_config(tag = 0x60)]
+    EmmStatus(EmmStatus),
+    #[tlv_config(tag = 0x62)]
+    DownlinkNasTransport(DownlinkNasTransport),
+    #[tlv_config(tag = 0x63)]
+    UplinkNasTransport(UplinkNasTransport),
+}
+
+/// TS 24.301 section 8.2.4
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AttachRequest {
+    #[tlv_config(ie_type = 1)]
+    pub eps_attach_type: u8,
+    #[tlv_config(ie_type = 1)]
+    pub nas_key_set_identifier: u8,
+    #[tlv_config(ie_type = 4, min_octets = 5, max_octets = 12)]
+    pub eps_mobile_identity: Vec<u8>,
+    #[tlv_config(ie_type = 4, min_octets = 3, max_octets = 14)]
+    pub ue_network_capability: Vec<u8>,
+    #[tlv_config(ie_type = 6, min_octets = 5)]
+    pub esm_message_container: Vec<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x19, min_octets = 4)]
+    pub old_p_tmsi_signature: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x50, min_octets = 13, max_octets = 13)]
+    pub additional_guti: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x52, min_octets = 6)]
+    pub last_visited_registered_tai: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x5C, min_octets = 3)]
+    pub drx_parameter: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x31, min_octets = 4, max_octets = 10)]
+    pub ms_network_capability: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x13, min_octets = 6)]
+    pub old_location_area_identification: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0x9)]
+    pub tmsi_status: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x11, min_octets = 5, max_octets = 5)]
+    pub mobile_station_classmark_2: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x20, min_octets = 2, max_octets = 34)]
+    pub mobile_station_classmark_3: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x40, min_octets = 5)]
+    pub supported_codecs: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xF)]
+    pub additional_update_type: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x5D, min_octets = 3, max_octets = 3)]
+    pub voice_domain_preference: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xD)]
+    pub device_properties: Option<u8>,
+    #[tlv_config(ie_type = 1, tag = 0xE)]
+    pub old_guti_type: Option<u8>,
+    #[tlv_config(ie_type = 1, tag = 0xC)]
+    pub ms_network_feature_support: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x10, min_octets = 4, max_octets = 4)]
+    pub tmsi_based_nri_container: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x6A, min_octets = 3, max_octets = 3)]
+    pub t3324_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 4, tag = 0x5E, min_octets = 3, max_octets = 3)]
+    pub t3412_extended_value: Option<GprsTimer3>,
+    #[tlv_config(ie_type = 4, tag = 0x6E, min_octets = 3, max_octets = 4)]
+    pub extended_drx_parameters: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x6F, min_octets = 6, max_octets = 6)]
+    pub ue_additional_security_capability: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.2.1
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AttachAccept {
+    #[tlv_config(ie_type = 1)]
+    pub eps_attach_result: u8,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub t3412_value: GprsTimer,
+    #[tlv_config(ie_type = 4, min_octets = 7, max_octets = 97)]
+    pub tai_list: Vec<u8>,
+    #[tlv_config(ie_type = 6, min_octets = 5)]
+    pub esm_message_container: Vec<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x50, min_octets = 13, max_octets = 13)]
+    pub guti: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x13, min_octets = 6)]
+    pub location_area_identification: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x23, min_octets = 7, max_octets = 10)]
+    pub ms_identity: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x53, min_octets = 2)]
+    pub emm_cause: Option<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x17, min_octets = 2)]
+    pub t3402_value: Option<GprsTimer>,
+    #[tlv_config(ie_type = 3, tag = 0x59, min_octets = 2)]
+    pub t3423_value: Option<GprsTimer>,
+    #[tlv_config(ie_type = 4, tag = 0x4A, min_octets = 5, max_octets = 47)]
+    pub equivalent_plmns: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x34, min_octets = 5, max_octets = 50)]
+    pub emergency_number_list: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x64, min_octets = 3, max_octets = 4)]
+    pub eps_network_feature_support: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xF)]
+    pub additional_update_result: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x5E, min_octets = 3, max_octets = 3)]
+    pub t3412_extended_value: Option<GprsTimer3>,
+    #[tlv_config(ie_type = 4, tag = 0x6A, min_octets = 3, max_octets = 3)]
+    pub t3324_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 4, tag = 0x6E, min_octets = 3, max_octets = 4)]
+    pub extended_drx_parameters: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.2.2
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct AttachComplete {
+    #[tlv_config(ie_type = 6, min_octets = 5)]
+    pub esm_message_container: Vec<u8>,
+}
+
+/// TS 24.301 section 8.2.3
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AttachReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub emm_cause: u8,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 6)]
+    pub esm_message_container: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x5F, min_octets = 3, max_octets = 3)]
+    pub t3346_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 4, tag = 0x16, min_octets = 3, max_octets = 3)]
+    pub t3402_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 1, tag = 0xA)]
+    pub extended_emm_cause: Option<u8>,
+}
+
+/// TS 24.301 section 8.2.11.1
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct DetachRequestUeOriginating {
+    #[tlv_config(ie_type = 1)]
+    pub detach_type: u8,
+    #[tlv_config(ie_type = 1)]
+    pub nas_key_set_identifier: u8,
+    #[tlv_config(ie_type = 4, min_octets = 5, max_octets = 12)]
+    pub eps_mobile_identity: Vec<u8>,
+}
+
+/// TS 24.301 section 8.2.28
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct TrackingAreaUpdateReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub emm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x5F, min_octets = 3, max_octets = 3)]
+    pub t3346_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 1, tag = 0xA)]
+    pub extended_emm_cause: Option<u8>,
+}
+
+/// TS 24.301 section 8.2.24
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct ServiceReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub emm_cause: u8,
+    #[tlv_config(ie_type = 3, tag = 0x5B, min_octets = 2)]
+    pub t3442_value: Option<GprsTimer>,
+    #[tlv_config(ie_type = 4, tag = 0x5F, min_octets = 3, max_octets = 3)]
+    pub t3346_value: Option<GprsTimer2>,
+}
+
+/// TS 24.301 section 8.2.7
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct AuthenticationRequest {
+    #[tlv_config(ie_type = 1)]
+    pub nas_key_set_identifier: u8,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+    #[tlv_config(ie_type = 3, min_octets = 16)]
+    pub rand: Vec<u8>,
+    #[tlv_config(ie_type = 4, min_octets = 17, max_octets = 17)]
+    pub autn: Vec<u8>,
+}
+
+/// TS 24.301 section 8.2.8
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct AuthenticationResponse {
+    #[tlv_config(ie_type = 4, min_octets = 5, max_octets = 17)]
+    pub res: Vec<u8>,
+}
+
+/// TS 24.301 section 8.2.5
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AuthenticationFailure {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub emm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x30, min_octets = 16, max_octets = 16)]
+    pub authentication_failure_parameter: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.2.18
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct IdentityRequest {
+    #[tlv_config(ie_type = 1)]
+    pub identity_type: u8,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+}
+
+/// TS 24.301 section 8.2.19
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct IdentityResponse {
+    #[tlv_config(ie_type = 4, min_octets = 4, max_octets = 10)]
+    pub mobile_identity: Vec<u8>,
+}
+
+/// TS 24.301 section 8.2.20
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct SecurityModeCommand {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub selected_nas_security_algorithms: u8,
+    #[tlv_config(ie_type = 1)]
+    pub nas_key_set_identifier: u8,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+    #[tlv_config(ie_type = 4, min_octets = 3, max_octets = 6)]
+    pub replayed_ue_security_capabilities: Vec<u8>,
+    #[tlv_config(ie_type = 1, tag = 0xC)]
+    pub imeisv_request: Option<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x55, min_octets = 5)]
+    pub replayed_nonce_ue: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x56, min_octets = 5)]
+    pub nonce_mme: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x4F, min_octets = 10, max_octets = 10)]
+    pub hash_mme: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x6F, min_octets = 6, max_octets = 6)]
+    pub replayed_ue_additional_security_capability: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.2.21
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct SecurityModeComplete {
+    #[tlv_config(ie_type = 4, tag = 0x23, min_octets = 11, max_octets = 11)]
+    pub imeisv: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x79, min_octets = 3)]
+    pub replayed_nas_message_container: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.2.22
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct SecurityModeReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub emm_cause: u8,
+}
+
+/// TS 24.301 section 8.2.14
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct EmmStatus {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub emm_cause: u8,
+}
+
+/// TS 24.301 section 8.2.12
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct DownlinkNasTransport {
+    #[tlv_config(ie_type = 4, min_octets = 3, max_octets = 253)]
+    pub nas_message_container: Vec<u8>,
+}
+
+/// TS 24.301 section 8.2.30
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct UplinkNasTransport {
+    #[tlv_config(ie_type = 4, min_octets = 3, max_octets = 253)]
+    pub nas_message_container: Vec<u8>,
+}
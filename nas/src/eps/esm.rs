@@ -0,0 +1,163 @@
+// EPS session management messages, TS 24.301 section 8.3. The optional IEs are declared
+// in the order of the message tables.
+use crate::ie::{Dnn, GprsTimer3};
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+/// Message types of TS 24.301 section 9.8
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub enum EsmMessageBody {
+    #[tlv_config(tag = 0xC1)]
+    ActivateDefaultEpsBearerContextRequest(ActivateDefaultEpsBearerContextRequest),
+    #[tlv_config(tag = 0xC2)]
+    ActivateDefaultEpsBearerContextAccept(ActivateDefaultEpsBearerContextAccept),
+    #[tlv_config(tag = 0xC3)]
+    ActivateDefaultEpsBearerContextReject(ActivateDefaultEpsBearerContextReject),
+    #[tlv_config(tag = 0xCD)]
+    DeactivateEpsBearerContextRequest(DeactivateEpsBearerContextRequest),
+    #[tlv_config(tag = 0xCE)]
+    DeactivateEpsBearerContextAccept(DeactivateEpsBearerContextAccept),
+    #[tlv_config(tag = 0xD0)]
+    PdnConnectivityRequest(PdnConnectivityRequest),
+    #[tlv_config(tag = 0xD1)]
+    PdnConnectivityReject(PdnConnectivityReject),
+    #[tlv_config(tag = 0xD9)]
+    EsmInformationRequest,
+    #[tlv_config(tag = 0xDA)]
+    EsmInformationResponse(EsmInformationResponse),
+    #[tlv_config(tag = 0xE8)]
+    EsmStatus(EsmStatus),
+}
+
+/// TS 24.301 section 8.3.6. The access point name has the label coding of a DNN.
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct ActivateDefaultEpsBearerContextRequest {
+    #[tlv_config(ie_type = 4, min_octets = 2, max_octets = 14)]
+    pub eps_qos: Vec<u8>,
+    #[tlv_config(ie_type = 4, min_octets = 2, max_octets = 101)]
+    pub access_point_name: Dnn,
+    #[tlv_config(ie_type = 4, min_octets = 6, max_octets = 14)]
+    pub pdn_address: Vec<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x5D, min_octets = 3, max_octets = 4)]
+    pub transaction_identifier: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x30, min_octets = 14, max_octets = 22)]
+    pub negotiated_qos: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x32, min_octets = 2)]
+    pub negotiated_llc_sapi: Option<u8>,
+    #[tlv_config(ie_type = 1, tag = 0x8)]
+    pub radio_priority: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x34, min_octets = 3, max_octets = 3)]
+    pub packet_flow_identifier: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x5E, min_octets = 4, max_octets = 8)]
+    pub apn_ambr: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x58, min_octets = 2)]
+    pub esm_cause: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xB)]
+    pub connectivity_type: Option<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.4
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct ActivateDefaultEpsBearerContextAccept {
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.5
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct ActivateDefaultEpsBearerContextReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub esm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.12
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct DeactivateEpsBearerContextRequest {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub esm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x37, min_octets = 3, max_octets = 3)]
+    pub t3396_value: Option<GprsTimer3>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.11
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct DeactivateEpsBearerContextAccept {
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.20
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PdnConnectivityRequest {
+    #[tlv_config(ie_type = 1)]
+    pub request_type: u8,
+    #[tlv_config(ie_type = 1)]
+    pub pdn_type: u8,
+    #[tlv_config(ie_type = 1, tag = 0xD)]
+    pub esm_information_transfer_flag: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x28, min_octets = 3, max_octets = 102)]
+    pub access_point_name: Option<Dnn>,
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xC)]
+    pub device_properties: Option<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.19
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PdnConnectivityReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub esm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x37, min_octets = 3, max_octets = 3)]
+    pub back_off_timer_value: Option<GprsTimer3>,
+    #[tlv_config(ie_type = 4, tag = 0x6B, min_octets = 3, max_octets = 3)]
+    pub re_attempt_indicator: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.14
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct EsmInformationResponse {
+    #[tlv_config(ie_type = 4, tag = 0x28, min_octets = 3, max_octets = 102)]
+    pub access_point_name: Option<Dnn>,
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 3, max_octets = 253)]
+    pub protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.301 section 8.3.15
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct EsmStatus {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub esm_cause: u8,
+}
@@ -0,0 +1,215 @@
+// EPS NAS messages of TS 24.301. Unlike 5GS NAS, the first octet carries a 4 bit protocol
+// discriminator in its low nibble, with the security header type (EMM) or the EPS bearer
+// identity (ESM) in the high nibble.
+pub mod emm;
+pub mod esm;
+
+use crate::header::SecurityHeaderType;
+use emm::EmmMessageBody;
+use esm::EsmMessageBody;
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+// Protocol discriminator, TS 24.007 section 11.2.3.1.1
+pub const PD_EPS_SESSION_MANAGEMENT: u8 = 0x2;
+pub const PD_EPS_MOBILITY_MANAGEMENT: u8 = 0x7;
+
+/// A plain EPS NAS message, dispatched on the protocol discriminator and then on the
+/// message type.
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub enum EpsNasMessage {
+    #[tlv_config(tag = 0x7, tag_mask = 0x0F)]
+    Emm(Box<EmmMessage>),
+    #[tlv_config(tag = 0x2, tag_mask = 0x0F)]
+    Esm(Box<EsmMessage>),
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub struct EmmMessage {
+    #[tlv_config(format = "V", length = 1)]
+    pub header: EmmHeader,
+    #[tlv_config(format = "V")]
+    pub body: EmmMessageBody,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub struct EsmMessage {
+    #[tlv_config(format = "V", length = 2)]
+    pub header: EsmHeader,
+    #[tlv_config(format = "V")]
+    pub body: EsmMessageBody,
+}
+
+/// Octet 1 of an EMM message, TS 24.301 section 9.3.1
+///
+/// Only a plain message is accepted. A security protected message or the Service request
+/// of security header type 0xC has other octets after the first one, a MAC and a sequence
+/// number or the KSI and short MAC, which would otherwise be read as the message type and
+/// its IEs.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EmmHeader {
+    pub security_header_type: SecurityHeaderType,
+}
+
+/// Octets 1 and 2 of an ESM message, TS 24.301 section 9.3.2 and 9.4
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EsmHeader {
+    pub eps_bearer_identity: u8,
+    pub procedure_transaction_id: u8,
+}
+
+fn encode_first_octet(
+    high: u8,
+    protocol_discriminator: u8,
+    field: &'static str,
+) -> Result<u8, TlvError> {
+    if high > 0x0F {
+        return Err(TlvError::InvalidValue { field });
+    }
+    Ok(high << 4 | protocol_discriminator)
+}
+
+fn decode_first_octet(
+    length: usize,
+    header_length: usize,
+    protocol_discriminator: u8,
+    bytes: &mut Bytes,
+) -> Result<u8, TlvError> {
+    if length != header_length || bytes.remaining() < header_length {
+        return Err(TlvError::InvalidLength {
+            field: "eps_nas_header",
+            length,
+        });
+    }
+    let octet = bytes.get_u8();
+    if octet & 0x0F != protocol_discriminator {
+        return Err(TlvError::TagMismatch {
+            expected: protocol_discriminator as usize,
+            found: (octet & 0x0F) as usize,
+            field: "protocol_discriminator",
+        });
+    }
+    Ok(octet >> 4)
+}
+
+impl TlvEncode for EmmHeader {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        if self.security_header_type != SecurityHeaderType::PlainNasMessage {
+            return Err(TlvError::InvalidValue {
+                field: "security_header_type",
+            });
+        }
+        bytes.put_u8(encode_first_octet(
+            u8::from(self.security_header_type),
+            PD_EPS_MOBILITY_MANAGEMENT,
+            "security_header_type",
+        )?);
+        Ok(1)
+    }
+}
+
+impl TlvDecode for EmmHeader {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        let high = decode_first_octet(length, 1, PD_EPS_MOBILITY_MANAGEMENT, bytes)?;
+        let security_header_type = SecurityHeaderType::from(high);
+        if security_header_type != SecurityHeaderType::PlainNasMessage {
+            return Err(TlvError::InvalidValue {
+                field: "security_header_type",
+            });
+        }
+        Ok(EmmHeader {
+            security_header_type,
+        })
+    }
+}
+
+impl TlvEncode for EsmHeader {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        bytes.put_u8(encode_first_octet(
+            self.eps_bearer_identity,
+            PD_EPS_SESSION_MANAGEMENT,
+            "eps_bearer_identity",
+        )?);
+        bytes.put_u8(self.procedure_transaction_id);
+        Ok(2)
+    }
+}
+
+impl TlvDecode for EsmHeader {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        let eps_bearer_identity = decode_first_octet(length, 2, PD_EPS_SESSION_MANAGEMENT, bytes)?;
+        Ok(EsmHeader {
+            eps_bearer_identity,
+            procedure_transaction_id: bytes.get_u8(),
+        })
+    }
+}
+
+/// Everything in front of the message body, TS 24.301 section 9.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpsNasHeader {
+    Emm {
+        security_header_type: SecurityHeaderType,
+        message_type: u8,
+    },
+    Esm {
+        eps_bearer_identity: u8,
+        procedure_transaction_id: u8,
+        message_type: u8,
+    },
+}
+
+impl EpsNasHeader {
+    pub fn protocol_discriminator(&self) -> u8 {
+        match self {
+            EpsNasHeader::Emm { .. } => PD_EPS_MOBILITY_MANAGEMENT,
+            EpsNasHeader::Esm { .. } => PD_EPS_SESSION_MANAGEMENT,
+        }
+    }
+
+    pub fn message_type(&self) -> u8 {
+        match self {
+            EpsNasHeader::Emm { message_type, .. } | EpsNasHeader::Esm { message_type, .. } => {
+                *message_type
+            }
+        }
+    }
+}
+
+impl EpsNasMessage {
+    /// A plain EMM message without security protection.
+    pub fn emm(body: EmmMessageBody) -> Self {
+        EpsNasMessage::Emm(Box::new(EmmMessage {
+            header: EmmHeader::default(),
+            body,
+        }))
+    }
+
+    pub fn esm(
+        eps_bearer_identity: u8,
+        procedure_transaction_id: u8,
+        body: EsmMessageBody,
+    ) -> Self {
+        EpsNasMessage::Esm(Box::new(EsmMessage {
+            header: EsmHeader {
+                eps_bearer_identity,
+                procedure_transaction_id,
+            },
+            body,
+        }))
+    }
+
+    pub fn header(&self) -> EpsNasHeader {
+        match self {
+            EpsNasMessage::Emm(message) => EpsNasHeader::Emm {
+                security_header_type: message.header.security_header_type,
+                message_type: message.body.tag() as u8,
+            },
+            EpsNasMessage::Esm(message) => EpsNasHeader::Esm {
+                eps_bearer_identity: message.header.eps_bearer_identity,
+                procedure_transaction_id: message.header.procedure_transaction_id,
+                message_type: message.body.tag() as u8,
+            },
+        }
+    }
+}
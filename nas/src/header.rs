@@ -0,0 +1,145 @@
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+// Extended protocol discriminator, TS 24.007 section 11.2.3.1.1A
+pub const EPD_5GS_MOBILITY_MANAGEMENT: u8 = 0x7E;
+pub const EPD_5GS_SESSION_MANAGEMENT: u8 = 0x2E;
+
+/// Security header type, TS 24.501 section 9.3.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecurityHeaderType {
+    #[default]
+    PlainNasMessage,
+    IntegrityProtected,
+    IntegrityProtectedAndCiphered,
+    IntegrityProtectedWithNewSecurityContext,
+    IntegrityProtectedAndCipheredWithNewSecurityContext,
+    Reserved(u8),
+}
+
+impl From<u8> for SecurityHeaderType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SecurityHeaderType::PlainNasMessage,
+            1 => SecurityHeaderType::IntegrityProtected,
+            2 => SecurityHeaderType::IntegrityProtectedAndCiphered,
+            3 => SecurityHeaderType::IntegrityProtectedWithNewSecurityContext,
+            4 => SecurityHeaderType::IntegrityProtectedAndCipheredWithNewSecurityContext,
+            value => SecurityHeaderType::Reserved(value),
+        }
+    }
+}
+
+impl From<SecurityHeaderType> for u8 {
+    fn from(value: SecurityHeaderType) -> Self {
+        match value {
+            SecurityHeaderType::PlainNasMessage => 0,
+            SecurityHeaderType::IntegrityProtected => 1,
+            SecurityHeaderType::IntegrityProtectedAndCiphered => 2,
+            SecurityHeaderType::IntegrityProtectedWithNewSecurityContext => 3,
+            SecurityHeaderType::IntegrityProtectedAndCipheredWithNewSecurityContext => 4,
+            SecurityHeaderType::Reserved(value) => value,
+        }
+    }
+}
+
+/// Octet 2 of a 5GMM message, the extended protocol discriminator and the message
+/// type are written by [`crate::NasMessage`] and [`crate::mm::MmMessageBody`].
+///
+/// Only a plain message is accepted, a security protected one has to go through
+/// [`crate::security::SecurityProtected`]. Taken as plain, its MAC would be read as the
+/// message type and the first IEs.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MmHeader {
+    pub security_header_type: SecurityHeaderType,
+    pub spare: u8,
+}
+
+impl TlvEncode for MmHeader {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        if self.security_header_type != SecurityHeaderType::PlainNasMessage {
+            return Err(TlvError::InvalidValue {
+                field: "security_header_type",
+            });
+        }
+        if self.spare > 0x0F {
+            return Err(TlvError::InvalidValue { field: "spare" });
+        }
+        bytes.put_u8(self.spare << 4);
+        Ok(1)
+    }
+}
+
+impl TlvDecode for MmHeader {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        Self::decode_with_options(length, bytes, &DecodeOptions::default())
+    }
+
+    fn decode_with_options(
+        length: usize,
+        bytes: &mut Bytes,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        if length != 1 || !bytes.has_remaining() {
+            return Err(TlvError::InvalidLength {
+                field: "security_header_type",
+                length,
+            });
+        }
+        let octet = bytes.get_u8();
+        let security_header_type = SecurityHeaderType::from(octet & 0x0F);
+        if security_header_type != SecurityHeaderType::PlainNasMessage {
+            return Err(TlvError::InvalidValue {
+                field: "security_header_type",
+            });
+        }
+        let spare = octet >> 4;
+        if options.is_strict() && spare != 0 {
+            return Err(TlvError::NonZeroSpare { field: "spare" });
+        }
+        Ok(MmHeader {
+            security_header_type,
+            spare,
+        })
+    }
+}
+
+/// Octets 2 and 3 of a 5GSM message.
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct SmHeader {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub pdu_session_id: u8,
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub procedure_transaction_id: u8,
+}
+
+/// Everything in front of the message body, TS 24.501 section 9.1.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NasHeader {
+    Mm {
+        security_header_type: SecurityHeaderType,
+        message_type: u8,
+    },
+    Sm {
+        pdu_session_id: u8,
+        procedure_transaction_id: u8,
+        message_type: u8,
+    },
+}
+
+impl NasHeader {
+    pub fn extended_protocol_discriminator(&self) -> u8 {
+        match self {
+            NasHeader::Mm { .. } => EPD_5GS_MOBILITY_MANAGEMENT,
+            NasHeader::Sm { .. } => EPD_5GS_SESSION_MANAGEMENT,
+        }
+    }
+
+    pub fn message_type(&self) -> u8 {
+        match self {
+            NasHeader::Mm { message_type, .. } | NasHeader::Sm { message_type, .. } => {
+                *message_type
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+// Swapped nibble BCD digit strings, TS 24.008 section 10.5.1.3 and 10.5.4.7. The first
+// digit goes in the low nibble of an octet, the second one in the high nibble, and a
+// 0xF filler completes an odd number of digits.
+use std::fmt;
+use std::str::FromStr;
+use tlv::prelude::*;
+
+const FILLER: u8 = 0xF;
+
+/// A string of decimal digits, e.g. an IMSI, MSIN or emergency number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Bcd(String);
+
+impl Bcd {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The number of octets the digits take on the wire.
+    pub fn octets(&self) -> usize {
+        self.0.len().div_ceil(2)
+    }
+
+    fn nibbles(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.bytes().map(|c| c - b'0')
+    }
+
+    /// Writes the digits into exactly `octets` octets, unused nibbles are filler. Used
+    /// for fixed size fields like the routing indicator.
+    pub fn encode_padded(&self, octets: usize, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        if self.octets() > octets {
+            return Err(TlvError::InvalidLength {
+                field: "bcd",
+                length: self.octets(),
+            });
+        }
+        let mut nibbles = self.nibbles().chain(std::iter::repeat(FILLER));
+        for _ in 0..octets {
+            let low = nibbles.next().unwrap_or(FILLER);
+            let high = nibbles.next().unwrap_or(FILLER);
+            bytes.put_u8(high << 4 | low);
+        }
+        Ok(octets)
+    }
+}
+
+pub(crate) fn nibble_to_digit(nibble: u8) -> Result<char, TlvError> {
+    char::from_digit(nibble as u32, 10).ok_or(TlvError::InvalidDigit {
+        value: char::from_digit(nibble as u32, 16).unwrap_or('?'),
+    })
+}
+
+impl FromStr for Bcd {
+    type Err = TlvError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(value) = value.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(TlvError::InvalidDigit { value });
+        }
+        Ok(Bcd(value.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Bcd {
+    type Error = TlvError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Bcd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TlvEncode for Bcd {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        self.encode_padded(self.octets(), bytes)
+    }
+}
+
+// Fillers are only accepted at the end, once one is seen every following nibble must be
+// a filler as well.
+impl TlvDecode for Bcd {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if bytes.remaining() < length {
+            return Err(TlvError::InvalidLength {
+                field: "bcd",
+                length,
+            });
+        }
+        let mut digits = String::with_capacity(length * 2);
+        let mut filled = false;
+        for octet in bytes.split_to(length) {
+            for nibble in [octet & 0x0F, octet >> 4] {
+                if nibble == FILLER {
+                    filled = true;
+                } else if filled {
+                    return Err(TlvError::InvalidDigit { value: 'f' });
+                } else {
+                    digits.push(nibble_to_digit(nibble)?);
+                }
+            }
+        }
+        Ok(Bcd(digits))
+    }
+}
+
+/// PLMN identity, TS 24.008 figure 10.5.3. A two digit MNC puts its filler in the high
+/// nibble of the second octet rather than at the end.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PlmnId {
+    mcc: Bcd,
+    mnc: Bcd,
+}
+
+impl PlmnId {
+    pub fn new(mcc: &str, mnc: &str) -> Result<Self, TlvError> {
+        let mcc: Bcd = mcc.parse()?;
+        let mnc: Bcd = mnc.parse()?;
+        if mcc.len() != 3 {
+            return Err(TlvError::InvalidLength {
+                field: "mcc",
+                length: mcc.len(),
+            });
+        }
+        if mnc.len() != 2 && mnc.len() != 3 {
+            return Err(TlvError::InvalidLength {
+                field: "mnc",
+                length: mnc.len(),
+            });
+        }
+        Ok(PlmnId { mcc, mnc })
+    }
+
+    pub fn mcc(&self) -> &str {
+        self.mcc.as_str()
+    }
+
+    pub fn mnc(&self) -> &str {
+        self.mnc.as_str()
+    }
+}
+
+/// Parses the MCC followed by the MNC, five digits for a two digit MNC and six for a
+/// three digit one.
+impl FromStr for PlmnId {
+    type Err = TlvError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let digits: Bcd = value.parse()?;
+        if digits.len() < 5 || digits.len() > 6 {
+            return Err(TlvError::InvalidLength {
+                field: "plmn_id",
+                length: digits.len(),
+            });
+        }
+        let (mcc, mnc) = digits.as_str().split_at(3);
+        PlmnId::new(mcc, mnc)
+    }
+}
+
+impl TryFrom<&str> for PlmnId {
+    type Error = TlvError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for PlmnId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.mcc, self.mnc)
+    }
+}
+
+impl TlvEncode for PlmnId {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        let mcc: Vec<u8> = self.mcc.nibbles().collect();
+        let mnc: Vec<u8> = self.mnc.nibbles().collect();
+        // Only a default constructed identity gets here without its digits
+        if mcc.len() != 3 || mnc.len() < 2 {
+            return Err(TlvError::InvalidLength {
+                field: "plmn_id",
+                length: mcc.len() + mnc.len(),
+            });
+        }
+        bytes.put_u8(mcc[1] << 4 | mcc[0]);
+        bytes.put_u8(mnc.get(2).copied().unwrap_or(FILLER) << 4 | mcc[2]);
+        bytes.put_u8(mnc[1] << 4 | mnc[0]);
+        Ok(3)
+    }
+}
+
+impl TlvDecode for PlmnId {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if length != 3 || bytes.remaining() < 3 {
+            return Err(TlvError::InvalidLength {
+                field: "plmn_id",
+                length,
+            });
+        }
+        let octets = [bytes.get_u8(), bytes.get_u8(), bytes.get_u8()];
+        let mut mcc = String::with_capacity(3);
+        for nibble in [octets[0] & 0x0F, octets[0] >> 4, octets[1] & 0x0F] {
+            mcc.push(nibble_to_digit(nibble)?);
+        }
+        let mut mnc = String::with_capacity(3);
+        for nibble in [octets[2] & 0x0F, octets[2] >> 4] {
+            mnc.push(nibble_to_digit(nibble)?);
+        }
+        if octets[1] >> 4 != FILLER {
+            mnc.push(nibble_to_digit(octets[1] >> 4)?);
+        }
+        Ok(PlmnId {
+            mcc: Bcd(mcc),
+            mnc: Bcd(mnc),
+        })
+    }
+}
@@ -0,0 +1,122 @@
+// DNN, TS 24.501 section 9.11.2.1B. The value is an APN network identifier in the label
+// format of TS 23.003 section 9.1, each label prefixed with its length instead of being
+// separated by dots.
+use std::fmt;
+use std::str::FromStr;
+use tlv::prelude::*;
+
+const MAX_LABEL_LENGTH: usize = 63;
+const MAX_LENGTH: usize = 100;
+
+/// A data network name, e.g. "internet" or "ims.mnc001.mcc001.gprs".
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Dnn(String);
+
+impl Dnn {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.0.split('.')
+    }
+
+    /// The number of octets the labels take on the wire.
+    pub fn octets(&self) -> usize {
+        self.labels().map(|label| 1 + label.len()).sum()
+    }
+}
+
+fn check_label(label: &str) -> Result<(), TlvError> {
+    if label.is_empty() || label.len() > MAX_LABEL_LENGTH {
+        return Err(TlvError::InvalidLength {
+            field: "dnn_label",
+            length: label.len(),
+        });
+    }
+    // Letters, digits and hyphens, TS 23.003 section 9.1. A dot within a decoded label
+    // would come back as a label separator on encode.
+    if !label.bytes().all(|octet| octet.is_ascii_alphanumeric() || octet == b'-') {
+        return Err(TlvError::InvalidValue { field: "dnn" });
+    }
+    Ok(())
+}
+
+/// Parses the dotted form. Labels have to be 1 to 63 letters, digits or hyphens and the
+/// encoded name at most 100 octets.
+impl FromStr for Dnn {
+    type Err = TlvError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let dnn = Dnn(value.to_string());
+        for label in dnn.labels() {
+            check_label(label)?;
+        }
+        if dnn.octets() > MAX_LENGTH {
+            return Err(TlvError::InvalidLength {
+                field: "dnn",
+                length: dnn.octets(),
+            });
+        }
+        Ok(dnn)
+    }
+}
+
+impl TryFrom<&str> for Dnn {
+    type Error = TlvError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Dnn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TlvEncode for Dnn {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        // Only a default constructed name gets here without a label
+        if self.0.is_empty() {
+            return Err(TlvError::InvalidLength {
+                field: "dnn",
+                length: 0,
+            });
+        }
+        for label in self.labels() {
+            bytes.put_u8(label.len() as u8);
+            bytes.put_slice(label.as_bytes());
+        }
+        Ok(self.octets())
+    }
+}
+
+impl TlvDecode for Dnn {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if length == 0 || length > MAX_LENGTH || bytes.remaining() < length {
+            return Err(TlvError::InvalidLength {
+                field: "dnn",
+                length,
+            });
+        }
+        let mut value = bytes.split_to(length);
+        let mut labels = Vec::new();
+        while value.has_remaining() {
+            let label_length = value.get_u8() as usize;
+            if label_length > value.remaining() {
+                return Err(TlvError::InvalidLength {
+                    field: "dnn_label",
+                    length: label_length,
+                });
+            }
+            let label = value.split_to(label_length);
+            let label =
+                std::str::from_utf8(&label).map_err(|_| TlvError::InvalidValue { field: "dnn" })?;
+            check_label(label)?;
+            labels.push(label.to_string());
+        }
+        Ok(Dnn(labels.join(".")))
+    }
+}
@@ -0,0 +1,151 @@
+// EAP-AKA' packets, RFC 9048, as carried by the EAP message IE of TS 24.501 section
+// 9.11.2.2. The IE stays a `Vec<u8>` in the messages since it also carries EAP Success and
+// Failure, an EAP-AKA' request or response is decoded out of it with `EapAkaPrime`.
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+pub const EAP_CODE_REQUEST: u8 = 1;
+pub const EAP_CODE_RESPONSE: u8 = 2;
+pub const EAP_TYPE_AKA_PRIME: u8 = 50;
+
+pub const EAP_AKA_SUBTYPE_CHALLENGE: u8 = 1;
+pub const EAP_AKA_SUBTYPE_AUTHENTICATION_REJECT: u8 = 2;
+pub const EAP_AKA_SUBTYPE_SYNCHRONIZATION_FAILURE: u8 = 4;
+pub const EAP_AKA_SUBTYPE_IDENTITY: u8 = 5;
+pub const EAP_AKA_SUBTYPE_NOTIFICATION: u8 = 12;
+pub const EAP_AKA_SUBTYPE_CLIENT_ERROR: u8 = 14;
+
+// Code, identifier, length, type, subtype and the reserved octets
+const HEADER_OCTETS: usize = 8;
+
+/// Attributes of an EAP-AKA' packet, RFC 4187 section 10. The length of an attribute counts
+/// 4 octet units including its type and length octets. Values are kept as they are after
+/// the length: reserved octets, actual length fields and padding included. A server offering
+/// several key derivation functions sends an AT_KDF for each, RFC 9048 section 3.2. An
+/// AKA-Identity request asks for an identity with one of the three `_id_req` attributes.
+/// Attributes come in any order, unknown ones of 128 and up are skipped and the others fail.
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unordered, skippable_from = 128)]
+pub struct EapAkaAttributes {
+    #[tlv_config(tag = 1, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_rand: Option<Vec<u8>>,
+    #[tlv_config(tag = 2, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_autn: Option<Vec<u8>>,
+    #[tlv_config(tag = 3, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_res: Option<Vec<u8>>,
+    #[tlv_config(tag = 4, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_auts: Option<Vec<u8>>,
+    #[tlv_config(tag = 10, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_permanent_id_req: Option<Vec<u8>>,
+    #[tlv_config(tag = 11, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_mac: Option<Vec<u8>>,
+    #[tlv_config(tag = 12, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_notification: Option<Vec<u8>>,
+    #[tlv_config(tag = 13, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_any_id_req: Option<Vec<u8>>,
+    #[tlv_config(tag = 14, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_identity: Option<Vec<u8>>,
+    #[tlv_config(tag = 17, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_fullauth_id_req: Option<Vec<u8>>,
+    #[tlv_config(tag = 22, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_client_error_code: Option<Vec<u8>>,
+    #[tlv_config(tag = 23, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_kdf_input: Option<Vec<u8>>,
+    #[tlv_config(tag = 24, format = "TLV", length_unit = 4, length_includes_header, repeated)]
+    pub at_kdf: Vec<Vec<u8>>,
+    #[tlv_config(tag = 129, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_iv: Option<Vec<u8>>,
+    #[tlv_config(tag = 130, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_encr_data: Option<Vec<u8>>,
+    #[tlv_config(tag = 134, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_checkcode: Option<Vec<u8>>,
+    #[tlv_config(tag = 135, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_result_ind: Option<Vec<u8>>,
+    #[tlv_config(tag = 136, format = "TLV", length_unit = 4, length_includes_header)]
+    pub at_bidding: Option<Vec<u8>>,
+}
+
+/// An EAP-AKA' request or response, RFC 4187 section 8.1.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EapAkaPrime {
+    pub code: u8,
+    pub identifier: u8,
+    pub subtype: u8,
+    pub attributes: EapAkaAttributes,
+}
+
+impl TlvEncode for EapAkaPrime {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        let mut attributes = BytesMut::new();
+        let length = HEADER_OCTETS + self.attributes.encode(&mut attributes)?;
+        if length > u16::MAX as usize {
+            return Err(TlvError::InvalidLength {
+                field: "eap_message",
+                length,
+            });
+        }
+        bytes.put_u8(self.code);
+        bytes.put_u8(self.identifier);
+        bytes.put_u16(length as u16);
+        bytes.put_u8(EAP_TYPE_AKA_PRIME);
+        bytes.put_u8(self.subtype);
+        bytes.put_u16(0);
+        bytes.put(attributes);
+        Ok(length)
+    }
+}
+
+impl TlvDecode for EapAkaPrime {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        Self::decode_with_options(length, bytes, &DecodeOptions::default())
+    }
+
+    fn decode_with_options(
+        length: usize,
+        bytes: &mut Bytes,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        if length < HEADER_OCTETS || bytes.remaining() < length {
+            return Err(TlvError::InvalidLength {
+                field: "eap_message",
+                length,
+            });
+        }
+        let mut packet = bytes.split_to(length);
+        let code = packet.get_u8();
+        let identifier = packet.get_u8();
+        let eap_length = packet.get_u16() as usize;
+        if eap_length < HEADER_OCTETS
+            || eap_length > length
+            || (options.is_strict() && eap_length != length)
+        {
+            return Err(TlvError::InvalidLength {
+                field: "eap_message",
+                length: eap_length,
+            });
+        }
+        packet.truncate(eap_length - 4);
+        let eap_type = packet.get_u8();
+        if eap_type != EAP_TYPE_AKA_PRIME {
+            return Err(TlvError::TagMismatch {
+                expected: EAP_TYPE_AKA_PRIME as usize,
+                found: eap_type as usize,
+                field: "eap_message",
+            });
+        }
+        let subtype = packet.get_u8();
+        if packet.get_u16() != 0 && options.is_strict() {
+            return Err(TlvError::NonZeroSpare {
+                field: "eap_message",
+            });
+        }
+        let attributes =
+            EapAkaAttributes::decode_with_options(packet.remaining(), &mut packet, options)?;
+        Ok(EapAkaPrime {
+            code,
+            identifier,
+            subtype,
+            attributes,
+        })
+    }
+}
@@ -0,0 +1,339 @@
+// 5GS mobile identity, TS 24.501 section 9.11.3.4. The type of identity in bits 3 to 1
+// of the first octet selects the layout, the other bits of that octet belong to the
+// identity itself so every identity encodes and decodes it on its own.
+use crate::ie::bcd::{Bcd, PlmnId, nibble_to_digit};
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+const TYPE_SUCI: u8 = 0b001;
+const TYPE_GUTI: u8 = 0b010;
+const TYPE_IMEI: u8 = 0b011;
+const TYPE_S_TMSI: u8 = 0b100;
+const TYPE_IMEISV: u8 = 0b101;
+const TYPE_MAC_ADDRESS: u8 = 0b110;
+const TYPE_EUI64: u8 = 0b111;
+
+const SUPI_FORMAT_IMSI: u8 = 0b000;
+const SUPI_FORMAT_NAI: u8 = 0b001;
+
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub enum MobileIdentity {
+    #[default]
+    #[tlv_config(tag = 0b000, tag_mask = 0x07)]
+    NoIdentity,
+    #[tlv_config(tag = 0b001, tag_mask = 0x07)]
+    Suci(Suci),
+    #[tlv_config(tag = 0b010, tag_mask = 0x07)]
+    Guti(Guti5g),
+    #[tlv_config(tag = 0b011, tag_mask = 0x07)]
+    Imei(Imei),
+    #[tlv_config(tag = 0b100, tag_mask = 0x07)]
+    STmsi(STmsi5g),
+    #[tlv_config(tag = 0b101, tag_mask = 0x07)]
+    Imeisv(Imeisv),
+    #[tlv_config(tag = 0b110, tag_mask = 0x07)]
+    MacAddress(MacAddress),
+    #[tlv_config(tag = 0b111, tag_mask = 0x07)]
+    Eui64(Eui64),
+}
+
+/// SUCI, figures 9.11.3.4.3 and 9.11.3.4.3a
+#[derive(Debug, Clone, PartialEq)]
+pub enum Suci {
+    Imsi(SuciImsi),
+    Nai(String),
+}
+
+/// SUCI with the IMSI SUPI format. `scheme_output` is the MSIN for the null scheme,
+/// otherwise the ECIES output of TS 33.501 annex C.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SuciImsi {
+    pub plmn_id: PlmnId,
+    pub routing_indicator: Bcd,
+    pub protection_scheme_id: u8,
+    pub home_network_public_key_id: u8,
+    pub scheme_output: Vec<u8>,
+}
+
+/// 5G-GUTI, figure 9.11.3.4.1
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Guti5g {
+    pub plmn_id: PlmnId,
+    pub amf_region_id: u8,
+    pub amf_set_id: u16,
+    pub amf_pointer: u8,
+    pub tmsi: u32,
+}
+
+/// 5G-S-TMSI, figure 9.11.3.4.5
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct STmsi5g {
+    pub amf_set_id: u16,
+    pub amf_pointer: u8,
+    pub tmsi: u32,
+}
+
+/// IMEI digits, figure 9.11.3.4.2
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Imei(pub Bcd);
+
+/// IMEISV digits, figure 9.11.3.4.2
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Imeisv(pub Bcd);
+
+/// MAC address, figure 9.11.3.4.6
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MacAddress {
+    pub address: [u8; 6],
+    pub usage_restricted: bool,
+}
+
+/// EUI-64, figure 9.11.3.4.7
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Eui64(pub [u8; 8]);
+
+fn ensure_remaining(bytes: &Bytes, length: usize, field: &'static str) -> Result<(), TlvError> {
+    if bytes.remaining() < length {
+        return Err(TlvError::InvalidLength {
+            field,
+            length: bytes.remaining(),
+        });
+    }
+    Ok(())
+}
+
+// AMF set ID (10 bits) followed by the AMF pointer (6 bits)
+fn put_amf_set_pointer(bytes: &mut BytesMut, amf_set_id: u16, amf_pointer: u8) -> Result<(), TlvError> {
+    if amf_set_id > 0x3FF || amf_pointer > 0x3F {
+        return Err(TlvError::MalformedPayload);
+    }
+    bytes.put_u16(amf_set_id << 6 | amf_pointer as u16);
+    Ok(())
+}
+
+fn get_amf_set_pointer(bytes: &mut Bytes) -> (u16, u8) {
+    let value = bytes.get_u16();
+    (value >> 6, (value & 0x3F) as u8)
+}
+
+// IMEI and IMEISV, the first digit shares the octet with the odd/even indicator and the
+// remaining ones are plain BCD.
+fn encode_imei_digits(value: &Bcd, identity_type: u8, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+    let (first, rest) = value.as_str().split_at_checked(1).ok_or(TlvError::MalformedPayload)?;
+    let first = first.parse::<u8>().map_err(|_| TlvError::MalformedPayload)?;
+    let odd = value.len() % 2;
+    bytes.put_u8(first << 4 | (odd as u8) << 3 | identity_type);
+    Ok(1 + rest.parse::<Bcd>()?.encode(bytes)?)
+}
+
+fn decode_imei_digits(length: usize, bytes: &mut Bytes) -> Result<Bcd, TlvError> {
+    if length == 0 {
+        return Err(TlvError::InvalidLength {
+            field: "mobile_identity",
+            length,
+        });
+    }
+    ensure_remaining(bytes, length, "mobile_identity")?;
+    let octet = bytes.get_u8();
+    let odd = octet & 0x08 != 0;
+    let rest = Bcd::decode(length - 1, bytes)?;
+    if (rest.len() % 2 == 0) != odd {
+        return Err(TlvError::MalformedPayload);
+    }
+    format!("{}{}", nibble_to_digit(octet >> 4)?, rest).parse()
+}
+
+impl TlvEncode for Suci {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        match self {
+            Suci::Imsi(imsi) => {
+                bytes.put_u8(SUPI_FORMAT_IMSI << 4 | TYPE_SUCI);
+                imsi.plmn_id.encode(bytes)?;
+                // One to four digits, unused ones are filler
+                if imsi.routing_indicator.is_empty() || imsi.routing_indicator.len() > 4 {
+                    return Err(TlvError::MalformedPayload);
+                }
+                imsi.routing_indicator.encode_padded(2, bytes)?;
+                bytes.put_u8(imsi.protection_scheme_id & 0x0F);
+                bytes.put_u8(imsi.home_network_public_key_id);
+                bytes.put_slice(&imsi.scheme_output);
+                Ok(8 + imsi.scheme_output.len())
+            }
+            Suci::Nai(nai) => {
+                bytes.put_u8(SUPI_FORMAT_NAI << 4 | TYPE_SUCI);
+                bytes.put_slice(nai.as_bytes());
+                Ok(1 + nai.len())
+            }
+        }
+    }
+}
+
+impl TlvDecode for Suci {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        ensure_remaining(bytes, length.max(1), "mobile_identity")?;
+        let octet = bytes.get_u8();
+        match (octet >> 4) & 0x07 {
+            SUPI_FORMAT_IMSI => {
+                if length < 8 {
+                    return Err(TlvError::InvalidLength {
+                        field: "mobile_identity",
+                        length,
+                    });
+                }
+                let plmn_id = PlmnId::decode(3, bytes)?;
+                let routing_indicator = Bcd::decode(2, bytes)?;
+                Ok(Suci::Imsi(SuciImsi {
+                    plmn_id,
+                    routing_indicator,
+                    protection_scheme_id: bytes.get_u8() & 0x0F,
+                    home_network_public_key_id: bytes.get_u8(),
+                    scheme_output: bytes.split_to(length - 8).to_vec(),
+                }))
+            }
+            SUPI_FORMAT_NAI => {
+                let nai = bytes.split_to(length.saturating_sub(1));
+                String::from_utf8(nai.to_vec())
+                    .map(Suci::Nai)
+                    .map_err(|_| TlvError::MalformedPayload)
+            }
+            _ => Err(TlvError::MalformedPayload),
+        }
+    }
+}
+
+impl TlvEncode for Guti5g {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        bytes.put_u8(0xF0 | TYPE_GUTI);
+        self.plmn_id.encode(bytes)?;
+        bytes.put_u8(self.amf_region_id);
+        put_amf_set_pointer(bytes, self.amf_set_id, self.amf_pointer)?;
+        bytes.put_u32(self.tmsi);
+        Ok(11)
+    }
+}
+
+impl TlvDecode for Guti5g {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if length != 11 {
+            return Err(TlvError::InvalidLength {
+                field: "mobile_identity",
+                length,
+            });
+        }
+        ensure_remaining(bytes, length, "mobile_identity")?;
+        bytes.advance(1);
+        let plmn_id = PlmnId::decode(3, bytes)?;
+        let amf_region_id = bytes.get_u8();
+        let (amf_set_id, amf_pointer) = get_amf_set_pointer(bytes);
+        Ok(Guti5g {
+            plmn_id,
+            amf_region_id,
+            amf_set_id,
+            amf_pointer,
+            tmsi: bytes.get_u32(),
+        })
+    }
+}
+
+impl TlvEncode for STmsi5g {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        bytes.put_u8(0xF0 | TYPE_S_TMSI);
+        put_amf_set_pointer(bytes, self.amf_set_id, self.amf_pointer)?;
+        bytes.put_u32(self.tmsi);
+        Ok(7)
+    }
+}
+
+impl TlvDecode for STmsi5g {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if length != 7 {
+            return Err(TlvError::InvalidLength {
+                field: "mobile_identity",
+                length,
+            });
+        }
+        ensure_remaining(bytes, length, "mobile_identity")?;
+        bytes.advance(1);
+        let (amf_set_id, amf_pointer) = get_amf_set_pointer(bytes);
+        Ok(STmsi5g {
+            amf_set_id,
+            amf_pointer,
+            tmsi: bytes.get_u32(),
+        })
+    }
+}
+
+impl TlvEncode for Imei {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        encode_imei_digits(&self.0, TYPE_IMEI, bytes)
+    }
+}
+
+impl TlvDecode for Imei {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        decode_imei_digits(length, bytes).map(Imei)
+    }
+}
+
+impl TlvEncode for Imeisv {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        encode_imei_digits(&self.0, TYPE_IMEISV, bytes)
+    }
+}
+
+impl TlvDecode for Imeisv {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        decode_imei_digits(length, bytes).map(Imeisv)
+    }
+}
+
+impl TlvEncode for MacAddress {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        bytes.put_u8((self.usage_restricted as u8) << 3 | TYPE_MAC_ADDRESS);
+        bytes.put_slice(&self.address);
+        Ok(7)
+    }
+}
+
+impl TlvDecode for MacAddress {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if length != 7 {
+            return Err(TlvError::InvalidLength {
+                field: "mobile_identity",
+                length,
+            });
+        }
+        ensure_remaining(bytes, length, "mobile_identity")?;
+        let usage_restricted = bytes.get_u8() & 0x08 != 0;
+        let mut address = [0u8; 6];
+        bytes.copy_to_slice(&mut address);
+        Ok(MacAddress {
+            address,
+            usage_restricted,
+        })
+    }
+}
+
+impl TlvEncode for Eui64 {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        bytes.put_u8(TYPE_EUI64);
+        bytes.put_slice(&self.0);
+        Ok(9)
+    }
+}
+
+impl TlvDecode for Eui64 {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if length != 9 {
+            return Err(TlvError::InvalidLength {
+                field: "mobile_identity",
+                length,
+            });
+        }
+        ensure_remaining(bytes, length, "mobile_identity")?;
+        bytes.advance(1);
+        let mut eui64 = [0u8; 8];
+        bytes.copy_to_slice(&mut eui64);
+        Ok(Eui64(eui64))
+    }
+}
@@ -0,0 +1,20 @@
+// Information elements of TS 24.501 section 9.11 that need more than a `Vec<u8>`.
+pub mod bcd;
+pub mod dnn;
+pub mod eap;
+pub mod mobile_identity;
+pub mod nssai;
+pub mod payload_container;
+pub mod timer;
+
+pub use bcd::{Bcd, PlmnId};
+pub use dnn::Dnn;
+pub use eap::{EapAkaAttributes, EapAkaPrime};
+pub use mobile_identity::{
+    Eui64, Guti5g, Imei, Imeisv, MacAddress, MobileIdentity, STmsi5g, Suci, SuciImsi,
+};
+pub use nssai::{Nssai, SNssai, Sd};
+pub use payload_container::{
+    PayloadContainer, PayloadContainerEntry, PayloadContainerEntryIes, PayloadContainerType,
+};
+pub use timer::{GprsTimer, GprsTimer2, GprsTimer3, GprsTimer3Unit, GprsTimerUnit};
@@ -0,0 +1,110 @@
+// S-NSSAI, TS 24.501 section 9.11.2.8, and NSSAI, section 9.11.3.37. Which parts of an
+// S-NSSAI are present is only known from its length.
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+/// Slice differentiator, three octets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Sd(pub [u8; 3]);
+
+impl From<Sd> for u32 {
+    fn from(value: Sd) -> Self {
+        u32::from_be_bytes([0, value.0[0], value.0[1], value.0[2]])
+    }
+}
+
+impl TryFrom<u32> for Sd {
+    type Error = TlvError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        let [high, octets @ ..] = value.to_be_bytes();
+        if high != 0 {
+            return Err(TlvError::MalformedPayload);
+        }
+        Ok(Sd(octets))
+    }
+}
+
+impl TlvEncode for Sd {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        bytes.put_slice(&self.0);
+        Ok(3)
+    }
+}
+
+impl TlvDecode for Sd {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        if length != 3 || bytes.remaining() < 3 {
+            return Err(TlvError::InvalidLength { field: "sd", length });
+        }
+        let mut sd = [0u8; 3];
+        bytes.copy_to_slice(&mut sd);
+        Ok(Sd(sd))
+    }
+}
+
+/// The length is 1, 2, 4, 5 or 8 depending on which of the optional parts are present,
+/// any other combination is rejected when encoding.
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct SNssai {
+    #[tlv_config(format = "V", length = 1)]
+    pub sst: u8,
+    #[tlv_config(format = "V", length = 3, present_if_length = [4, 5, 8])]
+    pub sd: Option<Sd>,
+    #[tlv_config(format = "V", length = 1, present_if_length = [2, 5, 8])]
+    pub mapped_hplmn_sst: Option<u8>,
+    #[tlv_config(format = "V", length = 3, present_if_length = [8])]
+    pub mapped_hplmn_sd: Option<Sd>,
+}
+
+/// A list of S-NSSAIs, each one length prefixed. Used for the requested, allowed and
+/// configured NSSAI.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Nssai(pub Vec<SNssai>);
+
+impl TlvEncode for Nssai {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        let mut total_length = 0;
+        for s_nssai in &self.0 {
+            let mut value = BytesMut::new();
+            let length = s_nssai.encode(&mut value)?;
+            bytes.put_u8(length as u8);
+            bytes.put(value);
+            total_length += 1 + length;
+        }
+        Ok(total_length)
+    }
+}
+
+impl TlvDecode for Nssai {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        Self::decode_with_options(length, bytes, &DecodeOptions::default())
+    }
+
+    fn decode_with_options(
+        length: usize,
+        bytes: &mut Bytes,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        if bytes.remaining() < length {
+            return Err(TlvError::InvalidLength {
+                field: "nssai",
+                length,
+            });
+        }
+        let mut value = bytes.split_to(length);
+        let mut s_nssais = Vec::new();
+        while value.has_remaining() {
+            let s_nssai_length = value.get_u8() as usize;
+            if value.remaining() < s_nssai_length {
+                return Err(TlvError::InvalidLength {
+                    field: "s_nssai",
+                    length: s_nssai_length,
+                });
+            }
+            let mut s_nssai = value.split_to(s_nssai_length);
+            s_nssais.push(SNssai::decode_with_options(s_nssai_length, &mut s_nssai, options)?);
+        }
+        Ok(Nssai(s_nssais))
+    }
+}
@@ -0,0 +1,322 @@
+// Payload container, TS 24.501 section 9.11.3.39, and payload container type, section
+// 9.11.3.40. What the container holds is only known from the type, which is a separate IE
+// of the message, so the container is decoded with `depends_on` the type.
+use crate::header::{EPD_5GS_MOBILITY_MANAGEMENT, EPD_5GS_SESSION_MANAGEMENT};
+use crate::ie::{Dnn, GprsTimer3, SNssai};
+use crate::message::NasMessage;
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+/// Payload container type, a half octet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadContainerType {
+    N1SmInformation,
+    Sms,
+    LppMessage,
+    SorTransparentContainer,
+    UePolicyContainer,
+    UeParametersUpdateTransparentContainer,
+    LocationServicesMessageContainer,
+    CiotUserData,
+    #[default]
+    MultiplePayloads,
+    Reserved(u8),
+}
+
+impl From<u8> for PayloadContainerType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x1 => PayloadContainerType::N1SmInformation,
+            0x2 => PayloadContainerType::Sms,
+            0x3 => PayloadContainerType::LppMessage,
+            0x4 => PayloadContainerType::SorTransparentContainer,
+            0x5 => PayloadContainerType::UePolicyContainer,
+            0x6 => PayloadContainerType::UeParametersUpdateTransparentContainer,
+            0x7 => PayloadContainerType::LocationServicesMessageContainer,
+            0x8 => PayloadContainerType::CiotUserData,
+            0xF => PayloadContainerType::MultiplePayloads,
+            value => PayloadContainerType::Reserved(value),
+        }
+    }
+}
+
+impl From<PayloadContainerType> for u8 {
+    fn from(value: PayloadContainerType) -> Self {
+        match value {
+            PayloadContainerType::N1SmInformation => 0x1,
+            PayloadContainerType::Sms => 0x2,
+            PayloadContainerType::LppMessage => 0x3,
+            PayloadContainerType::SorTransparentContainer => 0x4,
+            PayloadContainerType::UePolicyContainer => 0x5,
+            PayloadContainerType::UeParametersUpdateTransparentContainer => 0x6,
+            PayloadContainerType::LocationServicesMessageContainer => 0x7,
+            PayloadContainerType::CiotUserData => 0x8,
+            PayloadContainerType::MultiplePayloads => 0xF,
+            PayloadContainerType::Reserved(value) => value,
+        }
+    }
+}
+
+/// The contents of a payload container. `payload_container_type` gives the type IE to
+/// send along with it, encoding a message with another type fails.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayloadContainer {
+    /// A 5GSM message
+    N1SmInformation(Box<NasMessage>),
+    Sms(Vec<u8>),
+    LppMessage(Vec<u8>),
+    MultiplePayloads(Vec<PayloadContainerEntry>),
+    /// The types without a model here, kept as they are
+    Other(PayloadContainerType, Vec<u8>),
+}
+
+/// An empty list of multiple payloads, matching the default `PayloadContainerType`.
+impl Default for PayloadContainer {
+    fn default() -> Self {
+        PayloadContainer::MultiplePayloads(Vec::new())
+    }
+}
+
+impl PayloadContainer {
+    pub fn payload_container_type(&self) -> PayloadContainerType {
+        match self {
+            PayloadContainer::N1SmInformation(_) => PayloadContainerType::N1SmInformation,
+            PayloadContainer::Sms(_) => PayloadContainerType::Sms,
+            PayloadContainer::LppMessage(_) => PayloadContainerType::LppMessage,
+            PayloadContainer::MultiplePayloads(_) => PayloadContainerType::MultiplePayloads,
+            PayloadContainer::Other(payload_container_type, _) => *payload_container_type,
+        }
+    }
+}
+
+impl TlvEncode for PayloadContainer {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        match self {
+            PayloadContainer::N1SmInformation(message) => {
+                if let NasMessage::Mm(_) = message.as_ref() {
+                    return Err(TlvError::InvalidValue {
+                        field: "payload_container",
+                    });
+                }
+                message.encode(bytes)
+            }
+            PayloadContainer::Sms(contents)
+            | PayloadContainer::LppMessage(contents)
+            | PayloadContainer::Other(_, contents) => contents.encode(bytes),
+            PayloadContainer::MultiplePayloads(entries) => {
+                if entries.len() > u8::MAX as usize {
+                    return Err(TlvError::InvalidValue {
+                        field: "payload_container",
+                    });
+                }
+                bytes.put_u8(entries.len() as u8);
+                let mut total_length = 1;
+                for entry in entries {
+                    total_length += entry.encode(bytes)?;
+                }
+                Ok(total_length)
+            }
+        }
+    }
+}
+
+/// The container has to be of the type sent along with it.
+impl TlvEncodeWith<PayloadContainerType> for PayloadContainer {
+    fn check_with(&self, context: &PayloadContainerType) -> Result<(), TlvError> {
+        if self.payload_container_type() != *context {
+            return Err(TlvError::InvalidValue {
+                field: "payload_container",
+            });
+        }
+        Ok(())
+    }
+}
+
+impl TlvDecodeWith<PayloadContainerType> for PayloadContainer {
+    fn decode_with(
+        length: usize,
+        bytes: &mut Bytes,
+        context: &PayloadContainerType,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        if bytes.remaining() < length {
+            return Err(TlvError::InvalidLength {
+                field: "payload_container",
+                length,
+            });
+        }
+        match context {
+            PayloadContainerType::N1SmInformation => {
+                let message = NasMessage::decode_with_options(length, bytes, options)?;
+                if let NasMessage::Mm(_) = message {
+                    return Err(TlvError::TagMismatch {
+                        expected: EPD_5GS_SESSION_MANAGEMENT as usize,
+                        found: EPD_5GS_MOBILITY_MANAGEMENT as usize,
+                        field: "payload_container",
+                    });
+                }
+                Ok(PayloadContainer::N1SmInformation(Box::new(message)))
+            }
+            PayloadContainerType::Sms => Ok(PayloadContainer::Sms(Vec::decode(length, bytes)?)),
+            PayloadContainerType::LppMessage => {
+                Ok(PayloadContainer::LppMessage(Vec::decode(length, bytes)?))
+            }
+            PayloadContainerType::MultiplePayloads => {
+                let mut value = bytes.split_to(length);
+                if !value.has_remaining() {
+                    return Err(TlvError::InvalidLength {
+                        field: "payload_container",
+                        length,
+                    });
+                }
+                let count = value.get_u8();
+                let mut entries = Vec::with_capacity(count as usize);
+                for _ in 0..count {
+                    entries.push(PayloadContainerEntry::decode_with_options(
+                        value.remaining(),
+                        &mut value,
+                        options,
+                    )?);
+                }
+                if options.is_strict() && value.has_remaining() {
+                    return Err(TlvError::TrailingBytes {
+                        field: "payload_container",
+                        remaining: value.remaining(),
+                    });
+                }
+                Ok(PayloadContainer::MultiplePayloads(entries))
+            }
+            payload_container_type => Ok(PayloadContainer::Other(
+                *payload_container_type,
+                Vec::decode(length, bytes)?,
+            )),
+        }
+    }
+}
+
+/// Optional IEs of a payload container entry, all of them TLV.
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct PayloadContainerEntryIes {
+    #[tlv_config(ie_type = 4, tag = 0x12, min_octets = 3, max_octets = 3)]
+    pub pdu_session_id: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x59, min_octets = 3, max_octets = 3)]
+    pub old_pdu_session_id: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x22, min_octets = 3, max_octets = 10)]
+    pub s_nssai: Option<SNssai>,
+    #[tlv_config(ie_type = 4, tag = 0x25, min_octets = 3, max_octets = 102)]
+    pub dnn: Option<Dnn>,
+    #[tlv_config(ie_type = 4, tag = 0x24, min_octets = 3)]
+    pub additional_information: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x58, min_octets = 3, max_octets = 3)]
+    pub mm_cause: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x37, min_octets = 3, max_octets = 3)]
+    pub back_off_timer_value: Option<GprsTimer3>,
+}
+
+/// One payload of a multiple payloads container, TS 24.501 figure 9.11.3.39.2. The entry
+/// carries its own type next to the number of optional IEs, so entries are not nested.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PayloadContainerEntry {
+    pub optional_ies: PayloadContainerEntryIes,
+    pub payload: PayloadContainer,
+}
+
+// Number of TLV IEs in `bytes`, the entry only gives the number and not their length
+fn count_ies(bytes: &[u8]) -> usize {
+    let mut count = 0;
+    let mut offset = 0;
+    while offset + 1 < bytes.len() {
+        offset += 2 + bytes[offset + 1] as usize;
+        count += 1;
+    }
+    count
+}
+
+impl TlvEncode for PayloadContainerEntry {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        let payload_container_type = self.payload.payload_container_type();
+        if payload_container_type == PayloadContainerType::MultiplePayloads {
+            return Err(TlvError::InvalidValue {
+                field: "payload_container_entry",
+            });
+        }
+        let mut ies = BytesMut::new();
+        self.optional_ies.encode(&mut ies)?;
+        let count = count_ies(&ies);
+        if count > 0x0F {
+            return Err(TlvError::InvalidValue {
+                field: "payload_container_entry",
+            });
+        }
+        let mut value = BytesMut::new();
+        value.put_u8((count as u8) << 4 | u8::from(payload_container_type));
+        value.put(ies);
+        self.payload.encode(&mut value)?;
+        if value.len() > u16::MAX as usize {
+            return Err(TlvError::InvalidLength {
+                field: "payload_container_entry",
+                length: value.len(),
+            });
+        }
+        bytes.put_u16(value.len() as u16);
+        let length = value.len();
+        bytes.put(value);
+        Ok(2 + length)
+    }
+}
+
+impl TlvDecode for PayloadContainerEntry {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        Self::decode_with_options(length, bytes, &DecodeOptions::default())
+    }
+
+    fn decode_with_options(
+        _length: usize,
+        bytes: &mut Bytes,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        if bytes.remaining() < 3 {
+            return Err(TlvError::InvalidLength {
+                field: "payload_container_entry",
+                length: bytes.remaining(),
+            });
+        }
+        let entry_length = bytes.get_u16() as usize;
+        if entry_length < 1 || bytes.remaining() < entry_length {
+            return Err(TlvError::InvalidLength {
+                field: "payload_container_entry",
+                length: entry_length,
+            });
+        }
+        let mut entry = bytes.split_to(entry_length);
+        let octet = entry.get_u8();
+        let payload_container_type = PayloadContainerType::from(octet & 0x0F);
+        if payload_container_type == PayloadContainerType::MultiplePayloads {
+            return Err(TlvError::MalformedPayload);
+        }
+
+        let mut ies_length = 0;
+        for _ in 0..octet >> 4 {
+            if ies_length + 2 > entry.remaining() {
+                return Err(TlvError::MalformedPayload);
+            }
+            ies_length += 2 + entry[ies_length + 1] as usize;
+        }
+        if ies_length > entry.remaining() {
+            return Err(TlvError::MalformedPayload);
+        }
+        let mut ies = entry.split_to(ies_length);
+        let optional_ies =
+            PayloadContainerEntryIes::decode_with_options(ies_length, &mut ies, options)?;
+        let payload = PayloadContainer::decode_with(
+            entry.remaining(),
+            &mut entry,
+            &payload_container_type,
+            options,
+        )?;
+        Ok(PayloadContainerEntry {
+            optional_ies,
+            payload,
+        })
+    }
+}
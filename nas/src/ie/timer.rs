@@ -0,0 +1,216 @@
+// GPRS timer, GPRS timer 2 and GPRS timer 3, TS 24.008 sections 10.5.7.3, 10.5.7.4 and
+// 10.5.7.4a. All of them are a 5 bit value in bits 5 to 1 multiplied by the 3 bit unit in
+// bits 8 to 6, only the unit sets differ.
+use std::time::Duration;
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+const MAX_VALUE: u64 = 0b11111;
+
+/// Unit of a GPRS timer and GPRS timer 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GprsTimerUnit {
+    #[default]
+    TwoSeconds,
+    OneMinute,
+    Decihours,
+    Deactivated,
+    /// Interpreted as multiples of 1 minute
+    Reserved(u8),
+}
+
+impl From<u8> for GprsTimerUnit {
+    fn from(value: u8) -> Self {
+        match value {
+            0b000 => GprsTimerUnit::TwoSeconds,
+            0b001 => GprsTimerUnit::OneMinute,
+            0b010 => GprsTimerUnit::Decihours,
+            0b111 => GprsTimerUnit::Deactivated,
+            value => GprsTimerUnit::Reserved(value),
+        }
+    }
+}
+
+impl From<GprsTimerUnit> for u8 {
+    fn from(value: GprsTimerUnit) -> Self {
+        match value {
+            GprsTimerUnit::TwoSeconds => 0b000,
+            GprsTimerUnit::OneMinute => 0b001,
+            GprsTimerUnit::Decihours => 0b010,
+            GprsTimerUnit::Deactivated => 0b111,
+            GprsTimerUnit::Reserved(value) => value,
+        }
+    }
+}
+
+impl GprsTimerUnit {
+    // From the finest to the coarsest, which is the order `best_unit` tries them in
+    const UNITS: [(GprsTimerUnit, u64); 3] = [
+        (GprsTimerUnit::TwoSeconds, 2),
+        (GprsTimerUnit::OneMinute, 60),
+        (GprsTimerUnit::Decihours, 360),
+    ];
+
+    fn seconds(&self) -> Option<u64> {
+        match self {
+            GprsTimerUnit::TwoSeconds => Some(2),
+            GprsTimerUnit::OneMinute | GprsTimerUnit::Reserved(_) => Some(60),
+            GprsTimerUnit::Decihours => Some(360),
+            GprsTimerUnit::Deactivated => None,
+        }
+    }
+}
+
+/// Unit of a GPRS timer 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GprsTimer3Unit {
+    #[default]
+    TenMinutes,
+    OneHour,
+    TenHours,
+    TwoSeconds,
+    ThirtySeconds,
+    OneMinute,
+    ThreeHundredTwentyHours,
+    Deactivated,
+}
+
+impl From<u8> for GprsTimer3Unit {
+    fn from(value: u8) -> Self {
+        match value & 0b111 {
+            0b000 => GprsTimer3Unit::TenMinutes,
+            0b001 => GprsTimer3Unit::OneHour,
+            0b010 => GprsTimer3Unit::TenHours,
+            0b011 => GprsTimer3Unit::TwoSeconds,
+            0b100 => GprsTimer3Unit::ThirtySeconds,
+            0b101 => GprsTimer3Unit::OneMinute,
+            0b110 => GprsTimer3Unit::ThreeHundredTwentyHours,
+            _ => GprsTimer3Unit::Deactivated,
+        }
+    }
+}
+
+impl From<GprsTimer3Unit> for u8 {
+    fn from(value: GprsTimer3Unit) -> Self {
+        match value {
+            GprsTimer3Unit::TenMinutes => 0b000,
+            GprsTimer3Unit::OneHour => 0b001,
+            GprsTimer3Unit::TenHours => 0b010,
+            GprsTimer3Unit::TwoSeconds => 0b011,
+            GprsTimer3Unit::ThirtySeconds => 0b100,
+            GprsTimer3Unit::OneMinute => 0b101,
+            GprsTimer3Unit::ThreeHundredTwentyHours => 0b110,
+            GprsTimer3Unit::Deactivated => 0b111,
+        }
+    }
+}
+
+impl GprsTimer3Unit {
+    const UNITS: [(GprsTimer3Unit, u64); 7] = [
+        (GprsTimer3Unit::TwoSeconds, 2),
+        (GprsTimer3Unit::ThirtySeconds, 30),
+        (GprsTimer3Unit::OneMinute, 60),
+        (GprsTimer3Unit::TenMinutes, 600),
+        (GprsTimer3Unit::OneHour, 3600),
+        (GprsTimer3Unit::TenHours, 36000),
+        (GprsTimer3Unit::ThreeHundredTwentyHours, 1152000),
+    ];
+
+    fn seconds(&self) -> Option<u64> {
+        if *self == GprsTimer3Unit::Deactivated {
+            return None;
+        }
+        Self::UNITS
+            .iter()
+            .find(|(unit, _)| unit == self)
+            .map(|(_, seconds)| *seconds)
+    }
+}
+
+// The finest unit which represents `duration` exactly within the 5 bit value
+fn best_unit<U: Copy>(duration: Duration, units: &[(U, u64)]) -> Result<(U, u8), TlvError> {
+    if duration.subsec_nanos() != 0 {
+        return Err(TlvError::InvalidValue { field: "timer" });
+    }
+    let seconds = duration.as_secs();
+    units
+        .iter()
+        .find(|(_, unit_seconds)| {
+            seconds.is_multiple_of(*unit_seconds) && seconds / unit_seconds <= MAX_VALUE
+        })
+        .map(|(unit, unit_seconds)| (*unit, (seconds / unit_seconds) as u8))
+        .ok_or(TlvError::InvalidValue { field: "timer" })
+}
+
+/// GPRS timer, a type 3 IE with a single octet value
+#[derive(TlvEncode, TlvDecode, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GprsTimer {
+    #[tlv_config(format = "V", bits = 5)]
+    pub value: u8,
+    #[tlv_config(format = "V", bits = 3)]
+    pub unit: GprsTimerUnit,
+}
+
+/// GPRS timer 2 has the coding of GPRS timer in a type 4 IE.
+pub type GprsTimer2 = GprsTimer;
+
+impl GprsTimer {
+    pub fn deactivated() -> Self {
+        GprsTimer {
+            value: 0,
+            unit: GprsTimerUnit::Deactivated,
+        }
+    }
+
+    /// `None` when the timer is deactivated.
+    pub fn duration(&self) -> Option<Duration> {
+        self.unit
+            .seconds()
+            .map(|seconds| Duration::from_secs(seconds * self.value as u64))
+    }
+}
+
+/// Picks the finest unit, durations which are not a multiple of any unit or too long for
+/// the coarsest one are an error.
+impl TryFrom<Duration> for GprsTimer {
+    type Error = TlvError;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let (unit, value) = best_unit(duration, &GprsTimerUnit::UNITS)?;
+        Ok(GprsTimer { value, unit })
+    }
+}
+
+/// GPRS timer 3, a type 4 IE
+#[derive(TlvEncode, TlvDecode, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GprsTimer3 {
+    #[tlv_config(format = "V", bits = 5)]
+    pub value: u8,
+    #[tlv_config(format = "V", bits = 3)]
+    pub unit: GprsTimer3Unit,
+}
+
+impl GprsTimer3 {
+    pub fn deactivated() -> Self {
+        GprsTimer3 {
+            value: 0,
+            unit: GprsTimer3Unit::Deactivated,
+        }
+    }
+
+    /// `None` when the timer is deactivated.
+    pub fn duration(&self) -> Option<Duration> {
+        self.unit
+            .seconds()
+            .map(|seconds| Duration::from_secs(seconds * self.value as u64))
+    }
+}
+
+impl TryFrom<Duration> for GprsTimer3 {
+    type Error = TlvError;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        let (unit, value) = best_unit(duration, &GprsTimer3Unit::UNITS)?;
+        Ok(GprsTimer3 { value, unit })
+    }
+}
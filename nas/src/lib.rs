@@ -0,0 +1,12 @@
+pub mod eps;
+pub mod header;
+pub mod ie;
+pub mod message;
+pub mod mm;
+pub mod security;
+pub mod sm;
+
+pub use eps::{EmmMessage, EpsNasHeader, EpsNasMessage, EsmMessage};
+pub use header::{MmHeader, NasHeader, SecurityHeaderType, SmHeader};
+pub use message::{MmMessage, NasMessage, SmMessage};
+pub use security::{SecurityContext, SecurityError, SecurityProtected};
@@ -0,0 +1,65 @@
+use crate::header::{MmHeader, NasHeader, SmHeader};
+use crate::mm::MmMessageBody;
+use crate::sm::SmMessageBody;
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+/// A plain 5GS NAS message, dispatched on the extended protocol discriminator and
+/// then on the message type. Encoding writes both from the variants.
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub enum NasMessage {
+    #[tlv_config(tag = 0x7E)]
+    Mm(Box<MmMessage>),
+    #[tlv_config(tag = 0x2E)]
+    Sm(Box<SmMessage>),
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub struct MmMessage {
+    #[tlv_config(format = "V", length = 1)]
+    pub header: MmHeader,
+    #[tlv_config(format = "V")]
+    pub body: MmMessageBody,
+}
+
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub struct SmMessage {
+    #[tlv_config(format = "V", length = 2)]
+    pub header: SmHeader,
+    #[tlv_config(format = "V")]
+    pub body: SmMessageBody,
+}
+
+impl NasMessage {
+    /// A plain 5GMM message without security protection.
+    pub fn mm(body: MmMessageBody) -> Self {
+        NasMessage::Mm(Box::new(MmMessage {
+            header: MmHeader::default(),
+            body,
+        }))
+    }
+
+    pub fn sm(pdu_session_id: u8, procedure_transaction_id: u8, body: SmMessageBody) -> Self {
+        NasMessage::Sm(Box::new(SmMessage {
+            header: SmHeader {
+                pdu_session_id,
+                procedure_transaction_id,
+            },
+            body,
+        }))
+    }
+
+    pub fn header(&self) -> NasHeader {
+        match self {
+            NasMessage::Mm(message) => NasHeader::Mm {
+                security_header_type: message.header.security_header_type,
+                message_type: message.body.tag() as u8,
+            },
+            NasMessage::Sm(message) => NasHeader::Sm {
+                pdu_session_id: message.header.pdu_session_id,
+                procedure_transaction_id: message.header.procedure_transaction_id,
+                message_type: message.body.tag() as u8,
+            },
+        }
+    }
+}
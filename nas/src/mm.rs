@@ -0,0 +1,424 @@
+// 5GS mobility management messages, TS 24.501 section 8.2. The optional IEs are declared
+// in the order of the message tables.
+use crate::ie::{
+    Dnn, GprsTimer2, GprsTimer3, MobileIdentity, Nssai, PayloadContainer, PayloadContainerType,
+    SNssai,
+};
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+/// Message types of TS 24.501 section 9.7
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub enum MmMessageBody {
+    #[tlv_config(tag = 0x41)]
+    RegistrationRequest(RegistrationRequest),
+    #[tlv_config(tag = 0x42)]
+    RegistrationAccept(RegistrationAccept),
+    #[tlv_config(tag = 0x43)]
+    RegistrationComplete(RegistrationComplete),
+    #[tlv_config(tag = 0x44)]
+    RegistrationReject(RegistrationReject),
+    #[tlv_config(tag = 0x45)]
+    DeregistrationRequestUeOriginating(DeregistrationRequestUeOriginating),
+    #[tlv_config(tag = 0x46)]
+    DeregistrationAcceptUeOriginating,
+    #[tlv_config(tag = 0x4C)]
+    ServiceRequest(ServiceRequest),
+    #[tlv_config(tag = 0x4D)]
+    ServiceReject(ServiceReject),
+    #[tlv_config(tag = 0x4E)]
+    ServiceAccept(ServiceAccept),
+    #[tlv_config(tag = 0x56)]
+    AuthenticationRequest(AuthenticationRequest),
+    #[tlv_config(tag = 0x57)]
+    AuthenticationResponse(AuthenticationResponse),
+    #[tlv_config(tag = 0x58)]
+    AuthenticationReject(AuthenticationReject),
+    #[tlv_config(tag = 0x59)]
+    AuthenticationFailure(AuthenticationFailure),
+    #[tlv_config(tag = 0x5B)]
+    IdentityRequest(IdentityRequest),
+    #[tlv_config(tag = 0x5C)]
+    IdentityResponse(IdentityResponse),
+    #[tlv_config(tag = 0x5D)]
+    SecurityModeCommand(SecurityModeCommand),
+    #[tlv_config(tag = 0x5E)]
+    SecurityModeComplete(SecurityModeComplete),
+    #[tlv_config(tag = 0x5F)]
+    SecurityModeReject(SecurityModeReject),
+    #[tlv_config(tag = 0x64)]
+    MmStatus(MmStatus),
+    #[tlv_config(tag = 0x67)]
+    UlNasTransport(UlNasTransport),
+    #[tlv_config(tag = 0x68)]
+    DlNasTransport(DlNasTransport),
+}
+
+/// TS 24.501 section 8.2.6
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct RegistrationRequest {
+    #[tlv_config(ie_type = 1)]
+    pub registration_type: u8,
+    #[tlv_config(ie_type = 1)]
+    pub ngksi: u8,
+    #[tlv_config(ie_type = 6, min_octets = 6)]
+    pub mobile_identity: MobileIdentity,
+    #[tlv_config(ie_type = 1, tag = 0xC)]
+    pub non_current_native_ngksi: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x10, min_octets = 3, max_octets = 15)]
+    pub mm_capability: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x2E, min_octets = 4, max_octets = 10)]
+    pub ue_security_capability: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x2F, min_octets = 4, max_octets = 74)]
+    pub requested_nssai: Option<Nssai>,
+    #[tlv_config(ie_type = 3, tag = 0x52, min_octets = 7)]
+    pub last_visited_registered_tai: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x17, min_octets = 4, max_octets = 15)]
+    pub s1_ue_network_capability: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x40, min_octets = 4, max_octets = 34)]
+    pub uplink_data_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x50, min_octets = 4, max_octets = 34)]
+    pub pdu_session_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xB)]
+    pub mico_indication: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x2B, min_octets = 3, max_octets = 3)]
+    pub ue_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x77, min_octets = 14, max_octets = 14)]
+    pub additional_guti: Option<MobileIdentity>,
+    #[tlv_config(ie_type = 4, tag = 0x25, min_octets = 4, max_octets = 34)]
+    pub allowed_pdu_session_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x18, min_octets = 3, max_octets = 3)]
+    pub ue_usage_setting: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x51, min_octets = 3, max_octets = 3)]
+    pub requested_drx_parameters: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x70, min_octets = 4)]
+    pub eps_nas_message_container: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x74, min_octets = 3, max_octets = 811)]
+    pub ladn_indication: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0x8)]
+    pub payload_container_type: Option<PayloadContainerType>,
+    #[tlv_config(
+        ie_type = 6,
+        tag = 0x7B,
+        min_octets = 4,
+        max_octets = 65538,
+        depends_on = "payload_container_type"
+    )]
+    pub payload_container: Option<PayloadContainer>,
+    #[tlv_config(ie_type = 1, tag = 0x9)]
+    pub network_slicing_indication: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x53, min_octets = 3, max_octets = 3)]
+    pub update_type: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x71, min_octets = 4)]
+    pub nas_message_container: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.7
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct RegistrationAccept {
+    #[tlv_config(ie_type = 4, min_octets = 2, max_octets = 2)]
+    pub registration_result: Vec<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x77, min_octets = 14, max_octets = 14)]
+    pub guti: Option<MobileIdentity>,
+    #[tlv_config(ie_type = 4, tag = 0x4A, min_octets = 5, max_octets = 47)]
+    pub equivalent_plmns: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x54, min_octets = 9, max_octets = 114)]
+    pub tai_list: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x15, min_octets = 4, max_octets = 74)]
+    pub allowed_nssai: Option<Nssai>,
+    #[tlv_config(ie_type = 4, tag = 0x11, min_octets = 4, max_octets = 42)]
+    pub rejected_nssai: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x31, min_octets = 4, max_octets = 146)]
+    pub configured_nssai: Option<Nssai>,
+    #[tlv_config(ie_type = 4, tag = 0x21, min_octets = 3, max_octets = 5)]
+    pub network_feature_support: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x50, min_octets = 4, max_octets = 34)]
+    pub pdu_session_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x26, min_octets = 4, max_octets = 34)]
+    pub pdu_session_reactivation_result: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x72, min_octets = 5, max_octets = 515)]
+    pub pdu_session_reactivation_result_error_cause: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x79, min_octets = 12, max_octets = 1715)]
+    pub ladn_information: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xB)]
+    pub mico_indication: Option<u8>,
+    #[tlv_config(ie_type = 1, tag = 0x9)]
+    pub network_slicing_indication: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x27, min_octets = 6, max_octets = 114)]
+    pub service_area_list: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x5E, min_octets = 3, max_octets = 3)]
+    pub t3512_value: Option<GprsTimer3>,
+    #[tlv_config(ie_type = 4, tag = 0x5D, min_octets = 3, max_octets = 3)]
+    pub non_3gpp_deregistration_timer_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 4, tag = 0x16, min_octets = 3, max_octets = 3)]
+    pub t3502_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 4, tag = 0x34, min_octets = 5, max_octets = 50)]
+    pub emergency_number_list: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7A, min_octets = 7, max_octets = 65538)]
+    pub extended_emergency_number_list: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x73, min_octets = 20)]
+    pub sor_transparent_container: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xA)]
+    pub nssai_inclusion_mode: Option<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x76, min_octets = 3)]
+    pub operator_defined_access_category_definitions: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x51, min_octets = 3, max_octets = 3)]
+    pub negotiated_drx_parameters: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.8
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct RegistrationComplete {
+    #[tlv_config(ie_type = 6, tag = 0x73, min_octets = 20)]
+    pub sor_transparent_container: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.9
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct RegistrationReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub mm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x5F, min_octets = 3, max_octets = 3)]
+    pub t3346_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 4, tag = 0x16, min_octets = 3, max_octets = 3)]
+    pub t3502_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x69, min_octets = 4, max_octets = 42)]
+    pub rejected_nssai: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.12
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct DeregistrationRequestUeOriginating {
+    #[tlv_config(ie_type = 1)]
+    pub deregistration_type: u8,
+    #[tlv_config(ie_type = 1)]
+    pub ngksi: u8,
+    #[tlv_config(ie_type = 6, min_octets = 6)]
+    pub mobile_identity: MobileIdentity,
+}
+
+/// TS 24.501 section 8.2.16
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct ServiceRequest {
+    #[tlv_config(ie_type = 1)]
+    pub ngksi: u8,
+    #[tlv_config(ie_type = 1)]
+    pub service_type: u8,
+    #[tlv_config(ie_type = 6, min_octets = 9, max_octets = 9)]
+    pub s_tmsi: MobileIdentity,
+    #[tlv_config(ie_type = 4, tag = 0x40, min_octets = 4, max_octets = 34)]
+    pub uplink_data_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x50, min_octets = 4, max_octets = 34)]
+    pub pdu_session_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x25, min_octets = 4, max_octets = 34)]
+    pub allowed_pdu_session_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x71, min_octets = 4)]
+    pub nas_message_container: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.18
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct ServiceReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub mm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x50, min_octets = 4, max_octets = 34)]
+    pub pdu_session_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x5F, min_octets = 3, max_octets = 3)]
+    pub t3346_value: Option<GprsTimer2>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.17
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct ServiceAccept {
+    #[tlv_config(ie_type = 4, tag = 0x50, min_octets = 4, max_octets = 34)]
+    pub pdu_session_status: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x26, min_octets = 4, max_octets = 34)]
+    pub pdu_session_reactivation_result: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x72, min_octets = 5, max_octets = 515)]
+    pub pdu_session_reactivation_result_error_cause: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.1
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AuthenticationRequest {
+    #[tlv_config(ie_type = 1)]
+    pub ngksi: u8,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+    #[tlv_config(ie_type = 4, min_octets = 3)]
+    pub abba: Vec<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x21, min_octets = 17)]
+    pub rand: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x20, min_octets = 18, max_octets = 18)]
+    pub autn: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.2
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AuthenticationResponse {
+    #[tlv_config(ie_type = 4, tag = 0x2D, min_octets = 6, max_octets = 18)]
+    pub authentication_response_parameter: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.5
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AuthenticationReject {
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.4
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct AuthenticationFailure {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub mm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x30, min_octets = 16, max_octets = 16)]
+    pub authentication_failure_parameter: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.21
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct IdentityRequest {
+    #[tlv_config(ie_type = 1)]
+    pub identity_type: u8,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+}
+
+/// TS 24.501 section 8.2.22
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct IdentityResponse {
+    #[tlv_config(ie_type = 6, min_octets = 3)]
+    pub mobile_identity: MobileIdentity,
+}
+
+/// TS 24.501 section 8.2.25
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct SecurityModeCommand {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub selected_nas_security_algorithms: u8,
+    #[tlv_config(ie_type = 1)]
+    pub ngksi: u8,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+    #[tlv_config(ie_type = 4, min_octets = 3, max_octets = 9)]
+    pub replayed_ue_security_capabilities: Vec<u8>,
+    #[tlv_config(ie_type = 1, tag = 0xE)]
+    pub imeisv_request: Option<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x57, min_octets = 2)]
+    pub selected_eps_nas_security_algorithms: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x36, min_octets = 3, max_octets = 3)]
+    pub additional_security_information: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x38, min_octets = 4)]
+    pub abba: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x19, min_octets = 4, max_octets = 7)]
+    pub replayed_s1_ue_security_capabilities: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.26
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct SecurityModeComplete {
+    #[tlv_config(ie_type = 6, tag = 0x77, min_octets = 12, max_octets = 12)]
+    pub imeisv: Option<MobileIdentity>,
+    #[tlv_config(ie_type = 6, tag = 0x71, min_octets = 4)]
+    pub nas_message_container: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.2.27
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct SecurityModeReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub mm_cause: u8,
+}
+
+/// TS 24.501 section 8.2.29
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct MmStatus {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub mm_cause: u8,
+}
+
+/// TS 24.501 section 8.2.10
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct UlNasTransport {
+    #[tlv_config(ie_type = 1)]
+    pub payload_container_type: PayloadContainerType,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+    #[tlv_config(
+        ie_type = 6,
+        min_octets = 3,
+        max_octets = 65537,
+        depends_on = "payload_container_type"
+    )]
+    pub payload_container: PayloadContainer,
+    #[tlv_config(ie_type = 3, tag = 0x12, min_octets = 2)]
+    pub pdu_session_id: Option<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x59, min_octets = 2)]
+    pub old_pdu_session_id: Option<u8>,
+    #[tlv_config(ie_type = 1, tag = 0x8)]
+    pub request_type: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x22, min_octets = 3, max_octets = 10)]
+    pub s_nssai: Option<SNssai>,
+    #[tlv_config(ie_type = 4, tag = 0x25, min_octets = 3, max_octets = 102)]
+    pub dnn: Option<Dnn>,
+    #[tlv_config(ie_type = 4, tag = 0x24, min_octets = 3)]
+    pub additional_information: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xA)]
+    pub ma_pdu_session_information: Option<u8>,
+    #[tlv_config(ie_type = 1, tag = 0xF)]
+    pub release_assistance_indication: Option<u8>,
+}
+
+/// TS 24.501 section 8.2.11
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct DlNasTransport {
+    #[tlv_config(ie_type = 1)]
+    pub payload_container_type: PayloadContainerType,
+    #[tlv_config(ie_type = 1, spare)]
+    pub spare: u8,
+    #[tlv_config(
+        ie_type = 6,
+        min_octets = 3,
+        max_octets = 65537,
+        depends_on = "payload_container_type"
+    )]
+    pub payload_container: PayloadContainer,
+    #[tlv_config(ie_type = 3, tag = 0x12, min_octets = 2)]
+    pub pdu_session_id: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x24, min_octets = 3)]
+    pub additional_information: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x58, min_octets = 2)]
+    pub mm_cause: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x37, min_octets = 3, max_octets = 3)]
+    pub back_off_timer_value: Option<GprsTimer3>,
+}
@@ -0,0 +1,279 @@
+// Security protected 5GS NAS messages, TS 24.501 section 9.1.1 and 4.4.3, and the NAS
+// integrity and ciphering algorithms of TS 33.501 annex D.
+use crate::header::{EPD_5GS_MOBILITY_MANAGEMENT, SecurityHeaderType};
+use aes::Aes128;
+use aes::cipher::{KeyIvInit, StreamCipher};
+use cmac::{Cmac, Mac};
+use std::marker::PhantomData;
+use thiserror::Error;
+use tlv::prelude::*;
+
+/// Errors of protecting and unprotecting NAS messages. A failed integrity check carries
+/// no MAC values, so that callers can't hand them out to a peer.
+#[derive(Error, Debug)]
+pub enum SecurityError {
+    #[error(transparent)]
+    Tlv(#[from] TlvError),
+    #[error("MAC verification failed")]
+    MacMismatch,
+    #[error("A plain NAS message can not be security protected")]
+    PlainNasMessage,
+}
+
+/// Direction input of the NAS algorithms, TS 33.501 section D.2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Direction {
+    #[default]
+    Uplink,
+    Downlink,
+}
+
+impl From<Direction> for u8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Uplink => 0,
+            Direction::Downlink => 1,
+        }
+    }
+}
+
+/// The COUNT, BEARER and DIRECTION inputs shared by the integrity and ciphering algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AlgorithmInput {
+    pub count: u32,
+    pub bearer: u8,
+    pub direction: Direction,
+}
+
+impl AlgorithmInput {
+    // COUNT || BEARER || DIRECTION || 0^26, the first 64 bits of both the NIA2 CMAC
+    // message and the NEA2 initial counter block.
+    fn prefix(&self) -> [u8; 8] {
+        let mut prefix = [0u8; 8];
+        prefix[..4].copy_from_slice(&self.count.to_be_bytes());
+        prefix[4] = (self.bearer & 0x1F) << 3 | u8::from(self.direction) << 2;
+        prefix
+    }
+}
+
+/// A 128-bit NAS integrity algorithm, producing the 32-bit MAC of a message.
+pub trait IntegrityAlgorithm {
+    fn mac(&self, key: &[u8; 16], input: &AlgorithmInput, message: &[u8]) -> [u8; 4];
+}
+
+/// A 128-bit NAS ciphering algorithm, ciphering and deciphering in place.
+pub trait CipheringAlgorithm {
+    fn apply(&self, key: &[u8; 16], input: &AlgorithmInput, data: &mut [u8]);
+}
+
+/// Null integrity protection, the MAC is all zeros.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nia0;
+
+impl IntegrityAlgorithm for Nia0 {
+    fn mac(&self, _key: &[u8; 16], _input: &AlgorithmInput, _message: &[u8]) -> [u8; 4] {
+        [0; 4]
+    }
+}
+
+/// 128-NIA2, AES-CMAC truncated to 32 bits, TS 33.501 section D.3.1.3
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nia2;
+
+impl IntegrityAlgorithm for Nia2 {
+    fn mac(&self, key: &[u8; 16], input: &AlgorithmInput, message: &[u8]) -> [u8; 4] {
+        let mut cmac = <Cmac<Aes128> as Mac>::new(key.into());
+        cmac.update(&input.prefix());
+        cmac.update(message);
+        let tag = cmac.finalize().into_bytes();
+        [tag[0], tag[1], tag[2], tag[3]]
+    }
+}
+
+/// Null ciphering, the data is left untouched.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nea0;
+
+impl CipheringAlgorithm for Nea0 {
+    fn apply(&self, _key: &[u8; 16], _input: &AlgorithmInput, _data: &mut [u8]) {}
+}
+
+/// 128-NEA2, AES in counter mode, TS 33.501 section D.2.1.3
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nea2;
+
+impl CipheringAlgorithm for Nea2 {
+    fn apply(&self, key: &[u8; 16], input: &AlgorithmInput, data: &mut [u8]) {
+        let mut counter = [0u8; 16];
+        counter[..8].copy_from_slice(&input.prefix());
+        let mut cipher = ctr::Ctr128BE::<Aes128>::new(key.into(), &counter.into());
+        cipher.apply_keystream(data);
+    }
+}
+
+/// The negotiated algorithms and keys of a 5G NAS security context. `count` is the NAS
+/// COUNT of the next message in `direction`, its low octet goes on the wire as the
+/// sequence number.
+pub struct SecurityContext {
+    pub integrity: Box<dyn IntegrityAlgorithm>,
+    pub ciphering: Box<dyn CipheringAlgorithm>,
+    pub k_nas_int: [u8; 16],
+    pub k_nas_enc: [u8; 16],
+    pub count: u32,
+    pub bearer: u8,
+    pub direction: Direction,
+}
+
+impl SecurityContext {
+    fn input(&self, sequence_number: u8) -> AlgorithmInput {
+        AlgorithmInput {
+            count: (self.count & !0xFF) | sequence_number as u32,
+            bearer: self.bearer,
+            direction: self.direction,
+        }
+    }
+}
+
+fn is_ciphered(security_header_type: SecurityHeaderType) -> bool {
+    matches!(
+        security_header_type,
+        SecurityHeaderType::IntegrityProtectedAndCiphered
+            | SecurityHeaderType::IntegrityProtectedAndCipheredWithNewSecurityContext
+    )
+}
+
+/// A security protected 5GS NAS message, TS 24.501 section 9.1.1. `payload` holds the
+/// encoded plain NAS message as it is on the wire, ciphered or not depending on the
+/// security header type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityProtected<T: TlvEncode + TlvDecode> {
+    pub security_header_type: SecurityHeaderType,
+    pub mac: [u8; 4],
+    pub sequence_number: u8,
+    pub payload: Bytes,
+    _message: PhantomData<T>,
+}
+
+impl<T: TlvEncode + TlvDecode> SecurityProtected<T> {
+    /// Encodes `message`, ciphers it if the security header type asks for it and
+    /// computes the MAC over the sequence number and the (ciphered) message.
+    pub fn protect(
+        message: &T,
+        security_header_type: SecurityHeaderType,
+        context: &SecurityContext,
+    ) -> Result<Self, SecurityError> {
+        if security_header_type == SecurityHeaderType::PlainNasMessage {
+            return Err(SecurityError::PlainNasMessage);
+        }
+        let mut bytes = BytesMut::new();
+        message.encode(&mut bytes)?;
+        let sequence_number = context.count as u8;
+        let input = context.input(sequence_number);
+        if is_ciphered(security_header_type) {
+            context.ciphering.apply(&context.k_nas_enc, &input, &mut bytes);
+        }
+        let payload = bytes.freeze();
+        let mac = Self::compute_mac(context, &input, sequence_number, &payload);
+        Ok(SecurityProtected {
+            security_header_type,
+            mac,
+            sequence_number,
+            payload,
+            _message: PhantomData,
+        })
+    }
+
+    /// Verifies the MAC, deciphers if needed and decodes the plain NAS message.
+    pub fn unprotect(&self, context: &SecurityContext) -> Result<T, SecurityError> {
+        self.unprotect_with_options(context, &DecodeOptions::default())
+    }
+
+    pub fn unprotect_with_options(
+        &self,
+        context: &SecurityContext,
+        options: &DecodeOptions,
+    ) -> Result<T, SecurityError> {
+        let input = context.input(self.sequence_number);
+        let expected = Self::compute_mac(context, &input, self.sequence_number, &self.payload);
+        if expected != self.mac {
+            return Err(SecurityError::MacMismatch);
+        }
+        let mut plain = BytesMut::from(&self.payload[..]);
+        if is_ciphered(self.security_header_type) {
+            context.ciphering.apply(&context.k_nas_enc, &input, &mut plain);
+        }
+        let mut plain = plain.freeze();
+        Ok(T::decode_with_options(plain.len(), &mut plain, options)?)
+    }
+
+    fn compute_mac(
+        context: &SecurityContext,
+        input: &AlgorithmInput,
+        sequence_number: u8,
+        payload: &[u8],
+    ) -> [u8; 4] {
+        let mut message = Vec::with_capacity(payload.len() + 1);
+        message.push(sequence_number);
+        message.extend_from_slice(payload);
+        context.integrity.mac(&context.k_nas_int, input, &message)
+    }
+}
+
+impl<T: TlvEncode + TlvDecode> TlvEncode for SecurityProtected<T> {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        bytes.put_u8(EPD_5GS_MOBILITY_MANAGEMENT);
+        bytes.put_u8(u8::from(self.security_header_type) & 0x0F);
+        bytes.put_slice(&self.mac);
+        bytes.put_u8(self.sequence_number);
+        bytes.put_slice(&self.payload);
+        Ok(7 + self.payload.len())
+    }
+}
+
+impl<T: TlvEncode + TlvDecode> TlvDecode for SecurityProtected<T> {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        Self::decode_with_options(length, bytes, &DecodeOptions::default())
+    }
+
+    fn decode_with_options(
+        length: usize,
+        bytes: &mut Bytes,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        if length < 7 || bytes.remaining() < length {
+            return Err(TlvError::InvalidLength {
+                field: "SecurityProtected",
+                length,
+            });
+        }
+        let epd = bytes.get_u8();
+        if epd != EPD_5GS_MOBILITY_MANAGEMENT {
+            return Err(TlvError::TagMismatch {
+                expected: EPD_5GS_MOBILITY_MANAGEMENT as usize,
+                found: epd as usize,
+                field: "SecurityProtected",
+            });
+        }
+        let octet = bytes.get_u8();
+        if options.is_strict() && octet >> 4 != 0 {
+            return Err(TlvError::NonZeroSpare {
+                field: "SecurityProtected",
+            });
+        }
+        let security_header_type = SecurityHeaderType::from(octet & 0x0F);
+        if security_header_type == SecurityHeaderType::PlainNasMessage {
+            return Err(TlvError::MalformedPayload);
+        }
+        let mut mac = [0u8; 4];
+        bytes.copy_to_slice(&mut mac);
+        let sequence_number = bytes.get_u8();
+        let payload = bytes.split_to(length - 7);
+        Ok(SecurityProtected {
+            security_header_type,
+            mac,
+            sequence_number,
+            payload,
+            _message: PhantomData,
+        })
+    }
+}
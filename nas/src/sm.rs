@@ -0,0 +1,137 @@
+// 5GS session management messages, TS 24.501 section 8.3. The optional IEs are declared
+// in the order of the message tables.
+use crate::ie::{Dnn, GprsTimer, GprsTimer3, SNssai};
+use tlv::prelude::*;
+use tlv::tlv_derive::*;
+
+/// Message types of TS 24.501 section 9.7
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq)]
+pub enum SmMessageBody {
+    #[tlv_config(tag = 0xC1)]
+    PduSessionEstablishmentRequest(PduSessionEstablishmentRequest),
+    #[tlv_config(tag = 0xC2)]
+    PduSessionEstablishmentAccept(PduSessionEstablishmentAccept),
+    #[tlv_config(tag = 0xC3)]
+    PduSessionEstablishmentReject(PduSessionEstablishmentReject),
+    #[tlv_config(tag = 0xD1)]
+    PduSessionReleaseRequest(PduSessionReleaseRequest),
+    #[tlv_config(tag = 0xD3)]
+    PduSessionReleaseCommand(PduSessionReleaseCommand),
+    #[tlv_config(tag = 0xD4)]
+    PduSessionReleaseComplete(PduSessionReleaseComplete),
+    #[tlv_config(tag = 0xD6)]
+    SmStatus(SmStatus),
+}
+
+/// TS 24.501 section 8.3.1
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PduSessionEstablishmentRequest {
+    #[tlv_config(ie_type = 3, min_octets = 2)]
+    pub integrity_protection_maximum_data_rate: Vec<u8>,
+    #[tlv_config(ie_type = 1, tag = 0x9)]
+    pub pdu_session_type: Option<u8>,
+    #[tlv_config(ie_type = 1, tag = 0xA)]
+    pub ssc_mode: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x28, min_octets = 3, max_octets = 15)]
+    pub sm_capability: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x55, min_octets = 3)]
+    pub maximum_number_of_supported_packet_filters: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 1, tag = 0xB)]
+    pub always_on_pdu_session_requested: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x39, min_octets = 3, max_octets = 255)]
+    pub sm_pdu_dn_request_container: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.3.2
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PduSessionEstablishmentAccept {
+    #[tlv_config(ie_type = 1)]
+    pub selected_pdu_session_type: u8,
+    #[tlv_config(ie_type = 1)]
+    pub selected_ssc_mode: u8,
+    #[tlv_config(ie_type = 6, min_octets = 6, max_octets = 65537)]
+    pub authorized_qos_rules: Vec<u8>,
+    #[tlv_config(ie_type = 4, min_octets = 7, max_octets = 7)]
+    pub session_ambr: Vec<u8>,
+    #[tlv_config(ie_type = 3, tag = 0x59, min_octets = 2)]
+    pub sm_cause: Option<u8>,
+    #[tlv_config(ie_type = 4, tag = 0x29, min_octets = 7, max_octets = 29)]
+    pub pdu_address: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 3, tag = 0x56, min_octets = 2)]
+    pub rq_timer_value: Option<GprsTimer>,
+    #[tlv_config(ie_type = 4, tag = 0x22, min_octets = 3, max_octets = 10)]
+    pub s_nssai: Option<SNssai>,
+    #[tlv_config(ie_type = 1, tag = 0x8)]
+    pub always_on_pdu_session_indication: Option<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x75, min_octets = 7, max_octets = 65538)]
+    pub mapped_eps_bearer_contexts: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x79, min_octets = 6, max_octets = 65538)]
+    pub authorized_qos_flow_descriptions: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 4, tag = 0x25, min_octets = 3, max_octets = 102)]
+    pub dnn: Option<Dnn>,
+}
+
+/// TS 24.501 section 8.3.3
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PduSessionEstablishmentReject {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub sm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x37, min_octets = 3, max_octets = 3)]
+    pub back_off_timer_value: Option<GprsTimer3>,
+    #[tlv_config(ie_type = 1, tag = 0xF)]
+    pub allowed_ssc_mode: Option<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.3.12
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PduSessionReleaseRequest {
+    #[tlv_config(ie_type = 3, tag = 0x59, min_octets = 2)]
+    pub sm_cause: Option<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.3.14
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PduSessionReleaseCommand {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub sm_cause: u8,
+    #[tlv_config(ie_type = 4, tag = 0x37, min_octets = 3, max_octets = 3)]
+    pub back_off_timer_value: Option<GprsTimer3>,
+    #[tlv_config(ie_type = 6, tag = 0x78, min_octets = 7, max_octets = 1503)]
+    pub eap_message: Option<Vec<u8>>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.3.15
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+#[tlv_config(unknown_ie = "ts24007")]
+pub struct PduSessionReleaseComplete {
+    #[tlv_config(ie_type = 3, tag = 0x59, min_octets = 2)]
+    pub sm_cause: Option<u8>,
+    #[tlv_config(ie_type = 6, tag = 0x7B, min_octets = 4, max_octets = 65538)]
+    pub extended_protocol_configuration_options: Option<Vec<u8>>,
+}
+
+/// TS 24.501 section 8.3.18
+#[derive(TlvEncode, TlvDecode, Debug, Clone, PartialEq, Default)]
+pub struct SmStatus {
+    #[tlv_config(ie_type = 3, min_octets = 1)]
+    pub sm_cause: u8,
+}
@@ -0,0 +1,84 @@
+use nas::ie::{Bcd, PlmnId};
+use tlv::prelude::*;
+
+fn encode<T: TlvEncode>(value: &T) -> Vec<u8> {
+    let mut bytes = BytesMut::new();
+    let len = value.encode(&mut bytes).unwrap();
+    assert_eq!(len, bytes.len());
+    bytes.to_vec()
+}
+
+fn decode<T: TlvDecode>(wire: &'static [u8]) -> Result<T, TlvError> {
+    T::decode(wire.len(), &mut Bytes::from_static(wire))
+}
+
+#[test]
+fn test_bcd_even() {
+    let msin: Bcd = "0999999999".parse().unwrap();
+    assert_eq!(encode(&msin), vec![0x90, 0x99, 0x99, 0x99, 0x99]);
+    assert_eq!(decode::<Bcd>(&[0x90, 0x99, 0x99, 0x99, 0x99]).unwrap(), msin);
+}
+
+#[test]
+fn test_bcd_odd() {
+    // IMSI 234150999999999, the filler completes the last octet
+    let imsi: Bcd = "234150999999999".parse().unwrap();
+    let wire = &[0x32, 0x14, 0x05, 0x99, 0x99, 0x99, 0x99, 0xf9];
+    assert_eq!(encode(&imsi), wire);
+    assert_eq!(decode::<Bcd>(wire).unwrap(), imsi);
+    assert_eq!(imsi.to_string(), "234150999999999");
+}
+
+#[test]
+fn test_bcd_padded() {
+    let routing_indicator: Bcd = "0".parse().unwrap();
+    let mut bytes = BytesMut::new();
+    routing_indicator.encode_padded(2, &mut bytes).unwrap();
+    assert_eq!(&bytes[..], &[0xf0, 0xff]);
+    assert_eq!(decode::<Bcd>(&[0xf0, 0xff]).unwrap(), routing_indicator);
+    assert!(routing_indicator.encode_padded(0, &mut bytes).is_err());
+}
+
+#[test]
+fn test_bcd_rejects_non_digits() {
+    assert!(matches!(
+        "12a4".parse::<Bcd>(),
+        Err(TlvError::InvalidDigit { value: 'a' })
+    ));
+    assert!(matches!(
+        decode::<Bcd>(&[0x21, 0xc3]),
+        Err(TlvError::InvalidDigit { value: 'c' })
+    ));
+    // A filler in the middle
+    assert!(matches!(
+        decode::<Bcd>(&[0xf1, 0x32]),
+        Err(TlvError::InvalidDigit { value: 'f' })
+    ));
+}
+
+#[test]
+fn test_plmn_id_two_digit_mnc() {
+    let plmn_id: PlmnId = "23415".parse().unwrap();
+    assert_eq!((plmn_id.mcc(), plmn_id.mnc()), ("234", "15"));
+    assert_eq!(encode(&plmn_id), vec![0x32, 0xf4, 0x51]);
+    assert_eq!(decode::<PlmnId>(&[0x32, 0xf4, 0x51]).unwrap(), plmn_id);
+    assert_eq!(plmn_id.to_string(), "23415");
+}
+
+#[test]
+fn test_plmn_id_three_digit_mnc() {
+    let plmn_id = PlmnId::new("310", "410").unwrap();
+    assert_eq!(encode(&plmn_id), vec![0x13, 0x00, 0x14]);
+    assert_eq!(decode::<PlmnId>(&[0x13, 0x00, 0x14]).unwrap(), plmn_id);
+    assert_eq!(PlmnId::try_from("310410").unwrap(), plmn_id);
+}
+
+#[test]
+fn test_plmn_id_invalid() {
+    assert!(PlmnId::new("23", "15").is_err());
+    assert!(PlmnId::new("234", "1").is_err());
+    assert!("2341".parse::<PlmnId>().is_err());
+    assert!("23x15".parse::<PlmnId>().is_err());
+    assert!(decode::<PlmnId>(&[0x3a, 0xf4, 0x51]).is_err());
+    assert!(decode::<PlmnId>(&[0x32, 0xf4]).is_err());
+}
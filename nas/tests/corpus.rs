@@ -0,0 +1,326 @@
+// Decodes the messages of tests/corpus, checks their field values and encodes them again.
+use nas::NasMessage;
+use nas::ie::{GprsTimer2, GprsTimer3, PayloadContainer, PayloadContainerType};
+use nas::ie::{Guti5g, Imeisv, MobileIdentity, Nssai, SNssai, STmsi5g, Sd, Suci, SuciImsi};
+use nas::mm::*;
+use nas::sm::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tlv::prelude::*;
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+fn parse_hex(text: &str) -> Vec<u8> {
+    let digits: String = text
+        .lines()
+        .map(|line| line.split('#').next().unwrap())
+        .flat_map(|line| line.split_whitespace())
+        .collect();
+    assert!(digits.len().is_multiple_of(2), "odd number of hex digits");
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn load(name: &str) -> Vec<u8> {
+    let path = corpus_dir().join(name);
+    parse_hex(&std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{path:?}: {e}")))
+}
+
+fn check(name: &str, expected: NasMessage) {
+    let wire = load(name);
+    let decoded = NasMessage::decode(wire.len(), &mut Bytes::from(wire.clone()))
+        .unwrap_or_else(|e| panic!("{name}: {e:?}"));
+    assert_eq!(decoded, expected, "{name}");
+
+    let mut bytes = BytesMut::new();
+    assert_eq!(expected.encode(&mut bytes).unwrap(), wire.len(), "{name}");
+    assert_eq!(&bytes[..], &wire[..], "{name}");
+}
+
+fn plmn() -> nas::ie::PlmnId {
+    "20893".parse().unwrap()
+}
+
+fn suci() -> MobileIdentity {
+    MobileIdentity::Suci(Suci::Imsi(SuciImsi {
+        plmn_id: plmn(),
+        routing_indicator: "0".parse().unwrap(),
+        protection_scheme_id: 0,
+        home_network_public_key_id: 0,
+        scheme_output: vec![0x00, 0x00, 0x00, 0x00, 0x10],
+    }))
+}
+
+fn guti() -> MobileIdentity {
+    MobileIdentity::Guti(Guti5g {
+        plmn_id: plmn(),
+        amf_region_id: 0xca,
+        amf_set_id: 1,
+        amf_pointer: 1,
+        tmsi: 1,
+    })
+}
+
+fn sst_1() -> SNssai {
+    SNssai {
+        sst: 1,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_corpus_round_trip() {
+    let mut count = 0;
+    for entry in std::fs::read_dir(corpus_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "hex") {
+            continue;
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("# Source: "), "{path:?} does not name its source");
+        let wire = parse_hex(&text);
+        let message = NasMessage::decode(wire.len(), &mut Bytes::from(wire.clone()))
+            .unwrap_or_else(|e| panic!("{path:?}: {e:?}"));
+        let mut bytes = BytesMut::new();
+        message.encode(&mut bytes).unwrap();
+        assert_eq!(&bytes[..], &wire[..], "{path:?}");
+        count += 1;
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn test_corpus_truncated() {
+    // A message cut anywhere is an error in both modes rather than a panic. Only a cut
+    // right before optional IEs leaves a shorter message, which has to encode to the same
+    // octets.
+    for entry in std::fs::read_dir(corpus_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "hex") {
+            continue;
+        }
+        let wire = parse_hex(&std::fs::read_to_string(&path).unwrap());
+        for end in 0..wire.len() {
+            let prefix = &wire[..end];
+            for options in [DecodeOptions::strict(), DecodeOptions::lenient()] {
+                let decoded =
+                    NasMessage::decode_with_options(end, &mut Bytes::copy_from_slice(prefix), &options);
+                if let Ok(message) = decoded {
+                    let mut bytes = BytesMut::new();
+                    message.encode(&mut bytes).unwrap();
+                    assert_eq!(&bytes[..], prefix, "{path:?} cut at {end}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_registration_request() {
+    check(
+        "registration_request.hex",
+        NasMessage::mm(MmMessageBody::RegistrationRequest(RegistrationRequest {
+            registration_type: 0x9,
+            ngksi: 0x7,
+            mobile_identity: suci(),
+            mm_capability: Some(vec![0x00]),
+            ue_security_capability: Some(vec![0xf0, 0x70, 0xf0, 0x70]),
+            requested_nssai: Some(Nssai(vec![SNssai {
+                sst: 1,
+                sd: Some(Sd([0x00, 0x00, 0x01])),
+                ..Default::default()
+            }])),
+            ..Default::default()
+        })),
+    );
+}
+
+#[test]
+fn test_authentication() {
+    check(
+        "authentication_request.hex",
+        NasMessage::mm(MmMessageBody::AuthenticationRequest(
+            AuthenticationRequest {
+                ngksi: 0,
+                abba: vec![0x00, 0x00],
+                rand: Some(vec![
+                    0x23, 0x55, 0x3c, 0xbe, 0x96, 0x37, 0xa8, 0x9d, 0x21, 0x8a, 0xe6, 0x4d, 0xae,
+                    0x47, 0xbf, 0x35,
+                ]),
+                autn: Some(vec![
+                    0x55, 0xf3, 0x28, 0xb4, 0x35, 0x77, 0xb9, 0xb9, 0x4a, 0x9f, 0xfa, 0xc3, 0x54,
+                    0xdf, 0xaf, 0xb3,
+                ]),
+                ..Default::default()
+            },
+        )),
+    );
+    check(
+        "authentication_response.hex",
+        NasMessage::mm(MmMessageBody::AuthenticationResponse(
+            AuthenticationResponse {
+                authentication_response_parameter: Some(vec![
+                    0x5c, 0xc9, 0x52, 0x7f, 0x4d, 0x21, 0xc4, 0x3b, 0xee, 0x83, 0xa1, 0x54, 0x43,
+                    0xac, 0xf1, 0xc4,
+                ]),
+                ..Default::default()
+            },
+        )),
+    );
+}
+
+#[test]
+fn test_security_mode() {
+    check(
+        "security_mode_command.hex",
+        NasMessage::mm(MmMessageBody::SecurityModeCommand(SecurityModeCommand {
+            selected_nas_security_algorithms: 0x02,
+            replayed_ue_security_capabilities: vec![0xf0, 0x70, 0xf0, 0x70],
+            imeisv_request: Some(1),
+            additional_security_information: Some(vec![0x00]),
+            ..Default::default()
+        })),
+    );
+    check(
+        "security_mode_complete.hex",
+        NasMessage::mm(MmMessageBody::SecurityModeComplete(SecurityModeComplete {
+            imeisv: Some(MobileIdentity::Imeisv(Imeisv(
+                "4370816125816151".parse().unwrap(),
+            ))),
+            ..Default::default()
+        })),
+    );
+}
+
+#[test]
+fn test_registration_accept() {
+    check(
+        "registration_accept.hex",
+        NasMessage::mm(MmMessageBody::RegistrationAccept(RegistrationAccept {
+            registration_result: vec![0x01],
+            guti: Some(guti()),
+            tai_list: Some(vec![0x00, 0x02, 0xf8, 0x39, 0x00, 0x00, 0x01]),
+            allowed_nssai: Some(Nssai(vec![sst_1()])),
+            t3512_value: Some(GprsTimer3::try_from(Duration::from_secs(3600)).unwrap()),
+            ..Default::default()
+        })),
+    );
+    check(
+        "registration_complete.hex",
+        NasMessage::mm(MmMessageBody::RegistrationComplete(
+            RegistrationComplete::default(),
+        )),
+    );
+}
+
+#[test]
+fn test_registration_reject() {
+    check(
+        "registration_reject.hex",
+        NasMessage::mm(MmMessageBody::RegistrationReject(RegistrationReject {
+            mm_cause: 22,
+            t3346_value: Some(GprsTimer2::try_from(Duration::from_secs(12 * 60)).unwrap()),
+            t3502_value: Some(GprsTimer2::deactivated()),
+            ..Default::default()
+        })),
+    );
+}
+
+#[test]
+fn test_identity() {
+    check(
+        "identity_request.hex",
+        NasMessage::mm(MmMessageBody::IdentityRequest(IdentityRequest {
+            identity_type: 1,
+            ..Default::default()
+        })),
+    );
+    check(
+        "identity_response.hex",
+        NasMessage::mm(MmMessageBody::IdentityResponse(IdentityResponse {
+            mobile_identity: suci(),
+        })),
+    );
+}
+
+#[test]
+fn test_service_request() {
+    check(
+        "service_request.hex",
+        NasMessage::mm(MmMessageBody::ServiceRequest(ServiceRequest {
+            ngksi: 0,
+            service_type: 1,
+            s_tmsi: MobileIdentity::STmsi(STmsi5g {
+                amf_set_id: 1,
+                amf_pointer: 1,
+                tmsi: 1,
+            }),
+            uplink_data_status: Some(vec![0x20, 0x00]),
+            ..Default::default()
+        })),
+    );
+}
+
+#[test]
+fn test_deregistration_request() {
+    check(
+        "deregistration_request.hex",
+        NasMessage::mm(MmMessageBody::DeregistrationRequestUeOriginating(
+            DeregistrationRequestUeOriginating {
+                deregistration_type: 1,
+                ngksi: 0,
+                mobile_identity: guti(),
+            },
+        )),
+    );
+}
+
+#[test]
+fn test_pdu_session_establishment() {
+    let request = NasMessage::sm(
+        5,
+        1,
+        SmMessageBody::PduSessionEstablishmentRequest(PduSessionEstablishmentRequest {
+            integrity_protection_maximum_data_rate: vec![0xff, 0xff],
+            pdu_session_type: Some(1),
+            ssc_mode: Some(1),
+            ..Default::default()
+        }),
+    );
+    check("pdu_session_establishment_request.hex", request.clone());
+
+    check(
+        "ul_nas_transport.hex",
+        NasMessage::mm(MmMessageBody::UlNasTransport(UlNasTransport {
+            payload_container_type: PayloadContainerType::N1SmInformation,
+            payload_container: PayloadContainer::N1SmInformation(Box::new(request)),
+            pdu_session_id: Some(5),
+            request_type: Some(1),
+            s_nssai: Some(sst_1()),
+            dnn: Some("internet".parse().unwrap()),
+            ..Default::default()
+        })),
+    );
+
+    check(
+        "pdu_session_establishment_accept.hex",
+        NasMessage::sm(
+            5,
+            1,
+            SmMessageBody::PduSessionEstablishmentAccept(PduSessionEstablishmentAccept {
+                selected_pdu_session_type: 1,
+                selected_ssc_mode: 1,
+                authorized_qos_rules: vec![0x01, 0x00, 0x06, 0x31, 0x3f, 0x01, 0x01, 0xff, 0x09],
+                session_ambr: vec![0x06, 0x03, 0xe8, 0x06, 0x03, 0xe8],
+                pdu_address: Some(vec![0x01, 0x0a, 0x2d, 0x00, 0x02]),
+                s_nssai: Some(sst_1()),
+                dnn: Some("internet".parse().unwrap()),
+                ..Default::default()
+            }),
+        ),
+    );
+}
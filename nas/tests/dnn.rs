@@ -0,0 +1,109 @@
+use nas::NasMessage;
+use nas::ie::{Dnn, PayloadContainer, PayloadContainerType};
+use nas::mm::{MmMessageBody, UlNasTransport};
+use tlv::prelude::*;
+
+fn encode<T: TlvEncode>(value: &T) -> Result<Vec<u8>, TlvError> {
+    let mut bytes = BytesMut::new();
+    value.encode(&mut bytes)?;
+    Ok(bytes.to_vec())
+}
+
+fn decode<T: TlvDecode>(wire: &[u8]) -> Result<T, TlvError> {
+    T::decode(wire.len(), &mut Bytes::copy_from_slice(wire))
+}
+
+#[test]
+fn test_dnn_labels() {
+    let dnn: Dnn = "ims.mnc001.mcc001.gprs".parse().unwrap();
+    let wire = b"\x03ims\x06mnc001\x06mcc001\x04gprs";
+    assert_eq!(dnn.octets(), wire.len());
+    assert_eq!(encode(&dnn).unwrap(), wire);
+    assert_eq!(decode::<Dnn>(wire).unwrap(), dnn);
+    assert_eq!(dnn.to_string(), "ims.mnc001.mcc001.gprs");
+
+    let dnn = Dnn::try_from("internet").unwrap();
+    assert_eq!(encode(&dnn).unwrap(), b"\x08internet");
+}
+
+#[test]
+fn test_dnn_invalid_names() {
+    for name in ["", ".internet", "internet.", "ims..gprs"] {
+        assert!(
+            matches!(
+                name.parse::<Dnn>(),
+                Err(TlvError::InvalidLength {
+                    field: "dnn_label",
+                    length: 0
+                })
+            ),
+            "{name:?}"
+        );
+    }
+    assert!(matches!(
+        "ims.my_apn".parse::<Dnn>(),
+        Err(TlvError::InvalidValue { field: "dnn" })
+    ));
+    let label = "a".repeat(63);
+    assert!(label.parse::<Dnn>().is_ok());
+    assert!(matches!(
+        format!("{label}a").parse::<Dnn>(),
+        Err(TlvError::InvalidLength {
+            field: "dnn_label",
+            length: 64
+        })
+    ));
+
+    // 100 octets on the wire is the longest name
+    let name = ["a".repeat(63), "b".repeat(35)].join(".");
+    assert_eq!(name.parse::<Dnn>().unwrap().octets(), 100);
+    assert!(matches!(
+        format!("{name}b").parse::<Dnn>(),
+        Err(TlvError::InvalidLength {
+            field: "dnn",
+            length: 101
+        })
+    ));
+    assert!(matches!(
+        encode(&Dnn::default()),
+        Err(TlvError::InvalidLength { .. })
+    ));
+}
+
+#[test]
+fn test_dnn_invalid_wire() {
+    // Label overrunning the value, empty label, non ASCII label
+    for wire in [
+        &b"\x09internet"[..],
+        &b"\x03ims\x00"[..],
+        &b"\x02\xc3\xa9"[..],
+        &b""[..],
+    ] {
+        assert!(decode::<Dnn>(wire).is_err(), "{wire:02x?}");
+    }
+    // A dot would split the label in two on encode, other characters are not in the
+    // letter, digit, hyphen set either
+    for wire in [&b"\x03a.b"[..], &b"\x03a_b"[..], &b"\x03a b"[..]] {
+        assert!(
+            matches!(decode::<Dnn>(wire), Err(TlvError::InvalidValue { field: "dnn" })),
+            "{wire:02x?}"
+        );
+    }
+    assert_eq!(decode::<Dnn>(b"\x05my-5g").unwrap().as_str(), "my-5g");
+}
+
+#[test]
+fn test_ul_nas_transport_dnn() {
+    // One octet of SMS, PDU session 5, initial request to "internet"
+    let wire: &[u8] = b"\x7e\x00\x67\x02\x00\x01\xc1\x12\x05\x81\x25\x09\x08internet";
+    let expected = NasMessage::mm(MmMessageBody::UlNasTransport(UlNasTransport {
+        payload_container_type: PayloadContainerType::Sms,
+        payload_container: PayloadContainer::Sms(vec![0xc1]),
+        pdu_session_id: Some(5),
+        request_type: Some(1),
+        dnn: Some("internet".parse().unwrap()),
+        ..Default::default()
+    }));
+    assert_eq!(decode::<NasMessage>(wire).unwrap(), expected);
+    assert_eq!(encode(&expected).unwrap(), wire);
+}
@@ -0,0 +1,234 @@
+use nas::NasMessage;
+use nas::ie::eap::{EAP_AKA_SUBTYPE_CHALLENGE, EAP_AKA_SUBTYPE_IDENTITY, EAP_CODE_REQUEST};
+use nas::ie::{EapAkaAttributes, EapAkaPrime};
+use nas::mm::{AuthenticationRequest, MmMessageBody};
+use tlv::prelude::*;
+
+const NETWORK_NAME: &[u8] = b"5G:mnc01.mcc001.3gppnetwork.org";
+
+fn encode<T: TlvEncode>(value: &T) -> Vec<u8> {
+    let mut bytes = BytesMut::new();
+    let len = value.encode(&mut bytes).unwrap();
+    assert_eq!(len, bytes.len());
+    bytes.to_vec()
+}
+
+fn decode(wire: &[u8]) -> Result<EapAkaPrime, TlvError> {
+    EapAkaPrime::decode(wire.len(), &mut Bytes::copy_from_slice(wire))
+}
+
+// An AKA'-Challenge with the attributes in the order of `EapAkaAttributes`
+fn challenge_wire() -> Vec<u8> {
+    [
+        &[0x01, 0x2a, 0x00, 0x6c, 0x32, 0x01, 0x00, 0x00][..],
+        &[0x01, 0x05, 0x00, 0x00],
+        &[0x11; 16],
+        &[0x02, 0x05, 0x00, 0x00],
+        &[0x22; 16],
+        &[0x0b, 0x05, 0x00, 0x00],
+        &[0x33; 16],
+        &[0x17, 0x09, 0x00, 0x1f],
+        NETWORK_NAME,
+        &[0x00],
+        &[0x18, 0x01, 0x00, 0x01],
+    ]
+    .concat()
+}
+
+fn challenge(at_kdf_input: Vec<u8>) -> EapAkaPrime {
+    EapAkaPrime {
+        code: EAP_CODE_REQUEST,
+        identifier: 0x2a,
+        subtype: EAP_AKA_SUBTYPE_CHALLENGE,
+        attributes: EapAkaAttributes {
+            at_rand: Some([&[0x00, 0x00][..], &[0x11; 16]].concat()),
+            at_autn: Some([&[0x00, 0x00][..], &[0x22; 16]].concat()),
+            at_mac: Some([&[0x00, 0x00][..], &[0x33; 16]].concat()),
+            at_kdf_input: Some(at_kdf_input),
+            at_kdf: vec![vec![0x00, 0x01]],
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn test_challenge() {
+    let wire = challenge_wire();
+    // The padding of AT_KDF_INPUT is part of the value
+    let padded = challenge([&[0x00, 0x1f][..], NETWORK_NAME, &[0x00]].concat());
+    assert_eq!(decode(&wire).unwrap(), padded);
+    assert_eq!(encode(&padded), wire);
+    // and added when missing
+    let unpadded = challenge([&[0x00, 0x1f][..], NETWORK_NAME].concat());
+    assert_eq!(encode(&unpadded), wire);
+
+    // Attributes in any order, here AT_MAC last
+    let reordered = [&wire[..48], &wire[68..], &wire[48..68]].concat();
+    assert_eq!(decode(&reordered).unwrap(), padded);
+}
+
+#[test]
+fn test_eap_message_ie() {
+    let eap_message = challenge_wire();
+    let message = NasMessage::mm(MmMessageBody::AuthenticationRequest(
+        AuthenticationRequest {
+            abba: vec![0x00, 0x00],
+            eap_message: Some(eap_message),
+            ..Default::default()
+        },
+    ));
+    let wire = encode(&message);
+    let NasMessage::Mm(decoded) = NasMessage::decode(wire.len(), &mut Bytes::from(wire)).unwrap()
+    else {
+        panic!("not a 5GMM message");
+    };
+    let MmMessageBody::AuthenticationRequest(request) = decoded.body else {
+        panic!("not an authentication request");
+    };
+    let eap_message = request.eap_message.unwrap();
+    assert_eq!(
+        decode(&eap_message).unwrap().attributes.at_kdf,
+        vec![vec![0x00, 0x01]]
+    );
+}
+
+#[test]
+fn test_invalid() {
+    // A length of 0 does not even cover the type and length octets
+    let mut wire = challenge_wire();
+    wire.truncate(104);
+    wire.extend([0x18, 0x00]);
+    wire[3] = 0x6a;
+    assert!(matches!(
+        decode(&wire),
+        Err(TlvError::InvalidLength {
+            field: "at_kdf",
+            length: 0
+        })
+    ));
+
+    // AT_COUNTER (19) is not skippable, RFC 4187 section 8.1, in either mode
+    let wire = [
+        &[0x01, 0x2a, 0x00, 0x0c, 0x32, 0x01, 0x00, 0x00][..],
+        &[0x13, 0x01, 0x00, 0x01],
+    ]
+    .concat();
+    assert!(matches!(
+        decode(&wire),
+        Err(TlvError::UnknownTag { tag: 0x13 })
+    ));
+    assert!(matches!(
+        EapAkaPrime::decode_with_options(
+            wire.len(),
+            &mut Bytes::from(wire),
+            &DecodeOptions::lenient(),
+        ),
+        Err(TlvError::UnknownTag { tag: 0x13 })
+    ));
+
+    // EAP-AKA instead of EAP-AKA'
+    let mut wire = challenge_wire();
+    wire[4] = 23;
+    assert!(matches!(
+        decode(&wire),
+        Err(TlvError::TagMismatch {
+            expected: 50,
+            found: 23,
+            field: "eap_message"
+        })
+    ));
+}
+
+#[test]
+fn test_skippable_attribute() {
+    // AT_NEXT_REAUTH_ID (133) is not modelled but skippable, in either mode
+    let mut wire = [
+        &challenge_wire()[..],
+        &[0x85, 0x03, 0x00, 0x05],
+        b"reau\0\0\0\0",
+    ]
+    .concat();
+    wire[3] = 0x78;
+    let expected = challenge([&[0x00, 0x1f][..], NETWORK_NAME, &[0x00]].concat());
+    assert_eq!(decode(&wire).unwrap(), expected);
+    let decoded = EapAkaPrime::decode_with_options(
+        wire.len(),
+        &mut Bytes::from(wire),
+        &DecodeOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(decoded, expected);
+}
+
+#[test]
+fn test_identity_request() {
+    // AKA-Identity requests for the permanent, any and the full authentication identity,
+    // RFC 4187 section 9.2
+    for tag in [0x0a, 0x0d, 0x11] {
+        let wire = [0x01, 0x01, 0x00, 0x0c, 0x32, 0x05, 0x00, 0x00, tag, 0x01, 0x00, 0x00];
+        let id_req = Some(vec![0x00, 0x00]);
+        let mut attributes = EapAkaAttributes::default();
+        match tag {
+            0x0a => attributes.at_permanent_id_req = id_req,
+            0x0d => attributes.at_any_id_req = id_req,
+            _ => attributes.at_fullauth_id_req = id_req,
+        }
+        let expected = EapAkaPrime {
+            code: EAP_CODE_REQUEST,
+            identifier: 0x01,
+            subtype: EAP_AKA_SUBTYPE_IDENTITY,
+            attributes,
+        };
+        assert_eq!(decode(&wire).unwrap(), expected);
+        assert_eq!(encode(&expected), wire);
+    }
+}
+
+#[test]
+fn test_several_kdfs() {
+    // The server offers a KDF of a higher value first and then KDF 1, RFC 9048 section 3.2
+    let mut wire = challenge_wire();
+    wire.extend([0x18, 0x01, 0x00, 0x01]);
+    wire[107] = 0x02;
+    wire[3] = 0x70;
+    let mut expected = challenge([&[0x00, 0x1f][..], NETWORK_NAME, &[0x00]].concat());
+    expected.attributes.at_kdf = vec![vec![0x00, 0x02], vec![0x00, 0x01]];
+    assert_eq!(decode(&wire).unwrap(), expected);
+    assert_eq!(encode(&expected), wire);
+
+    // Each AT_KDF stays where it is among the other attributes
+    let split = [&wire[..48], &wire[104..108], &wire[48..104], &wire[108..]].concat();
+    assert_eq!(decode(&split).unwrap(), expected);
+}
+
+#[test]
+fn test_encrypted_data() {
+    // A challenge with AT_IV and AT_ENCR_DATA, RFC 4187 section 10.12, holding e.g. an
+    // AT_NEXT_PSEUDONYM
+    let wire = [
+        &[0x01, 0x2a, 0x00, 0x68, 0x32, 0x01, 0x00, 0x00][..],
+        &[0x01, 0x05, 0x00, 0x00],
+        &[0x11; 16],
+        &[0x02, 0x05, 0x00, 0x00],
+        &[0x22; 16],
+        &[0x81, 0x05, 0x00, 0x00],
+        &[0x44; 16],
+        &[0x82, 0x09, 0x00, 0x00],
+        &[0x55; 32],
+    ]
+    .concat();
+    let expected = EapAkaPrime {
+        code: EAP_CODE_REQUEST,
+        identifier: 0x2a,
+        subtype: EAP_AKA_SUBTYPE_CHALLENGE,
+        attributes: EapAkaAttributes {
+            at_rand: Some([&[0x00, 0x00][..], &[0x11; 16]].concat()),
+            at_autn: Some([&[0x00, 0x00][..], &[0x22; 16]].concat()),
+            at_iv: Some([&[0x00, 0x00][..], &[0x44; 16]].concat()),
+            at_encr_data: Some([&[0x00, 0x00][..], &[0x55; 32]].concat()),
+            ..Default::default()
+        },
+    };
+    assert_eq!(decode(&wire).unwrap(), expected);
+    assert_eq!(encode(&expected), wire);
+}
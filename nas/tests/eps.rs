@@ -0,0 +1,239 @@
+use nas::SecurityHeaderType;
+use nas::eps::emm::*;
+use nas::eps::esm::*;
+use nas::eps::{EpsNasHeader, EpsNasMessage};
+use nas::ie::GprsTimer;
+use std::time::Duration;
+use tlv::prelude::*;
+
+fn encode(message: &EpsNasMessage) -> Result<Vec<u8>, TlvError> {
+    let mut bytes = BytesMut::new();
+    let len = message.encode(&mut bytes)?;
+    assert_eq!(len, bytes.len());
+    Ok(bytes.to_vec())
+}
+
+fn decode(wire: &[u8]) -> Result<EpsNasMessage, TlvError> {
+    EpsNasMessage::decode(wire.len(), &mut Bytes::copy_from_slice(wire))
+}
+
+fn round_trip(wire: &[u8], expected: EpsNasMessage) {
+    assert_eq!(decode(wire).unwrap(), expected);
+    assert_eq!(encode(&expected).unwrap(), wire);
+}
+
+fn pdn_connectivity_request() -> EsmMessageBody {
+    EsmMessageBody::PdnConnectivityRequest(PdnConnectivityRequest {
+        request_type: 1,
+        pdn_type: 1,
+        esm_information_transfer_flag: Some(1),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_attach_request() {
+    // IMSI 001010000000001, the ESM container carries a PDN connectivity request. TMSI
+    // status, device properties and MS network feature support have 4 bit IEIs.
+    let wire: &[u8] = &[
+        0x07, 0x41, 0x71, 0x08, 0x09, 0x10, 0x10, 0x00, 0x00, 0x00, 0x00, 0x10, 0x02, 0xe0, 0xe0,
+        0x00, 0x05, 0x02, 0x01, 0xd0, 0x11, 0xd1, 0x52, 0x00, 0xf1, 0x10, 0x00, 0x01, 0x5c, 0x0a,
+        0x00, 0x31, 0x03, 0xe5, 0xe0, 0x3e, 0x90, 0x11, 0x03, 0x57, 0x58, 0xa6, 0x5d, 0x01, 0x03,
+        0xd1, 0xc1,
+    ];
+    let esm_message_container =
+        encode(&EpsNasMessage::esm(0, 1, pdn_connectivity_request())).unwrap();
+    let expected = EpsNasMessage::emm(EmmMessageBody::AttachRequest(AttachRequest {
+        eps_attach_type: 1,
+        nas_key_set_identifier: 7,
+        eps_mobile_identity: vec![0x09, 0x10, 0x10, 0x00, 0x00, 0x00, 0x00, 0x10],
+        ue_network_capability: vec![0xe0, 0xe0],
+        esm_message_container,
+        last_visited_registered_tai: Some(vec![0x00, 0xf1, 0x10, 0x00, 0x01]),
+        drx_parameter: Some(vec![0x0a, 0x00]),
+        ms_network_capability: Some(vec![0xe5, 0xe0, 0x3e]),
+        tmsi_status: Some(0),
+        mobile_station_classmark_2: Some(vec![0x57, 0x58, 0xa6]),
+        voice_domain_preference: Some(vec![0x03]),
+        device_properties: Some(1),
+        ms_network_feature_support: Some(1),
+        ..Default::default()
+    }));
+    round_trip(wire, expected.clone());
+    assert_eq!(
+        expected.header(),
+        EpsNasHeader::Emm {
+            security_header_type: SecurityHeaderType::PlainNasMessage,
+            message_type: 0x41,
+        }
+    );
+    assert_eq!(expected.header().protocol_discriminator(), 0x7);
+}
+
+#[test]
+fn test_attach_accept() {
+    let activate_default_bearer: &[u8] = &[
+        0x52, 0x01, 0xc1, 0x01, 0x09, 0x09, 0x08, 0x69, 0x6e, 0x74, 0x65, 0x72, 0x6e, 0x65, 0x74,
+        0x05, 0x01, 0x0a, 0x2d, 0x00, 0x02, 0x5e, 0x02, 0xfe, 0xfe,
+    ];
+    let expected_esm = EpsNasMessage::esm(
+        5,
+        1,
+        EsmMessageBody::ActivateDefaultEpsBearerContextRequest(
+            ActivateDefaultEpsBearerContextRequest {
+                eps_qos: vec![0x09],
+                access_point_name: "internet".parse().unwrap(),
+                pdn_address: vec![0x01, 0x0a, 0x2d, 0x00, 0x02],
+                apn_ambr: Some(vec![0xfe, 0xfe]),
+                ..Default::default()
+            },
+        ),
+    );
+    round_trip(activate_default_bearer, expected_esm);
+
+    let mut wire = vec![
+        0x07, 0x42, 0x01, 0x28, 0x06, 0x00, 0x00, 0xf1, 0x10, 0x00, 0x01, 0x00, 0x19,
+    ];
+    wire.extend_from_slice(activate_default_bearer);
+    wire.extend_from_slice(&[
+        0x50, 0x0b, 0xf6, 0x00, 0xf1, 0x10, 0x80, 0x01, 0x01, 0xc0, 0x00, 0x00, 0x01, 0x17, 0x2c,
+        0x64, 0x01, 0x01,
+    ]);
+    let expected = EpsNasMessage::emm(EmmMessageBody::AttachAccept(AttachAccept {
+        eps_attach_result: 1,
+        t3412_value: GprsTimer::try_from(Duration::from_secs(8 * 60)).unwrap(),
+        tai_list: vec![0x00, 0x00, 0xf1, 0x10, 0x00, 0x01],
+        esm_message_container: activate_default_bearer.to_vec(),
+        guti: Some(vec![
+            0xf6, 0x00, 0xf1, 0x10, 0x80, 0x01, 0x01, 0xc0, 0x00, 0x00, 0x01,
+        ]),
+        t3402_value: Some(GprsTimer::try_from(Duration::from_secs(12 * 60)).unwrap()),
+        eps_network_feature_support: Some(vec![0x01]),
+        ..Default::default()
+    }));
+    round_trip(&wire, expected);
+}
+
+#[test]
+fn test_esm_optional_ies() {
+    // A 4 bit IEI after an 8 bit one, and the APN in label format
+    let wire: &[u8] = &[
+        0x02, 0x01, 0xd0, 0x11, 0xd1, 0x28, 0x09, 0x08, 0x69, 0x6e, 0x74, 0x65, 0x72, 0x6e, 0x65,
+        0x74, 0xc1,
+    ];
+    let expected = EpsNasMessage::esm(
+        0,
+        1,
+        EsmMessageBody::PdnConnectivityRequest(PdnConnectivityRequest {
+            request_type: 1,
+            pdn_type: 1,
+            esm_information_transfer_flag: Some(1),
+            access_point_name: Some("internet".parse().unwrap()),
+            device_properties: Some(1),
+            ..Default::default()
+        }),
+    );
+    round_trip(wire, expected.clone());
+    assert_eq!(
+        expected.header(),
+        EpsNasHeader::Esm {
+            eps_bearer_identity: 0,
+            procedure_transaction_id: 1,
+            message_type: 0xd0,
+        }
+    );
+    round_trip(
+        &[0x02, 0x02, 0xd9],
+        EpsNasMessage::esm(0, 2, EsmMessageBody::EsmInformationRequest),
+    );
+}
+
+#[test]
+fn test_emm_messages() {
+    round_trip(
+        &[0x07, 0x55, 0x01],
+        EpsNasMessage::emm(EmmMessageBody::IdentityRequest(IdentityRequest {
+            identity_type: 1,
+            ..Default::default()
+        })),
+    );
+    round_trip(
+        &[0x07, 0x54],
+        EpsNasMessage::emm(EmmMessageBody::AuthenticationReject),
+    );
+    round_trip(
+        &[0x07, 0x44, 0x0f, 0x16, 0x01, 0x2c, 0xa1],
+        EpsNasMessage::emm(EmmMessageBody::AttachReject(AttachReject {
+            emm_cause: 15,
+            t3402_value: Some(GprsTimer::try_from(Duration::from_secs(12 * 60)).unwrap()),
+            extended_emm_cause: Some(1),
+            ..Default::default()
+        })),
+    );
+
+    let mut wire = vec![0x07, 0x52, 0x01];
+    wire.extend_from_slice(&[0x11; 16]);
+    wire.push(0x10);
+    wire.extend_from_slice(&[0x22; 16]);
+    round_trip(
+        &wire,
+        EpsNasMessage::emm(EmmMessageBody::AuthenticationRequest(
+            AuthenticationRequest {
+                nas_key_set_identifier: 1,
+                rand: vec![0x11; 16],
+                autn: vec![0x22; 16],
+                ..Default::default()
+            },
+        )),
+    );
+}
+
+#[test]
+fn test_protocol_discriminator() {
+    // A 5GS NAS message has no EPS protocol discriminator
+    assert!(matches!(
+        decode(&[0x7e, 0x00, 0x5b, 0x01]),
+        Err(TlvError::UnknownTag { .. })
+    ));
+    // Unknown message type
+    assert!(matches!(
+        decode(&[0x07, 0x99]),
+        Err(TlvError::UnknownTag { tag: 0x99 })
+    ));
+
+    let invalid = EpsNasMessage::esm(16, 1, EsmMessageBody::EsmInformationRequest);
+    assert!(matches!(
+        encode(&invalid),
+        Err(TlvError::InvalidValue {
+            field: "eps_bearer_identity"
+        })
+    ));
+}
+
+#[test]
+fn test_security_protected_as_plain() {
+    // Integrity protected Attach complete, and a Service request with its KSI, sequence
+    // number and short MAC
+    for wire in [
+        &[0x17, 0x5a, 0x3b, 0x1c, 0x2d, 0x01, 0x07, 0x43][..],
+        &[0xc7, 0x01, 0x6a, 0x9f],
+    ] {
+        assert!(matches!(
+            decode(wire),
+            Err(TlvError::InvalidValue {
+                field: "security_header_type"
+            })
+        ));
+    }
+
+    let mut message = EpsNasMessage::emm(EmmMessageBody::AttachComplete(Default::default()));
+    if let EpsNasMessage::Emm(message) = &mut message {
+        message.header.security_header_type = SecurityHeaderType::IntegrityProtected;
+    }
+    assert!(matches!(
+        encode(&message),
+        Err(TlvError::InvalidValue {
+            field: "security_header_type"
+        })
+    ));
+}
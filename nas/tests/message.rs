@@ -0,0 +1,138 @@
+use nas::header::{EPD_5GS_MOBILITY_MANAGEMENT, EPD_5GS_SESSION_MANAGEMENT};
+use nas::ie::{MobileIdentity, Suci, SuciImsi};
+use nas::mm::{IdentityRequest, MmMessageBody, RegistrationRequest};
+use nas::sm::{PduSessionEstablishmentRequest, SmMessageBody};
+use nas::{NasHeader, NasMessage, SecurityHeaderType};
+use tlv::prelude::*;
+
+fn encode(message: &NasMessage) -> Vec<u8> {
+    let mut bytes = BytesMut::with_capacity(64);
+    let len = message.encode(&mut bytes).unwrap();
+    assert_eq!(len, bytes.len());
+    bytes.to_vec()
+}
+
+fn decode(wire: &'static [u8]) -> Result<NasMessage, TlvError> {
+    NasMessage::decode(wire.len(), &mut Bytes::from_static(wire))
+}
+
+#[test]
+fn test_registration_request() {
+    // Initial registration with a SUCI (MCC 208, MNC 93, null scheme) and UE security capability
+    let wire: &[u8] = &[
+        0x7e, 0x00, 0x41, 0x79, 0x00, 0x0d, 0x01, 0x02, 0xf8, 0x39, 0xf0, 0xff, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x10, 0x2e, 0x04, 0xf0, 0xf0, 0xf0, 0xf0,
+    ];
+    let expected = NasMessage::mm(MmMessageBody::RegistrationRequest(RegistrationRequest {
+        registration_type: 0x9,
+        ngksi: 0x7,
+        mobile_identity: MobileIdentity::Suci(Suci::Imsi(SuciImsi {
+            plmn_id: "20893".parse().unwrap(),
+            routing_indicator: "0".parse().unwrap(),
+            protection_scheme_id: 0,
+            home_network_public_key_id: 0,
+            scheme_output: vec![0x00, 0x00, 0x00, 0x00, 0x10],
+        })),
+        ue_security_capability: Some(vec![0xf0, 0xf0, 0xf0, 0xf0]),
+        ..Default::default()
+    }));
+
+    let decoded = decode(wire).unwrap();
+    assert_eq!(decoded, expected);
+    assert_eq!(
+        decoded.header(),
+        NasHeader::Mm {
+            security_header_type: SecurityHeaderType::PlainNasMessage,
+            message_type: 0x41,
+        }
+    );
+    assert_eq!(decoded.header().extended_protocol_discriminator(), EPD_5GS_MOBILITY_MANAGEMENT);
+    assert_eq!(encode(&expected), wire);
+}
+
+#[test]
+fn test_identity_request() {
+    let wire: &[u8] = &[0x7e, 0x00, 0x5b, 0x01];
+    let expected = NasMessage::mm(MmMessageBody::IdentityRequest(IdentityRequest {
+        identity_type: 1,
+        spare: 0,
+    }));
+
+    assert_eq!(decode(wire).unwrap(), expected);
+    assert_eq!(encode(&expected), wire);
+}
+
+#[test]
+fn test_unit_message() {
+    let wire: &[u8] = &[0x7e, 0x00, 0x46];
+    let expected = NasMessage::mm(MmMessageBody::DeregistrationAcceptUeOriginating);
+
+    assert_eq!(decode(wire).unwrap(), expected);
+    assert_eq!(encode(&expected), wire);
+}
+
+#[test]
+fn test_pdu_session_establishment_request() {
+    // PDU session ID 1, PTI 1, full rate integrity protection, IPv4 and SSC mode 1
+    let wire: &[u8] = &[0x2e, 0x01, 0x01, 0xc1, 0xff, 0xff, 0x91, 0xa1];
+    let expected = NasMessage::sm(
+        1,
+        1,
+        SmMessageBody::PduSessionEstablishmentRequest(PduSessionEstablishmentRequest {
+            integrity_protection_maximum_data_rate: vec![0xff, 0xff],
+            pdu_session_type: Some(1),
+            ssc_mode: Some(1),
+            ..Default::default()
+        }),
+    );
+
+    let decoded = decode(wire).unwrap();
+    assert_eq!(decoded, expected);
+    assert_eq!(
+        decoded.header(),
+        NasHeader::Sm {
+            pdu_session_id: 1,
+            procedure_transaction_id: 1,
+            message_type: 0xc1,
+        }
+    );
+    assert_eq!(decoded.header().extended_protocol_discriminator(), EPD_5GS_SESSION_MANAGEMENT);
+    assert_eq!(encode(&expected), wire);
+}
+
+#[test]
+fn test_unknown_message_type() {
+    assert!(matches!(
+        decode(&[0x7e, 0x00, 0x40]),
+        Err(TlvError::UnknownTag { tag: 0x40 })
+    ));
+    assert!(matches!(
+        decode(&[0x0e, 0x00, 0x41]),
+        Err(TlvError::UnknownTag { tag: 0x0e })
+    ));
+}
+
+#[test]
+fn test_security_protected_as_plain() {
+    // Integrity protected and ciphered, the MAC must not be read as a Security mode reject
+    let wire: &[u8] = &[0x7e, 0x02, 0x5f, 0x16, 0xaa, 0xbb, 0x01];
+    for options in [DecodeOptions::strict(), DecodeOptions::lenient()] {
+        assert!(matches!(
+            NasMessage::decode_with_options(wire.len(), &mut Bytes::from_static(wire), &options),
+            Err(TlvError::InvalidValue {
+                field: "security_header_type"
+            })
+        ));
+    }
+
+    let mut message = NasMessage::mm(MmMessageBody::IdentityRequest(IdentityRequest::default()));
+    if let NasMessage::Mm(message) = &mut message {
+        message.header.security_header_type = SecurityHeaderType::IntegrityProtected;
+    }
+    assert!(matches!(
+        message.encode(&mut BytesMut::new()),
+        Err(TlvError::InvalidValue {
+            field: "security_header_type"
+        })
+    ));
+}
@@ -0,0 +1,151 @@
+// Encodings assembled octet by octet from the figures of TS 24.501 section 9.11.3.4
+use nas::NasMessage;
+use nas::ie::{
+    Eui64, Guti5g, Imei, Imeisv, MacAddress, MobileIdentity, STmsi5g, Suci, SuciImsi,
+};
+use nas::mm::{IdentityResponse, MmMessageBody};
+use tlv::prelude::*;
+
+fn round_trip(wire: &'static [u8], expected: MobileIdentity) {
+    let decoded = MobileIdentity::decode(wire.len(), &mut Bytes::from_static(wire)).unwrap();
+    assert_eq!(decoded, expected);
+    let mut bytes = BytesMut::new();
+    assert_eq!(expected.encode(&mut bytes).unwrap(), wire.len());
+    assert_eq!(&bytes[..], wire);
+}
+
+fn decode(wire: &'static [u8]) -> Result<MobileIdentity, TlvError> {
+    MobileIdentity::decode(wire.len(), &mut Bytes::from_static(wire))
+}
+
+#[test]
+fn test_suci_imsi() {
+    // MCC 234, MNC 15, routing indicator 678, null scheme with MSIN 0999999999
+    round_trip(
+        &[0x01, 0x32, 0xf4, 0x51, 0x76, 0xf8, 0x00, 0x00, 0x90, 0x99, 0x99, 0x99, 0x99],
+        MobileIdentity::Suci(Suci::Imsi(SuciImsi {
+            plmn_id: "23415".parse().unwrap(),
+            routing_indicator: "678".parse().unwrap(),
+            protection_scheme_id: 0,
+            home_network_public_key_id: 0,
+            scheme_output: vec![0x90, 0x99, 0x99, 0x99, 0x99],
+        })),
+    );
+}
+
+#[test]
+fn test_suci_nai() {
+    round_trip(
+        b"\x11user@example.com",
+        MobileIdentity::Suci(Suci::Nai("user@example.com".to_string())),
+    );
+}
+
+#[test]
+fn test_guti() {
+    round_trip(
+        &[0xf2, 0x32, 0xf4, 0x51, 0xca, 0x00, 0x41, 0x01, 0x02, 0x03, 0x04],
+        MobileIdentity::Guti(Guti5g {
+            plmn_id: "23415".parse().unwrap(),
+            amf_region_id: 0xca,
+            amf_set_id: 1,
+            amf_pointer: 1,
+            tmsi: 0x01020304,
+        }),
+    );
+    assert!(matches!(
+        decode(&[0xf2, 0x32, 0xf4, 0x51, 0xca, 0x00, 0x41, 0x01, 0x02, 0x03]),
+        Err(TlvError::InvalidLength { .. })
+    ));
+}
+
+#[test]
+fn test_s_tmsi() {
+    round_trip(
+        &[0xf4, 0xff, 0xff, 0x01, 0x02, 0x03, 0x04],
+        MobileIdentity::STmsi(STmsi5g {
+            amf_set_id: 0x3ff,
+            amf_pointer: 0x3f,
+            tmsi: 0x01020304,
+        }),
+    );
+    let mut bytes = BytesMut::new();
+    let too_large = MobileIdentity::STmsi(STmsi5g {
+        amf_set_id: 0x400,
+        ..Default::default()
+    });
+    assert!(too_large.encode(&mut bytes).is_err());
+}
+
+#[test]
+fn test_imei() {
+    // 15 digits, odd
+    round_trip(
+        &[0x4b, 0x09, 0x51, 0x24, 0x30, 0x32, 0x57, 0x81],
+        MobileIdentity::Imei(Imei("490154203237518".parse().unwrap())),
+    );
+    // Digits are 0 to 9 only
+    assert!(matches!(
+        decode(&[0x4b, 0x09, 0x51, 0x24, 0x30, 0x32, 0x57, 0x8a]),
+        Err(TlvError::InvalidDigit { value: 'a' })
+    ));
+    // The type octet is there, but the length says there are no digits
+    assert!(matches!(
+        Imei::decode(0, &mut Bytes::from_static(&[0x4b, 0x09])),
+        Err(TlvError::InvalidLength { length: 0, .. })
+    ));
+    assert!(matches!(
+        Imeisv::decode(0, &mut Bytes::from_static(&[0x45])),
+        Err(TlvError::InvalidLength { length: 0, .. })
+    ));
+}
+
+#[test]
+fn test_imeisv() {
+    // 16 digits, even, so the last octet is filled with 0xF
+    round_trip(
+        &[0x45, 0x73, 0x80, 0x61, 0x21, 0x85, 0x61, 0x51, 0xf1],
+        MobileIdentity::Imeisv(Imeisv("4370816125816151".parse().unwrap())),
+    );
+    assert!(matches!(
+        decode(&[0x45, 0x73, 0x80, 0x61, 0x21, 0x85, 0x61, 0x51, 0x01]),
+        Err(TlvError::MalformedPayload)
+    ));
+}
+
+#[test]
+fn test_mac_address_and_eui64() {
+    round_trip(
+        &[0x0e, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55],
+        MobileIdentity::MacAddress(MacAddress {
+            address: [0x00, 0x11, 0x22, 0x33, 0x44, 0x55],
+            usage_restricted: true,
+        }),
+    );
+    round_trip(
+        &[0x07, 0x00, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55],
+        MobileIdentity::Eui64(Eui64([0x00, 0x11, 0x22, 0xff, 0xfe, 0x33, 0x44, 0x55])),
+    );
+}
+
+#[test]
+fn test_no_identity() {
+    round_trip(&[0x00], MobileIdentity::NoIdentity);
+}
+
+#[test]
+fn test_identity_response() {
+    let wire: &[u8] = &[
+        0x7e, 0x00, 0x5c, 0x00, 0x09, 0x45, 0x73, 0x80, 0x61, 0x21, 0x85, 0x61, 0x51, 0xf1,
+    ];
+    let expected = NasMessage::mm(MmMessageBody::IdentityResponse(IdentityResponse {
+        mobile_identity: MobileIdentity::Imeisv(Imeisv("4370816125816151".parse().unwrap())),
+    }));
+    assert_eq!(
+        NasMessage::decode(wire.len(), &mut Bytes::from_static(wire)).unwrap(),
+        expected
+    );
+    let mut bytes = BytesMut::new();
+    expected.encode(&mut bytes).unwrap();
+    assert_eq!(&bytes[..], wire);
+}
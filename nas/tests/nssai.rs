@@ -0,0 +1,121 @@
+use nas::ie::{MobileIdentity, Nssai, SNssai, STmsi5g, Sd};
+use nas::mm::{MmMessageBody, RegistrationRequest};
+use tlv::prelude::*;
+
+fn round_trip<T: TlvEncode + TlvDecode + PartialEq + std::fmt::Debug>(wire: &'static [u8], expected: T) {
+    assert_eq!(T::decode(wire.len(), &mut Bytes::from_static(wire)).unwrap(), expected);
+    let mut bytes = BytesMut::new();
+    assert_eq!(expected.encode(&mut bytes).unwrap(), wire.len());
+    assert_eq!(&bytes[..], wire);
+}
+
+fn s_nssai(wire: &'static [u8]) -> Result<SNssai, TlvError> {
+    SNssai::decode(wire.len(), &mut Bytes::from_static(wire))
+}
+
+#[test]
+fn test_s_nssai_lengths() {
+    round_trip(&[0x01], SNssai { sst: 1, ..Default::default() });
+    round_trip(
+        &[0x01, 0x02],
+        SNssai {
+            sst: 1,
+            mapped_hplmn_sst: Some(2),
+            ..Default::default()
+        },
+    );
+    round_trip(
+        &[0x01, 0x00, 0x00, 0x01],
+        SNssai {
+            sst: 1,
+            sd: Some(Sd([0x00, 0x00, 0x01])),
+            ..Default::default()
+        },
+    );
+    round_trip(
+        &[0x01, 0x00, 0x00, 0x01, 0x02],
+        SNssai {
+            sst: 1,
+            sd: Some(Sd([0x00, 0x00, 0x01])),
+            mapped_hplmn_sst: Some(2),
+            ..Default::default()
+        },
+    );
+    round_trip(
+        &[0x01, 0x00, 0x00, 0x01, 0x02, 0xff, 0xff, 0xff],
+        SNssai {
+            sst: 1,
+            sd: Some(Sd([0x00, 0x00, 0x01])),
+            mapped_hplmn_sst: Some(2),
+            mapped_hplmn_sd: Some(Sd([0xff, 0xff, 0xff])),
+        },
+    );
+}
+
+#[test]
+fn test_s_nssai_invalid_lengths() {
+    for wire in [
+        &[][..],
+        &[0x01, 0x00, 0x00][..],
+        &[0x01, 0x00, 0x00, 0x01, 0x02, 0xff][..],
+        &[0x01, 0x00, 0x00, 0x01, 0x02, 0xff, 0xff][..],
+    ] {
+        let result = SNssai::decode(wire.len(), &mut Bytes::copy_from_slice(wire));
+        assert!(matches!(result, Err(TlvError::InvalidLength { .. })), "{wire:02x?}");
+    }
+    assert!(s_nssai(&[0x01]).is_ok());
+
+    // A mapped HPLMN SD needs the SD and the mapped HPLMN SST
+    let mut bytes = BytesMut::new();
+    let invalid = SNssai {
+        sst: 1,
+        mapped_hplmn_sd: Some(Sd([0, 0, 1])),
+        ..Default::default()
+    };
+    assert!(matches!(
+        invalid.encode(&mut bytes),
+        Err(TlvError::InvalidLength { .. })
+    ));
+}
+
+#[test]
+fn test_sd_conversion() {
+    assert_eq!(u32::from(Sd([0x12, 0x34, 0x56])), 0x123456);
+    assert_eq!(Sd::try_from(0x123456).unwrap(), Sd([0x12, 0x34, 0x56]));
+    assert!(Sd::try_from(0x1000000).is_err());
+}
+
+#[test]
+fn test_nssai() {
+    round_trip(
+        &[0x01, 0x01, 0x04, 0x01, 0x00, 0x00, 0x01],
+        Nssai(vec![
+            SNssai { sst: 1, ..Default::default() },
+            SNssai {
+                sst: 1,
+                sd: Some(Sd([0x00, 0x00, 0x01])),
+                ..Default::default()
+            },
+        ]),
+    );
+    // The second S-NSSAI claims 4 octets, only 3 are left
+    assert!(Nssai::decode(6, &mut Bytes::from_static(&[0x01, 0x01, 0x04, 0x01, 0x00, 0x00])).is_err());
+}
+
+#[test]
+fn test_requested_nssai() {
+    let body = MmMessageBody::RegistrationRequest(RegistrationRequest {
+        registration_type: 0x1,
+        mobile_identity: MobileIdentity::STmsi(STmsi5g {
+            amf_set_id: 1,
+            amf_pointer: 1,
+            tmsi: 0x01020304,
+        }),
+        requested_nssai: Some(Nssai(vec![SNssai { sst: 1, ..Default::default() }])),
+        ..Default::default()
+    });
+    let wire: &[u8] = &[
+        0x41, 0x01, 0x00, 0x07, 0xf4, 0x00, 0x41, 0x01, 0x02, 0x03, 0x04, 0x2f, 0x02, 0x01, 0x01,
+    ];
+    round_trip(wire, body);
+}
@@ -0,0 +1,204 @@
+use nas::NasMessage;
+use nas::ie::{
+    PayloadContainer, PayloadContainerEntry, PayloadContainerEntryIes, PayloadContainerType,
+};
+use nas::mm::{DlNasTransport, IdentityRequest, MmMessageBody, UlNasTransport};
+use nas::sm::{PduSessionEstablishmentRequest, SmMessageBody};
+use tlv::prelude::*;
+
+fn encode(message: &NasMessage) -> Result<Vec<u8>, TlvError> {
+    let mut bytes = BytesMut::new();
+    let len = message.encode(&mut bytes)?;
+    assert_eq!(len, bytes.len());
+    Ok(bytes.to_vec())
+}
+
+fn decode(wire: &[u8]) -> Result<NasMessage, TlvError> {
+    NasMessage::decode(wire.len(), &mut Bytes::copy_from_slice(wire))
+}
+
+fn pdu_session_establishment_request() -> NasMessage {
+    NasMessage::sm(
+        5,
+        1,
+        SmMessageBody::PduSessionEstablishmentRequest(PduSessionEstablishmentRequest {
+            integrity_protection_maximum_data_rate: vec![0xff, 0xff],
+            pdu_session_type: Some(1),
+            ssc_mode: Some(1),
+            ..Default::default()
+        }),
+    )
+}
+
+fn dl_nas_transport(
+    payload_container_type: PayloadContainerType,
+    payload_container: PayloadContainer,
+) -> NasMessage {
+    NasMessage::mm(MmMessageBody::DlNasTransport(DlNasTransport {
+        payload_container_type,
+        payload_container,
+        ..Default::default()
+    }))
+}
+
+#[test]
+fn test_n1_sm_information() {
+    let wire: &[u8] = &[
+        0x7e, 0x00, 0x68, 0x01, 0x00, 0x08, 0x2e, 0x05, 0x01, 0xc1, 0xff, 0xff, 0x91, 0xa1, 0x12,
+        0x05,
+    ];
+    let expected = NasMessage::mm(MmMessageBody::DlNasTransport(DlNasTransport {
+        payload_container_type: PayloadContainerType::N1SmInformation,
+        payload_container: PayloadContainer::N1SmInformation(Box::new(
+            pdu_session_establishment_request(),
+        )),
+        pdu_session_id: Some(5),
+        ..Default::default()
+    }));
+    assert_eq!(decode(wire).unwrap(), expected);
+    assert_eq!(encode(&expected).unwrap(), wire);
+
+    // N1 SM information holding a 5GMM message
+    let wire: &[u8] = &[0x7e, 0x00, 0x68, 0x01, 0x00, 0x04, 0x7e, 0x00, 0x5b, 0x01];
+    assert!(matches!(
+        decode(wire),
+        Err(TlvError::TagMismatch {
+            expected: 0x2e,
+            found: 0x7e,
+            field: "payload_container"
+        })
+    ));
+}
+
+#[test]
+fn test_uninterpreted_types() {
+    // The same octets are kept as they are for the types without a model
+    for (octet, payload_container) in [
+        (0x02, PayloadContainer::Sms(vec![0x01, 0x02])),
+        (0x03, PayloadContainer::LppMessage(vec![0x01, 0x02])),
+        (
+            0x05,
+            PayloadContainer::Other(PayloadContainerType::UePolicyContainer, vec![0x01, 0x02]),
+        ),
+        (
+            0x09,
+            PayloadContainer::Other(PayloadContainerType::Reserved(9), vec![0x01, 0x02]),
+        ),
+    ] {
+        let wire = [0x7e, 0x00, 0x67, octet, 0x00, 0x02, 0x01, 0x02];
+        let expected = NasMessage::mm(MmMessageBody::UlNasTransport(UlNasTransport {
+            payload_container_type: PayloadContainerType::from(octet),
+            payload_container: payload_container.clone(),
+            ..Default::default()
+        }));
+        assert_eq!(decode(&wire).unwrap(), expected);
+        assert_eq!(encode(&expected).unwrap(), wire);
+        assert_eq!(
+            payload_container.payload_container_type(),
+            PayloadContainerType::from(octet)
+        );
+    }
+}
+
+#[test]
+fn test_multiple_payloads() {
+    // An N1 SM information entry for PDU session 5 and an SMS entry without optional IEs
+    let wire: &[u8] = &[
+        0x7e, 0x00, 0x68, 0x0f, 0x00, 0x14, 0x02, 0x00, 0x0c, 0x11, 0x12, 0x01, 0x05, 0x2e, 0x05,
+        0x01, 0xc1, 0xff, 0xff, 0x91, 0xa1, 0x00, 0x03, 0x02, 0xaa, 0xbb,
+    ];
+    let expected = dl_nas_transport(
+        PayloadContainerType::MultiplePayloads,
+        PayloadContainer::MultiplePayloads(vec![
+            PayloadContainerEntry {
+                optional_ies: PayloadContainerEntryIes {
+                    pdu_session_id: Some(5),
+                    ..Default::default()
+                },
+                payload: PayloadContainer::N1SmInformation(Box::new(
+                    pdu_session_establishment_request(),
+                )),
+            },
+            PayloadContainerEntry {
+                optional_ies: PayloadContainerEntryIes::default(),
+                payload: PayloadContainer::Sms(vec![0xaa, 0xbb]),
+            },
+        ]),
+    );
+    assert_eq!(decode(wire).unwrap(), expected);
+    assert_eq!(encode(&expected).unwrap(), wire);
+}
+
+#[test]
+fn test_multiple_payloads_invalid() {
+    // An entry of type multiple payloads
+    let wire: &[u8] = &[
+        0x7e, 0x00, 0x68, 0x0f, 0x00, 0x05, 0x01, 0x00, 0x02, 0x0f, 0x00,
+    ];
+    assert!(matches!(decode(wire), Err(TlvError::MalformedPayload)));
+    // Two optional IEs announced, one present
+    let wire: &[u8] = &[
+        0x7e, 0x00, 0x68, 0x0f, 0x00, 0x07, 0x01, 0x00, 0x04, 0x22, 0x12, 0x01, 0x05,
+    ];
+    assert!(matches!(decode(wire), Err(TlvError::MalformedPayload)));
+    // Entry length beyond the container
+    let wire: &[u8] = &[
+        0x7e, 0x00, 0x68, 0x0f, 0x00, 0x05, 0x01, 0x00, 0x09, 0x02, 0xaa,
+    ];
+    assert!(matches!(
+        decode(wire),
+        Err(TlvError::InvalidLength {
+            field: "payload_container_entry",
+            ..
+        })
+    ));
+
+    let nested = dl_nas_transport(
+        PayloadContainerType::MultiplePayloads,
+        PayloadContainer::MultiplePayloads(vec![PayloadContainerEntry::default()]),
+    );
+    assert!(matches!(
+        encode(&nested),
+        Err(TlvError::InvalidValue {
+            field: "payload_container_entry"
+        })
+    ));
+    let not_sm = dl_nas_transport(
+        PayloadContainerType::N1SmInformation,
+        PayloadContainer::N1SmInformation(Box::new(NasMessage::mm(
+            MmMessageBody::IdentityRequest(IdentityRequest::default()),
+        ))),
+    );
+    assert!(matches!(
+        encode(&not_sm),
+        Err(TlvError::InvalidValue {
+            field: "payload_container"
+        })
+    ));
+}
+
+#[test]
+fn test_payload_container_type_mismatch() {
+    // The type IE has to tell what the container holds, in both transport messages
+    let dl = dl_nas_transport(
+        PayloadContainerType::Sms,
+        PayloadContainer::N1SmInformation(Box::new(pdu_session_establishment_request())),
+    );
+    let ul = NasMessage::mm(MmMessageBody::UlNasTransport(UlNasTransport {
+        payload_container_type: PayloadContainerType::N1SmInformation,
+        payload_container: PayloadContainer::Sms(vec![0x01, 0x02]),
+        ..Default::default()
+    }));
+    let other = dl_nas_transport(
+        PayloadContainerType::UePolicyContainer,
+        PayloadContainer::Other(PayloadContainerType::CiotUserData, vec![0x01, 0x02]),
+    );
+    for message in [dl, ul, other] {
+        assert!(matches!(
+            encode(&message),
+            Err(TlvError::InvalidValue {
+                field: "payload_container"
+            })
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+use nas::NasMessage;
+use nas::ie::{GprsTimer, GprsTimer2, GprsTimer3, GprsTimer3Unit, GprsTimerUnit};
+use nas::mm::{MmMessageBody, RegistrationReject};
+use std::time::Duration;
+use tlv::prelude::*;
+
+fn encode<T: TlvEncode>(value: &T) -> Vec<u8> {
+    let mut bytes = BytesMut::new();
+    value.encode(&mut bytes).unwrap();
+    bytes.to_vec()
+}
+
+fn decode<T: TlvDecode>(wire: &'static [u8]) -> T {
+    T::decode(wire.len(), &mut Bytes::from_static(wire)).unwrap()
+}
+
+#[test]
+fn test_gprs_timer_coding() {
+    let timer = GprsTimer {
+        value: 12,
+        unit: GprsTimerUnit::OneMinute,
+    };
+    assert_eq!(encode(&timer), vec![0x2c]);
+    assert_eq!(decode::<GprsTimer>(&[0x2c]), timer);
+    assert_eq!(timer.duration(), Some(Duration::from_secs(12 * 60)));
+
+    assert_eq!(encode(&GprsTimer::deactivated()), vec![0xe0]);
+    assert_eq!(decode::<GprsTimer>(&[0xe0]).duration(), None);
+
+    // Units 3 to 6 are read as minutes
+    let reserved = decode::<GprsTimer>(&[0x65]);
+    assert_eq!(reserved.unit, GprsTimerUnit::Reserved(3));
+    assert_eq!(reserved.duration(), Some(Duration::from_secs(5 * 60)));
+    assert_eq!(encode(&reserved), vec![0x65]);
+}
+
+#[test]
+fn test_gprs_timer_from_duration() {
+    let timer = GprsTimer::try_from(Duration::from_secs(30)).unwrap();
+    assert_eq!((timer.unit, timer.value), (GprsTimerUnit::TwoSeconds, 15));
+    let timer = GprsTimer::try_from(Duration::from_secs(12 * 60)).unwrap();
+    assert_eq!((timer.unit, timer.value), (GprsTimerUnit::OneMinute, 12));
+    let timer = GprsTimer::try_from(Duration::from_secs(3 * 3600)).unwrap();
+    assert_eq!((timer.unit, timer.value), (GprsTimerUnit::Decihours, 30));
+
+    for duration in [
+        Duration::from_secs(63),
+        Duration::from_millis(2500),
+        Duration::from_secs(32 * 360),
+    ] {
+        assert!(matches!(
+            GprsTimer::try_from(duration),
+            Err(TlvError::InvalidValue { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_gprs_timer_3() {
+    let timer = GprsTimer3::try_from(Duration::from_secs(3600)).unwrap();
+    assert_eq!((timer.unit, timer.value), (GprsTimer3Unit::TenMinutes, 6));
+    assert_eq!(encode(&timer), vec![0x06]);
+
+    let timer = GprsTimer3::try_from(Duration::from_secs(640 * 3600)).unwrap();
+    assert_eq!((timer.unit, timer.value), (GprsTimer3Unit::ThreeHundredTwentyHours, 2));
+    assert_eq!(encode(&timer), vec![0xc2]);
+    assert_eq!(decode::<GprsTimer3>(&[0xc2]).duration(), Some(Duration::from_secs(640 * 3600)));
+
+    assert_eq!(decode::<GprsTimer3>(&[0xe0]), GprsTimer3::deactivated());
+    // 54 minutes is neither a multiple of 10 minutes nor below 32 minutes
+    assert!(GprsTimer3::try_from(Duration::from_secs(54 * 60)).is_err());
+}
+
+#[test]
+fn test_value_out_of_range() {
+    let mut bytes = BytesMut::new();
+    let timer = GprsTimer {
+        value: 32,
+        unit: GprsTimerUnit::OneMinute,
+    };
+    assert!(matches!(
+        timer.encode(&mut bytes),
+        Err(TlvError::InvalidValue { field: "value" })
+    ));
+}
+
+#[test]
+fn test_registration_reject_timers() {
+    // 5GMM cause #22 congestion, T3346 12 minutes, T3502 deactivated
+    let wire: &[u8] = &[0x7e, 0x00, 0x44, 0x16, 0x5f, 0x01, 0x2c, 0x16, 0x01, 0xe0];
+    let expected = NasMessage::mm(MmMessageBody::RegistrationReject(RegistrationReject {
+        mm_cause: 22,
+        t3346_value: Some(GprsTimer2::try_from(Duration::from_secs(12 * 60)).unwrap()),
+        t3502_value: Some(GprsTimer2::deactivated()),
+        ..Default::default()
+    }));
+    assert_eq!(decode::<NasMessage>(wire), expected);
+    assert_eq!(encode(&expected), wire);
+}
@@ -1,8 +1,8 @@
-use attribute_derive::FromAttr;
+use attribute_derive::{Attribute, FromAttr};
 use proc_macro::Span;
-use syn::Ident;
+use syn::{Error, Ident};
 
-#[derive(FromAttr, Debug)]
+#[derive(FromAttr, Debug, Clone)]
 #[attribute(ident = tlv_config)]
 #[attribute(error(
     unknown_field = "expected one of {expected_fields:i(`{}`)(, )}",
@@ -13,6 +13,7 @@ use syn::Ident;
 ))]
 pub struct TlvConfig{
 	pub(crate) tag: Option<usize>,
+    pub(crate) tag_mask: Option<usize>,
     #[attribute(optional, default = 1)]
 	pub(crate) tag_bytes_format: u8,
     #[attribute(optional, default = Some(1))]
@@ -23,7 +24,561 @@ pub struct TlvConfig{
     pub(crate) max_length: Option<usize>,
     #[attribute(optional, default = 1)]
 	pub(crate) value_bytes_format: u8,
+    #[attribute(optional)]
 	pub(crate) format: String,
+    pub(crate) spare: bool,
+    pub(crate) ie_type: Option<u8>,
+    pub(crate) min_octets: Option<usize>,
+    pub(crate) max_octets: Option<usize>,
+    #[attribute(optional)]
+    pub(crate) present_if_length: Vec<usize>,
+    pub(crate) bits: Option<u8>,
+    pub(crate) depends_on: Option<String>,
+    pub(crate) instance: Option<u8>,
+    pub(crate) enterprise_id: Option<u16>,
+    pub(crate) vendor_id: Option<u32>,
+    pub(crate) avp_flags: Option<u8>,
+    pub(crate) tag_bits: Option<u8>,
+    pub(crate) length_bits: Option<u8>,
+    pub(crate) oui: Option<u32>,
+    pub(crate) subtype: Option<u8>,
+    pub(crate) flags: Option<u8>,
+    pub(crate) flags_mask: Option<u8>,
+    pub(crate) extended_length: Option<u8>,
+    pub(crate) length_unit: Option<usize>,
+    pub(crate) length_includes_header: bool,
+    pub(crate) extended_type: Option<u8>,
+    pub(crate) fragmented: bool,
+    pub(crate) end: bool,
+    pub(crate) pad: Option<u8>,
+    pub(crate) class: Option<String>,
+    pub(crate) constructed: bool,
+    pub(crate) repeated: bool,
+}
+
+impl TlvConfig {
+    pub(crate) fn parse(attrs: Vec<syn::Attribute>) -> Result<TlvConfig, Error> {
+        let mut tlv_config = TlvConfig::from_attributes(attrs)?;
+        if let Some(instance) = tlv_config.instance {
+            tlv_config.apply_instance(instance)?;
+        } else if let Some(ie_type) = tlv_config.ie_type {
+            tlv_config.apply_ie_type(ie_type)?;
+        } else if tlv_config.format.is_empty() {
+            return Err(config_error("missing field `format` or `ie_type`"));
+        }
+        if !tlv_config.present_if_length.is_empty()
+            && (tlv_config.format != "V" || tlv_config.length.is_none())
+        {
+            return Err(config_error("`present_if_length` requires a `V` with a fixed `length`"));
+        }
+        if let Some(bits) = tlv_config.bits
+            && (tlv_config.format != "V" || bits == 0 || bits > 32)
+        {
+            return Err(config_error("`bits` requires a `V` of 1 to 32 bits"));
+        }
+        if tlv_config.depends_on.is_some()
+            && (tlv_config.bits.is_some()
+                || tlv_config.format == "T"
+                || tlv_config.value_bytes_format == 0
+                || tlv_config.tag_bytes_format == 0)
+        {
+            return Err(config_error("`depends_on` requires a value of whole octets"));
+        }
+        if tlv_config.format == "BER" {
+            tlv_config.apply_ber()?;
+        } else if tlv_config.class.is_some() || tlv_config.constructed {
+            return Err(config_error("`class` and `constructed` require `format = \"BER\"`"));
+        }
+        if tlv_config.format == "AVP" {
+            tlv_config.apply_avp()?;
+        } else if tlv_config.avp_flags.is_some() {
+            return Err(config_error("`avp_flags` requires `format = \"AVP\"`"));
+        } else if tlv_config.vendor_id.is_some()
+            || tlv_config.extended_type.is_some()
+            || tlv_config.fragmented
+            || (tlv_config.packed_header().is_none() && (tlv_config.oui.is_some() || tlv_config.subtype.is_some()))
+        {
+            tlv_config.check_attribute()?;
+        }
+        if tlv_config.tag_bits.is_some() || tlv_config.length_bits.is_some() {
+            tlv_config.check_packed_header()?;
+        }
+        if tlv_config.extended_length.is_some() {
+            tlv_config.check_extended_length()?;
+        } else if tlv_config.flags.is_some() || tlv_config.flags_mask.is_some() {
+            return Err(config_error("`flags` and `flags_mask` require `extended_length`"));
+        }
+        if tlv_config.end {
+            tlv_config.check_end()?;
+        } else if tlv_config.pad.is_some() {
+            return Err(config_error("`pad` requires `end`"));
+        }
+        // Every occurrence is an element of a `Vec`, e.g. the Bearer Contexts of a GTPv2-C
+        // message
+        if tlv_config.repeated
+            && (!matches!(tlv_config.format.as_str(), "TLV" | "TLV-E" | "AVP" | "BER")
+                || tlv_config.fragmented
+                || tlv_config.is_gathered())
+        {
+            return Err(config_error("`repeated` requires a TLV, TLV-E, AVP or BER which is not `fragmented` or of a vendor"));
+        }
+        if tlv_config.length_unit.is_some() || tlv_config.length_includes_header {
+            tlv_config.check_length_unit()?;
+        }
+        if tlv_config.enterprise_id.is_some()
+            && (tlv_config.tag_bytes_format != 2
+                || tlv_config.tag.is_none_or(|tag| tag & 0x8000 == 0)
+                || !matches!(tlv_config.format.as_str(), "TLV" | "TLV-E"))
+        {
+            return Err(config_error("`enterprise_id` requires a TLV with a 2 octet `tag` which has the enterprise bit 0x8000 set"));
+        }
+        Ok(tlv_config)
+    }
+
+    // Variants of an enum only carry the tag they are dispatched on
+    pub(crate) fn parse_variant(attrs: Vec<syn::Attribute>) -> Result<TlvConfig, Error> {
+        let tlv_config = TlvConfig::from_attributes(attrs)?;
+        if tlv_config.tag.is_none() {
+            return Err(config_error("enum variants require a `tag`"));
+        }
+        if tlv_config.tag_bytes_format == 0 {
+            return Err(config_error("enum variants require a `tag_bytes_format` of at least 1"));
+        }
+        if let (Some(tag), Some(tag_mask)) = (tlv_config.tag, tlv_config.tag_mask)
+            && tag & !tag_mask != 0
+        {
+            return Err(config_error("`tag` has bits outside of `tag_mask`"));
+        }
+        Ok(tlv_config)
+    }
+
+    // Expands the TS 24.007 section 11.2.1.1 IE types into the formats above. The IE is
+    // in the T forms when it has an IEI i.e. `tag`, otherwise in the V forms. Like the
+    // message tables of TS 24.501, `min_octets` and `max_octets` count the IEI and the
+    // length octets as well.
+    fn apply_ie_type(&mut self, ie_type: u8) -> Result<(), Error> {
+        if !self.format.is_empty() {
+            return Err(config_error("`ie_type` and `format` are exclusive"));
+        }
+        let has_tag = self.tag.is_some();
+        match ie_type {
+            1 => {
+                self.check_single_octet("Type 1")?;
+                if has_tag {
+                    self.format = "TV".to_string();
+                    self.tag_bytes_format = 0;
+                } else {
+                    self.format = "V".to_string();
+                    self.value_bytes_format = 0;
+                }
+            }
+            2 => {
+                self.check_single_octet("Type 2")?;
+                if !has_tag {
+                    return Err(config_error("Type 2 IE requires a `tag`"));
+                }
+                self.format = "T".to_string();
+                self.tag_bytes_format = 1;
+            }
+            3 => {
+                let octets = self.min_octets.ok_or_else(|| config_error("Type 3 IE requires `min_octets`"))?;
+                if self.max_octets.is_some_and(|max_octets| max_octets != octets) {
+                    return Err(config_error("Type 3 IE has a fixed length, `min_octets` and `max_octets` should be equal"));
+                }
+                if has_tag {
+                    self.format = "TV".to_string();
+                    self.tag_bytes_format = 1;
+                } else {
+                    self.format = "V".to_string();
+                }
+                self.length = Some(self.value_octets(octets, if has_tag { 1 } else { 0 })?);
+            }
+            4 | 6 => {
+                let length_bytes_format = if ie_type == 4 { 1 } else { 2 };
+                self.format = match (has_tag, ie_type) {
+                    (true, 4) => "TLV",
+                    (false, 4) => "LV",
+                    (true, _) => "TLV-E",
+                    (false, _) => "LV-E",
+                }
+                .to_string();
+                self.tag_bytes_format = 1;
+                self.length = None;
+                self.length_bytes_format = length_bytes_format;
+                let header = if has_tag { 1 } else { 0 } + length_bytes_format as usize;
+                if let Some(min_octets) = self.min_octets {
+                    self.min_length = Some(self.value_octets(min_octets, header)?);
+                }
+                if let Some(max_octets) = self.max_octets {
+                    self.max_length = Some(self.value_octets(max_octets, header)?);
+                }
+            }
+            5 => {
+                return Err(config_error("Type 5 is not defined by TS 24.007, use `ie_type = 6` for TLV-E"));
+            }
+            _ => {
+                return Err(config_error("`ie_type` should be one of 1, 2, 3, 4 or 6"));
+            }
+        }
+        Ok(())
+    }
+
+    // The TS 29.274 section 8.2.1 header: a 1 octet type, a 2 octet length of the value
+    // and an octet with a spare nibble and the instance, which tells apart the IEs of the
+    // same type in a message. `min_length` and `max_length` bound the value as usual.
+    fn apply_instance(&mut self, instance: u8) -> Result<(), Error> {
+        if !self.format.is_empty() || self.ie_type.is_some() {
+            return Err(config_error("`instance` is exclusive with `ie_type` and `format`"));
+        }
+        if self.tag.is_none() {
+            return Err(config_error("`instance` requires a `tag`"));
+        }
+        if instance > 0xF {
+            return Err(config_error("`instance` should be 0 to 15"));
+        }
+        self.format = "TLV".to_string();
+        self.tag_bytes_format = 1;
+        self.length = None;
+        self.length_bytes_format = 2;
+        Ok(())
+    }
+
+    // A header of whole octets holding the tag in its `tag_bits` most significant bits and
+    // the length of the value in the `length_bits` after them, e.g. the 7 bit type and 9
+    // bit length of an LLDP TLV. `oui` and `subtype` follow the header of an LLDP
+    // organizationally specific TLV and are counted by its length.
+    fn check_packed_header(&self) -> Result<(), Error> {
+        let (Some(tag_bits), Some(length_bits)) = (self.tag_bits, self.length_bits) else {
+            return Err(config_error("`tag_bits` and `length_bits` go together"));
+        };
+        if self.format != "TLV" || self.tag.is_none() {
+            return Err(config_error("`tag_bits` and `length_bits` require a TLV with a `tag`"));
+        }
+        let header_bits = tag_bits as u32 + length_bits as u32;
+        if tag_bits == 0 || length_bits == 0 || !header_bits.is_multiple_of(8) || header_bits > 32 {
+            return Err(config_error("`tag_bits` and `length_bits` should fill 1 to 4 whole octets"));
+        }
+        if self.tag.is_some_and(|tag| tag >> tag_bits != 0) {
+            return Err(config_error("`tag` does not fit in `tag_bits`"));
+        }
+        if self.oui.is_some() != self.subtype.is_some() || self.oui.is_some_and(|oui| oui > 0xFF_FFFF) {
+            return Err(config_error("`oui` of 3 octets and `subtype` go together"));
+        }
+        Ok(())
+    }
+
+    // The tag and length widths of a packed header
+    pub(crate) fn packed_header(&self) -> Option<(u8, u8)> {
+        self.tag_bits.zip(self.length_bits)
+    }
+
+    pub(crate) fn packed_header_octets(&self) -> usize {
+        self.packed_header()
+            .map_or(0, |(tag_bits, length_bits)| (tag_bits as usize + length_bits as usize) / 8)
+    }
+
+    // The OUI and the subtype as they are on the wire, one 4 octet integer
+    pub(crate) fn oui_subtype(&self) -> Option<u32> {
+        self.oui.zip(self.subtype).map(|(oui, subtype)| oui << 8 | subtype as u32)
+    }
+
+    // The values of all instances of an optional TLV make up one value, wherever they are
+    // in the message. The sub-attributes of a vendor may be spread over several
+    // Vendor-Specific attributes, RFC 2865 section 5.26, and a long DHCP option over several
+    // instances, RFC 3396 section 7. A Long Extended Type ends with its More flag instead.
+    pub(crate) fn is_gathered(&self) -> bool {
+        self.format == "TLV"
+            && (self.vendor_id.is_some() || (self.fragmented && self.extended_type.is_none()))
+    }
+
+    // A flags octet in front of a 1 octet tag, one bit of it selects a 2 octet length instead
+    // of a 1 octet one, e.g. the Extended Length flag of a BGP path attribute. The flag
+    // follows the value length on encode, the other bits are always `flags`. Decoding checks
+    // the bits of `flags_mask` only, e.g. the Optional and Transitive bits of BGP while a
+    // speaker on the path may set the Partial bit.
+    fn check_extended_length(&self) -> Result<(), Error> {
+        let extended_length = self.extended_length.unwrap();
+        if self.format != "TLV"
+            || self.tag.is_none()
+            || self.tag_bytes_format != 1
+            || self.length_bytes_format != 1
+            || self.instance.is_some()
+            || self.enterprise_id.is_some()
+            || self.packed_header().is_some()
+        {
+            return Err(config_error("`extended_length` requires a TLV with a 1 octet `tag` and length"));
+        }
+        if extended_length.count_ones() != 1 {
+            return Err(config_error("`extended_length` should be a single flag bit"));
+        }
+        if self.flags.is_some_and(|flags| flags & extended_length != 0) {
+            return Err(config_error("`flags` can not set the `extended_length` bit, it follows the value length"));
+        }
+        if self.flags_mask.is_some_and(|flags_mask| flags_mask & extended_length != 0) {
+            return Err(config_error("`flags_mask` can not cover the `extended_length` bit"));
+        }
+        Ok(())
+    }
+
+    // A length counting multiples of `length_unit` octets and, with `length_includes_header`,
+    // the tag and length octets too, e.g. the EAP-AKA attributes of RFC 4187 section 8.1.
+    // The value is padded with zeros to a whole number of units.
+    fn check_length_unit(&self) -> Result<(), Error> {
+        if !matches!(self.format.as_str(), "TLV" | "TLV-E" | "LV" | "LV-E")
+            || self.length.is_some()
+            || self.length_bytes_format == 0
+            || self.instance.is_some()
+            || self.enterprise_id.is_some()
+            || self.packed_header().is_some()
+            || self.extended_length.is_some()
+        {
+            return Err(config_error("`length_unit` and `length_includes_header` require a TLV or LV of variable length"));
+        }
+        if self.length_unit == Some(0) {
+            return Err(config_error("`length_unit` should be at least 1"));
+        }
+        Ok(())
+    }
+
+    // A RADIUS attribute, RFC 2865 section 5, with a 1 octet type and length. `vendor_id`
+    // puts the Vendor-Id of a Vendor-Specific attribute and `extended_type` the Extended-Type
+    // of RFC 6929 after the length, both counted by it. `fragmented` values are split across
+    // consecutive attributes of the same type, with the More flag of a Long Extended Type
+    // when there is an `extended_type`. The elements of IEEE 802.11 section 9.4.2 have the
+    // same layout: `extended_type` is the Element ID Extension of element 255, and `oui` and
+    // `subtype` are the OUI and the type of a Vendor Specific element.
+    fn check_attribute(&self) -> Result<(), Error> {
+        if self.format != "TLV"
+            || self.tag.is_none()
+            || self.tag_bytes_format != 1
+            || self.length_bytes_format != 1
+            || self.length.is_some()
+            || self.instance.is_some()
+            || self.enterprise_id.is_some()
+            || self.packed_header().is_some()
+            || self.extended_length.is_some()
+            || self.length_unit() != 1
+        {
+            return Err(config_error("`vendor_id`, `extended_type`, `oui` and `fragmented` require a TLV with a 1 octet `tag` and length"));
+        }
+        let ids = [self.vendor_id.is_some(), self.extended_type.is_some(), self.oui.is_some()];
+        if ids.into_iter().filter(|id| *id).count() > 1 {
+            return Err(config_error("`vendor_id`, `extended_type` and `oui` are exclusive"));
+        }
+        if self.oui.is_some() != self.subtype.is_some() || self.oui.is_some_and(|oui| oui > 0xFF_FFFF) {
+            return Err(config_error("`oui` of 3 octets and `subtype` go together"));
+        }
+        // Splitting at any octet would cut the vendor sub-attributes in two
+        if (self.oui.is_some() || self.vendor_id.is_some()) && self.fragmented {
+            return Err(config_error("`fragmented` can not be combined with `vendor_id`, `oui` and `subtype`"));
+        }
+        Ok(())
+    }
+
+    // A single octet tag without a length which ends the optional IEs, e.g. the End option
+    // of DHCP, RFC 2132 section 3.2. `pad` is the single octet Pad option, skipped anywhere
+    // in the optional IEs and filling the octets after the end. A packed header of a zero
+    // length ends them too, e.g. the End of LLDPDU TLV of IEEE 802.1AB section 8.5.1, and
+    // only the zeros padding the frame may follow it.
+    fn check_end(&self) -> Result<(), Error> {
+        if self.packed_header().is_some() {
+            if self.pad.is_some() || self.oui.is_some() {
+                return Err(config_error("`end` with `tag_bits` takes no `pad`, `oui` or `subtype`"));
+            }
+            return Ok(());
+        }
+        if self.format != "T" || self.tag_bytes_format != 1 {
+            return Err(config_error("`end` requires a T with a 1 octet `tag` or a TLV with `tag_bits`"));
+        }
+        if self.pad.is_some_and(|pad| Some(pad as usize) == self.tag) {
+            return Err(config_error("`pad` and the `end` tag should differ"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn length_unit(&self) -> usize {
+        self.length_unit.unwrap_or(1)
+    }
+
+    // Header octets the length counts on top of the value
+    pub(crate) fn counted_header_octets(&self) -> usize {
+        match (self.length_includes_header, self.format.starts_with("LV")) {
+            (false, _) => 0,
+            (true, true) => self.length_bytes_format as usize,
+            (true, false) => (self.tag_bytes_format + self.length_bytes_format) as usize,
+        }
+    }
+
+    // The length is not simply the value octets
+    pub(crate) fn is_scaled_length(&self) -> bool {
+        self.length_unit() > 1 || self.length_includes_header
+    }
+
+    // A Diameter AVP, RFC 6733 section 4.1: a 4 octet code, the flags, a 3 octet length
+    // counting the header and the value, the Vendor-ID when the V flag is set, and the
+    // value padded to a multiple of 4 octets. The length and the padding are generated by
+    // the AVP code paths, `length_bytes_format` is only set for completeness.
+    fn apply_avp(&mut self) -> Result<(), Error> {
+        if self.tag.is_none_or(|tag| tag > u32::MAX as usize) {
+            return Err(config_error("AVP requires a 4 octet `tag`, the AVP code"));
+        }
+        if self.avp_flags.is_some_and(|avp_flags| avp_flags & !AVP_FLAGS_SETTABLE != 0) {
+            return Err(config_error("`avp_flags` can only set the M (0x40) and P (0x20) flags"));
+        }
+        self.tag_bytes_format = 4;
+        self.length = None;
+        self.length_bytes_format = 3;
+        Ok(())
+    }
+
+    // An ASN.1 BER element, X.690 section 8.1: the identifier octets with the `class`, the
+    // `constructed` bit and the `tag` number, a length in the short or the long form and
+    // the contents. The tag number is replaced by the identifier octets read as one
+    // integer, which is what is found on the wire and what optional IEs are matched on.
+    fn apply_ber(&mut self) -> Result<(), Error> {
+        let Some(number) = self.tag else {
+            return Err(config_error("BER requires a `tag`, the tag number"));
+        };
+        if number >= 1 << 21 {
+            return Err(config_error("`tag` of BER should fit 3 octets of the high tag number form, below 2^21"));
+        }
+        if self.tag_mask.is_some()
+            || self.instance.is_some()
+            || self.enterprise_id.is_some()
+            || self.vendor_id.is_some()
+            || self.packed_header().is_some()
+            || self.extended_length.is_some()
+            || self.length_unit.is_some()
+            || self.length_includes_header
+            || self.extended_type.is_some()
+            || self.fragmented
+            || self.end
+        {
+            return Err(config_error("BER only takes a `class`, `constructed` and length bounds besides the `tag`"));
+        }
+        let class = match self.class.as_deref().unwrap_or("universal") {
+            "universal" => 0x00,
+            "application" => 0x40,
+            "context" => 0x80,
+            "private" => 0xC0,
+            _ => {
+                return Err(config_error("`class` should be one of universal, application, context or private"));
+            }
+        };
+        let leading_octet = class | if self.constructed { 0x20 } else { 0x00 };
+        let (tag, tag_octets) = if number < 0x1F {
+            (leading_octet | number, 1)
+        } else {
+            // The high tag number form, 7 bits of the number per octet after the first
+            let groups = (usize::BITS - number.leading_zeros()).div_ceil(7);
+            let tag = (0..groups).rev().fold(leading_octet | 0x1F, |tag, group| {
+                let more = if group != 0 { 0x80 } else { 0x00 };
+                tag << 8 | (number >> (7 * group)) & 0x7F | more
+            });
+            (tag, 1 + groups as u8)
+        };
+        self.tag = Some(tag);
+        self.tag_bytes_format = tag_octets;
+        self.length = None;
+        Ok(())
+    }
+
+    // The flags octet written on encode, the V flag follows from `vendor_id`
+    pub(crate) fn avp_flags(&self) -> u8 {
+        self.avp_flags.unwrap_or(0) | if self.vendor_id.is_some() { AVP_FLAG_VENDOR } else { 0 }
+    }
+
+    pub(crate) fn avp_header_octets(&self) -> usize {
+        if self.vendor_id.is_some() { 12 } else { 8 }
+    }
+
+    // Octets in front of the value, the instance octet, the enterprise ID, the Vendor-Id,
+    // the Extended-Type or the OUI and subtype come after the length
+    pub(crate) fn header_octets(&self) -> u8 {
+        self.tag_bytes_format + self.length_bytes_format + self.extra_octets()
+    }
+
+    pub(crate) fn extra_octets(&self) -> u8 {
+        if self.instance.is_some() {
+            1
+        } else if self.enterprise_id.is_some() {
+            2
+        } else if self.vendor_id.is_some() && self.format != "AVP" {
+            4
+        } else if self.extended_type.is_some() {
+            // The flags octet of a Long Extended Type follows
+            if self.fragmented { 2 } else { 1 }
+        } else if self.oui_subtype().is_some() && self.packed_header().is_none() {
+            4
+        } else {
+            0
+        }
+    }
+
+    // The extra octets the length counts, all but the GTPv2-C instance octet
+    pub(crate) fn counted_extra_octets(&self) -> usize {
+        if self.instance.is_some() { 0 } else { self.extra_octets() as usize }
+    }
+
+    fn check_single_octet(&self, name: &str) -> Result<(), Error> {
+        if self.min_octets.is_some_and(|octets| octets != 1) || self.max_octets.is_some_and(|octets| octets != 1) {
+            return Err(config_error(&format!("{name} IE is always a single octet")));
+        }
+        Ok(())
+    }
+
+    fn value_octets(&self, octets: usize, header: usize) -> Result<usize, Error> {
+        octets
+            .checked_sub(header)
+            .ok_or_else(|| config_error(&format!("IE can not be shorter than its {header} header octets")))
+    }
+}
+
+// Settings of the whole struct, given on the struct itself. `unknown_ie` selects how
+// unknown optional IEs are skipped: `tlv` (the default) uses the header layout of the
+// optional IEs, `ts24007` derives the format from the IEI as per TS 24.007 section 11.2.4.
+// `unordered` is for protocols without a message table order, e.g. Diameter AVPs, where
+// no `OutOfOrderIe` handling applies. With `skippable_from`, unknown tags from that value
+// on are skipped and the ones below are an `UnknownTag` whatever the mode, e.g. the
+// skippable attributes 128 to 255 of EAP-AKA, RFC 4187 section 8.1.
+#[derive(FromAttr, Debug, Clone, Default)]
+#[attribute(ident = tlv_config)]
+#[attribute(error(
+    unknown_field = "expected one of {expected_fields:i(`{}`)(, )}",
+    duplicate_field = "duplicate `{field}`",
+    missing_field = "missing field `{field}`",
+    field_help = "try {attribute}: {field}={example}",
+    conflict = "{first} !!! {second}"
+))]
+pub struct StructConfig {
+    pub(crate) unknown_ie: Option<String>,
+    pub(crate) unordered: bool,
+    pub(crate) skippable_from: Option<usize>,
+}
+
+impl StructConfig {
+    pub(crate) fn parse(attrs: Vec<syn::Attribute>) -> Result<StructConfig, Error> {
+        let struct_config = StructConfig::from_attributes(attrs)?;
+        if struct_config
+            .unknown_ie
+            .as_deref()
+            .is_some_and(|unknown_ie| !matches!(unknown_ie, "tlv" | "ts24007"))
+        {
+            return Err(config_error("`unknown_ie` should be `tlv` or `ts24007`"));
+        }
+        Ok(struct_config)
+    }
+
+    pub(crate) fn is_ts24007(&self) -> bool {
+        self.unknown_ie.as_deref() == Some("ts24007")
+    }
+}
+
+const AVP_FLAG_VENDOR: u8 = 0x80;
+const AVP_FLAGS_SETTABLE: u8 = 0x60;
+
+fn config_error(message: &str) -> Error {
+    Error::new(Span::call_site().into(), message)
 }
 
 
@@ -51,6 +606,15 @@ pub(crate) fn get_bytes_format(bytes_format: u8) -> Ident {
 }
 
 
+// The smallest unsigned integer holding `bits`, bit fields are converted from and into it
+pub(crate) fn get_bits_type(bits: u8) -> Ident {
+    get_bytes_format(match bits {
+        0..=8 => 1,
+        9..=16 => 2,
+        _ => 4,
+    })
+}
+
 pub(crate) fn get_put_bytes(bytes_format: u8) -> Ident {
     match bytes_format {
         1 => {
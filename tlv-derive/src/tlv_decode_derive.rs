@@ -1,119 +1,724 @@
-use crate::tlv_config::{get_get_bytes, TlvConfig};
-use crate::utils::{get_struct_name, is_newtype};
-use attribute_derive::Attribute;
-use proc_macro2::{Ident, TokenStream};
+use crate::tlv_config::{get_bits_type, get_get_bytes, StructConfig, TlvConfig};
+use crate::utils::{
+    get_enum_variants, get_option_inner_type, get_struct_name, get_vec_inner_type, is_bool_type,
+    is_newtype,
+};
+use proc_macro2::{Ident, Span, TokenStream};
 use proc_macro_error::abort_call_site;
 use quote::quote;
-use syn::{DataStruct, DeriveInput, Error, Field, GenericArgument, Type};
+use syn::{DataEnum, DataStruct, DeriveInput, Error, Field, GenericArgument, Type};
 
-fn tag_decode(tlv_config: &TlvConfig) -> TokenStream {
+// A header read past the end of the input is an error rather than a panic of `Buf`.
+fn remaining_check(octets: usize, field_name_str: &str) -> TokenStream {
+    quote! {
+        if __bytes.remaining() < #octets {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __bytes.remaining(),
+            });
+        }
+    }
+}
+
+fn tag_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
     if tlv_config.tag_bytes_format == 0 {
         return quote! {
             let __actual_tag: usize = 0usize;
         };
     }
+    let field_name_str = field_name.to_string();
+    let remaining_stream = remaining_check(tlv_config.tag_bytes_format as usize, &field_name_str);
     match tlv_config.tag {
         Some(tag) => {
-            let tag_bytes = tlv_config.tag_bytes_format as usize;
+            let get_bytes = get_get_bytes(tlv_config.tag_bytes_format);
             quote! {
+                #remaining_stream
                 let __actual_tag: usize = #tag;
-                __bytes.advance(#tag_bytes);
+                let __found_tag = __bytes.#get_bytes() as usize;
+                if __options.is_strict() && __found_tag != __actual_tag {
+                    return Err(tlv::prelude::TlvError::TagMismatch {
+                        expected: __actual_tag,
+                        found: __found_tag,
+                        field: #field_name_str,
+                    });
+                }
             }
         }
         None => {
             let get_bytes = get_get_bytes(tlv_config.tag_bytes_format);
             quote! {
+                #remaining_stream
                 let __actual_tag = __bytes.#get_bytes() as usize;
             }
         }
     }
 }
 
+// 4bit tag shares the octet with the value, so the check happens on the already read chunk.
+fn tag_4bit_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
+    let tag = tlv_config.tag.expect("TAG is required to type Tv");
+    let field_name_str = field_name.to_string();
+    let remaining_stream = remaining_check(1, &field_name_str);
+    quote! {
+        #remaining_stream
+        let __chunk = __bytes.get_u8();
+        let __found_tag = (__chunk >> 4) as usize;
+        if __options.is_strict() && __found_tag != #tag {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #tag,
+                found: __found_tag,
+                field: #field_name_str,
+            });
+        }
+    }
+}
+
 
 // Todo: decide whether to put the check for lenth constraints or not,
 // will it have any performance impact.
 
-// fn length_decode(tlv_config: &TlvConfig) -> TokenStream {
-//     if tlv_config.length_bytes_format == 0 {
-//         return quote! {
-//             let __actual_length: usize = 0usize;
-//         };
-//     }
-//     match tlv_config.length {
-//         Some(length) => {
-//             let length_bytes = tlv_config.length_bytes_format as usize;
-//             quote! {
-//                 let __actual_length: usize = #length;
-//                 __bytes.advance(#length_bytes);
-//             }
-//         }
-//         None => {
-//             let get_bytes = get_get_bytes(tlv_config.length_bytes_format);
-//             quote! {
-//                 let __actual_length = __bytes.#get_bytes() as usize;
-//             }
-//         }
-//     }
-// }
-
-
-
-fn length_decode(tlv_config: &TlvConfig) -> TokenStream {
+// Produces `__wire_length`, the octets the value occupies on the wire, and `__actual_length`,
+// the octets handed to the value decoder. They only differ in lenient mode where the
+// superfluous octets of an over long IE are ignored as per TS 24.007.
+fn length_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
     if tlv_config.length_bytes_format == 0 {
         return quote! {
             let __actual_length: usize = 0usize;
+            let __wire_length: usize = 0usize;
         };
     }
-    
+    let field_name_str = field_name.to_string();
+    let get_bytes = get_get_bytes(tlv_config.length_bytes_format);
+    let remaining_stream = remaining_check(tlv_config.length_bytes_format as usize, &field_name_str);
+
     match tlv_config.length {
         Some(length) => {
-            let get_bytes = get_get_bytes(tlv_config.length_bytes_format);
-            
             quote! {
-                let __parsed_length = __bytes.#get_bytes() as usize;
-                if __parsed_length != #length {
-                    return Err(tlv::prelude::TlvError::MalformedPayload);
+                #remaining_stream
+                let __wire_length = __bytes.#get_bytes() as usize;
+                if __wire_length != #length && (__options.is_strict() || __wire_length < #length) {
+                    return Err(tlv::prelude::TlvError::InvalidLength {
+                        field: #field_name_str,
+                        length: __wire_length,
+                    });
                 }
                 let __actual_length: usize = #length;
             }
         }
-        None => {
-            let get_bytes = get_get_bytes(tlv_config.length_bytes_format);
-            
-            let length_validation_stream = if tlv_config.min_length.is_some() || tlv_config.max_length.is_some() {
-                let min_check = if let Some(min) = tlv_config.min_length {
-                    quote! {
-                        if __actual_length < #min {
-                            return Err(tlv::prelude::TlvError::MalformedPayload);
-                        }
-                    }
-                } else {
-                    quote! {}
-                };
-                
-                let max_check = if let Some(max) = tlv_config.max_length {
-                    quote! {
-                        if __actual_length > #max {
-                            return Err(tlv::prelude::TlvError::MalformedPayload);
-                        }
-                    }
-                } else {
-                    quote! {}
+        None if tlv_config.is_scaled_length() => {
+            let length_unit = tlv_config.length_unit();
+            let counted_header_octets = tlv_config.counted_header_octets();
+            let bounds_stream = length_bounds(tlv_config, &field_name_str);
+            quote! {
+                #remaining_stream
+                let __length_field = __bytes.#get_bytes() as usize;
+                let Some(__wire_length) = (__length_field * #length_unit).checked_sub(#counted_header_octets) else {
+                    return Err(tlv::prelude::TlvError::InvalidLength {
+                        field: #field_name_str,
+                        length: __length_field,
+                    });
                 };
-                
-                quote! {
-                    #min_check
-                    #max_check
-                }
-            } else {
-                quote! {}
-            };
-            
+                #[allow(unused_mut)]
+                let mut __actual_length = __wire_length;
+                #bounds_stream
+            }
+        }
+        None => {
+            let bounds_stream = length_bounds(tlv_config, &field_name_str);
             quote! {
-                let __actual_length = __bytes.#get_bytes() as usize;
-                #length_validation_stream
+                #remaining_stream
+                let __wire_length = __bytes.#get_bytes() as usize;
+                #[allow(unused_mut)]
+                let mut __actual_length = __wire_length;
+                #bounds_stream
+            }
+        }
+    }
+}
+
+// `min_length` and `max_length` checks of `__wire_length`, an over long value is cut to
+// `max_length` in lenient mode.
+fn length_bounds(tlv_config: &TlvConfig, field_name_str: &str) -> TokenStream {
+    let min_check = if let Some(min) = tlv_config.min_length {
+        quote! {
+            if __wire_length < #min {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __wire_length,
+                });
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let max_check = if let Some(max) = tlv_config.max_length {
+        quote! {
+            if __wire_length > #max {
+                if __options.is_strict() {
+                    return Err(tlv::prelude::TlvError::InvalidLength {
+                        field: #field_name_str,
+                        length: __wire_length,
+                    });
+                }
+                __actual_length = #max;
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #min_check
+        #max_check
+    }
+}
+
+// The AVP header up to the value. A different vendor is treated like a wrong code, the
+// header layout follows the V flag found on the wire so lenient decoding can go on.
+fn avp_header_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let code = tlv_config.tag.unwrap();
+    let vendor_id = tlv_config.vendor_id.unwrap_or(0) as usize;
+    let bounds_stream = length_bounds(tlv_config, &field_name_str);
+    quote! {
+        if __bytes.remaining() < 8 {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __bytes.remaining(),
+            });
+        }
+        let __found_tag = __bytes.get_u32() as usize;
+        if __options.is_strict() && __found_tag != #code {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #code,
+                found: __found_tag,
+                field: #field_name_str,
+            });
+        }
+        let __avp_flags = __bytes.get_u8();
+        if __options.is_strict() && __avp_flags & 0x1F != 0 {
+            return Err(tlv::prelude::TlvError::NonZeroSpare {
+                field: #field_name_str,
+            });
+        }
+        let __avp_length = __bytes.get_uint(3) as usize;
+        let (__header_length, __found_vendor_id) = if __avp_flags & 0x80 != 0 {
+            if __bytes.remaining() < 4 {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __avp_length,
+                });
+            }
+            (12usize, __bytes.get_u32() as usize)
+        } else {
+            (8usize, 0usize)
+        };
+        if __options.is_strict() && __found_vendor_id != #vendor_id {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #vendor_id,
+                found: __found_vendor_id,
+                field: #field_name_str,
+            });
+        }
+        let Some(__wire_length) = __avp_length.checked_sub(__header_length) else {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __avp_length,
+            });
+        };
+        #[allow(unused_mut)]
+        let mut __actual_length = __wire_length;
+        #bounds_stream
+    }
+}
+
+// The padding after the value is zero in strict mode, the last AVP of a lenient message
+// may come without it.
+fn avp_padding_decode(field_name: &Ident) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    quote! {
+        let __padding = (4 - __wire_length % 4) % 4;
+        if __options.is_strict() {
+            if __bytes.remaining() < __padding {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __wire_length,
+                });
+            }
+            if __bytes[..__padding].iter().any(|__octet| *__octet != 0) {
+                return Err(tlv::prelude::TlvError::NonZeroSpare {
+                    field: #field_name_str,
+                });
+            }
+        }
+        __bytes.advance(__padding.min(__bytes.remaining()));
+    }
+}
+
+fn format_avp_decode(value_type: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let header_stream = avp_header_decode(tlv_config, field_name);
+    let value_stream = value_decode(value_type, field_name, tlv_config);
+    let padding_stream = avp_padding_decode(field_name);
+    quote! {
+        #header_stream
+        #value_stream
+        #padding_stream
+    }
+}
+
+// A BER element into `__decoded`. Strict decoding is DER, a definite length in the fewest
+// octets, lenient decoding takes any BER length including the indefinite form of a
+// constructed element, whose end-of-contents octets are skipped after the value.
+fn format_ber_decode(value_type: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let tag = tlv_config.tag.unwrap();
+    let bounds_stream = length_bounds(tlv_config, &field_name_str);
+    let value_stream = value_decode(value_type, field_name, tlv_config);
+    quote! {
+        let (__found_tag, __wire_length, __end_of_contents) =
+            tlv::prelude::get_ber_header(__bytes, #field_name_str, __options.is_strict())?;
+        if __options.is_strict() && __found_tag != #tag {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #tag,
+                found: __found_tag,
+                field: #field_name_str,
+            });
+        }
+        #[allow(unused_mut)]
+        let mut __actual_length = __wire_length;
+        #bounds_stream
+        #value_stream
+        __bytes.advance(__end_of_contents);
+    }
+}
+
+// The octet after the length of a GTPv2-C IE, a spare nibble and the instance, the
+// enterprise ID of a PFCP IE, the Vendor-Id or Extended-Type of a RADIUS attribute, or the
+// OUI and type of an 802.11 Vendor Specific element. A
+// wrong instance, ID or type of a mandatory IE is treated like a wrong tag.
+fn extra_header_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    if let Some(enterprise_id) = tlv_config.enterprise_id {
+        return counted_id_decode(enterprise_id as usize, 2, &field_name_str);
+    }
+    if let Some(vendor_id) = tlv_config.vendor_id {
+        return counted_id_decode(vendor_id as usize, 4, &field_name_str);
+    }
+    if let Some(oui_subtype) = tlv_config.oui_subtype() {
+        return counted_id_decode(oui_subtype as usize, 4, &field_name_str);
+    }
+    if let Some(extended_type) = tlv_config.extended_type {
+        let extended_type_stream = counted_id_decode(extended_type as usize, 1, &field_name_str);
+        if !tlv_config.fragmented {
+            return extended_type_stream;
+        }
+        // The flags of a Long Extended Type, the More flag and 7 reserved bits
+        return quote! {
+            #extended_type_stream
+            if __wire_length < 1 || !__bytes.has_remaining() {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __wire_length,
+                });
+            }
+            let __long_flags = __bytes.get_u8();
+            if __options.is_strict() && __long_flags & 0x7F != 0 {
+                return Err(tlv::prelude::TlvError::NonZeroSpare {
+                    field: #field_name_str,
+                });
+            }
+            let __more = __long_flags & 0x80 != 0;
+            let __wire_length = __wire_length - 1;
+            let __actual_length = __actual_length.saturating_sub(1);
+        };
+    }
+    let Some(instance) = tlv_config.instance else {
+        return quote! {};
+    };
+    quote! {
+        if !__bytes.has_remaining() {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __wire_length,
+            });
+        }
+        let __instance_octet = __bytes.get_u8();
+        if __options.is_strict() {
+            if (__instance_octet & 0x0F) != #instance {
+                return Err(tlv::prelude::TlvError::TagMismatch {
+                    expected: #instance as usize,
+                    found: (__instance_octet & 0x0F) as usize,
+                    field: #field_name_str,
+                });
+            }
+            if __instance_octet >> 4 != 0 {
+                return Err(tlv::prelude::TlvError::NonZeroSpare {
+                    field: #field_name_str,
+                });
+            }
+        }
+    }
+}
+
+// An ID of `octets` after the length. It is counted by the length, so it is taken off the
+// value.
+fn counted_id_decode(id: usize, octets: usize, field_name_str: &str) -> TokenStream {
+    quote! {
+        if __wire_length < #octets || __bytes.remaining() < #octets {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __wire_length,
+            });
+        }
+        let __found_id = __bytes.get_uint(#octets) as usize;
+        if __options.is_strict() && __found_id != #id {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #id,
+                found: __found_id,
+                field: #field_name_str,
+            });
+        }
+        let __wire_length = __wire_length - #octets;
+        let __actual_length = __actual_length.saturating_sub(#octets);
+    }
+}
+
+// Everything of a TLV in front of its value
+fn tlv_header_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
+    if tlv_config.packed_header().is_some() {
+        return packed_header_decode(tlv_config, field_name);
+    }
+    if tlv_config.extended_length.is_some() {
+        return extended_length_header_decode(tlv_config, field_name);
+    }
+    let tag_stream = tag_decode(tlv_config, field_name);
+    let length_stream = length_decode(tlv_config, field_name);
+    let extra_header_stream = extra_header_decode(tlv_config, field_name);
+    quote! {
+        #tag_stream
+        #length_stream
+        #extra_header_stream
+    }
+}
+
+// The tag and the length share the header octets, the tag in the most significant bits.
+// The OUI and subtype of an organizationally specific TLV are taken off the value length.
+fn packed_header_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let (_, length_bits) = tlv_config.packed_header().unwrap();
+    let header_octets = tlv_config.packed_header_octets();
+    let tag = tlv_config.tag.unwrap();
+    let length_mask = (1u64 << length_bits) - 1;
+    let bounds_stream = length_bounds(tlv_config, &field_name_str);
+    let oui_subtype_stream = match tlv_config.oui_subtype() {
+        Some(oui_subtype) => quote! {
+            if __wire_length < 4 || __bytes.remaining() < 4 {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __wire_length,
+                });
+            }
+            let __found_oui_subtype = __bytes.get_u32();
+            if __options.is_strict() && __found_oui_subtype != #oui_subtype {
+                return Err(tlv::prelude::TlvError::TagMismatch {
+                    expected: #oui_subtype as usize,
+                    found: __found_oui_subtype as usize,
+                    field: #field_name_str,
+                });
             }
+            let __wire_length = __wire_length - 4;
+            let __actual_length = __actual_length.saturating_sub(4);
+        },
+        None => quote! {},
+    };
+    quote! {
+        if __bytes.remaining() < #header_octets {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __bytes.remaining(),
+            });
+        }
+        let __header = __bytes.get_uint(#header_octets);
+        let __found_tag = (__header >> #length_bits) as usize;
+        if __options.is_strict() && __found_tag != #tag {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #tag,
+                found: __found_tag,
+                field: #field_name_str,
+            });
         }
+        let __wire_length = (__header & #length_mask) as usize;
+        #[allow(unused_mut)]
+        let mut __actual_length = __wire_length;
+        #bounds_stream
+        #oui_subtype_stream
+    }
+}
+
+// The length width follows the `extended_length` flag found on the wire. Other flags than
+// `flags` within `flags_mask` are treated like a wrong tag.
+fn extended_length_header_decode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let tag = tlv_config.tag.unwrap();
+    let extended_length = tlv_config.extended_length.unwrap();
+    let flags_mask = tlv_config.flags_mask.unwrap_or(!extended_length);
+    let flags = tlv_config.flags.unwrap_or(0) & flags_mask;
+    let bounds_stream = length_bounds(tlv_config, &field_name_str);
+    quote! {
+        if __bytes.remaining() < 3 {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __bytes.remaining(),
+            });
+        }
+        let __flags = __bytes.get_u8();
+        if __options.is_strict() && __flags & #flags_mask != #flags {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #flags as usize,
+                found: (__flags & #flags_mask) as usize,
+                field: #field_name_str,
+            });
+        }
+        let __found_tag = __bytes.get_u8() as usize;
+        if __options.is_strict() && __found_tag != #tag {
+            return Err(tlv::prelude::TlvError::TagMismatch {
+                expected: #tag,
+                found: __found_tag,
+                field: #field_name_str,
+            });
+        }
+        let __wire_length = if __flags & #extended_length != 0 {
+            if __bytes.remaining() < 2 {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __bytes.remaining(),
+                });
+            }
+            __bytes.get_u16() as usize
+        } else {
+            __bytes.get_u8() as usize
+        };
+        #[allow(unused_mut)]
+        let mut __actual_length = __wire_length;
+        #bounds_stream
+    }
+}
+
+// Decodes `__actual_length` octets out of the next `__wire_length` octets into `__decoded`.
+// A `depends_on` value is decoded with the sibling bound to `__context` by `context_decode`.
+fn value_decode(field_type: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let decode_stream = if tlv_config.depends_on.is_some() {
+        quote! {
+            <#field_type as tlv::prelude::TlvDecodeWith<_>>::decode_with(
+                __actual_length,
+                &mut __value,
+                __context,
+                __options,
+            )?
+        }
+    } else {
+        quote! { <#field_type>::decode_with_options(__actual_length, &mut __value, __options)? }
+    };
+    // What the value leaves over of a length in units is its padding, short of a unit
+    let length_unit = tlv_config.length_unit();
+    let trailing_stream = if length_unit > 1 {
+        quote! {
+            if __options.is_strict() && __value.has_remaining() {
+                if __value.remaining() >= #length_unit {
+                    return Err(tlv::prelude::TlvError::TrailingBytes {
+                        field: #field_name_str,
+                        remaining: __value.remaining(),
+                    });
+                }
+                if __value.iter().any(|__octet| *__octet != 0) {
+                    return Err(tlv::prelude::TlvError::NonZeroSpare {
+                        field: #field_name_str,
+                    });
+                }
+            }
+        }
+    } else {
+        quote! {
+            if __options.is_strict() && __value.has_remaining() {
+                return Err(tlv::prelude::TlvError::TrailingBytes {
+                    field: #field_name_str,
+                    remaining: __value.remaining(),
+                });
+            }
+        }
+    };
+    quote! {
+        if __bytes.remaining() < __wire_length {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __wire_length,
+            });
+        }
+        let mut __value = __bytes.split_to(__wire_length);
+        __value.truncate(__actual_length);
+        let __decoded = #decode_stream;
+        #trailing_stream
+    }
+}
+
+// Binds `__context` to the sibling named by `depends_on`, which has to be declared before
+// the field. An optional sibling has to be present by the time the field is decoded.
+fn context_decode(tlv_config: &TlvConfig, declared_fields: &[(String, bool)]) -> TokenStream {
+    let Some(sibling) = &tlv_config.depends_on else {
+        return quote! {};
+    };
+    let Some((_, is_option)) = declared_fields.iter().find(|(field_name, _)| field_name == sibling) else {
+        abort_call_site!("`depends_on` should name a field declared before")
+    };
+    let sibling_ident = Ident::new(sibling, proc_macro2::Span::call_site());
+    if *is_option {
+        quote! {
+            let __context = #sibling_ident
+                .as_ref()
+                .ok_or(tlv::prelude::TlvError::MissingIe { field: #sibling })?;
+        }
+    } else {
+        quote! {
+            let __context = &#sibling_ident;
+        }
+    }
+}
+
+// Spare octets and half octets shall be zero, only checked in strict mode.
+fn spare_decode(tlv_config: &TlvConfig, field_name: &Ident, is_nonzero: TokenStream) -> TokenStream {
+    if !tlv_config.spare {
+        return quote! {};
+    }
+    let field_name_str = field_name.to_string();
+    quote! {
+        if __options.is_strict() && #is_nonzero {
+            return Err(tlv::prelude::TlvError::NonZeroSpare {
+                field: #field_name_str,
+            });
+        }
+    }
+}
+
+// The fragments of a mandatory value are the attributes of the same type that follow each
+// other, or for a Long Extended Type those up to the one without the More flag. Optional
+// values other than a Long Extended Type are gathered instead. `min_length` and
+// `max_length` bound the reassembled value.
+fn format_fragmented_decode(value_type: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let tag = tlv_config.tag.unwrap() as u8;
+    let fragment_config = TlvConfig {
+        min_length: None,
+        max_length: None,
+        ..tlv_config.clone()
+    };
+    let header_stream = tlv_header_decode(&fragment_config, field_name);
+    let bounds_stream = length_bounds(tlv_config, &field_name_str);
+    let value_stream = value_decode(value_type, field_name, tlv_config);
+    let offset = (tlv_config.tag_bytes_format + tlv_config.length_bytes_format) as usize;
+    let next_stream = if tlv_config.extended_type.is_some() {
+        quote! { __more }
+    } else {
+        quote! { __bytes.first() == Some(&#tag) }
+    };
+    quote! {
+        let mut __fragments = BytesMut::new();
+        loop {
+            if __bytes.remaining() < #offset {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __bytes.remaining(),
+                });
+            }
+            #header_stream
+            if __bytes.remaining() < __wire_length {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __wire_length,
+                });
+            }
+            __fragments.put(__bytes.split_to(__wire_length));
+            if !(#next_stream) {
+                break;
+            }
+        }
+        let mut __reassembled = __fragments.freeze();
+        let __wire_length = __reassembled.len();
+        #[allow(unused_mut)]
+        let mut __actual_length = __wire_length;
+        #bounds_stream
+        let __decoded = {
+            let __bytes = &mut __reassembled;
+            #value_stream
+            __decoded
+        };
+    }
+}
+
+fn gathered_name(field_name: &Ident) -> Ident {
+    Ident::new(&format!("__gathered_{field_name}"), Span::call_site())
+}
+
+// An instance of a gathered TLV only adds its value octets to the others, see
+// `gathered_value_decode` for the value.
+fn gathered_instance_decode(field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let gathered_name = gathered_name(field_name);
+    let instance_config = TlvConfig {
+        min_length: None,
+        max_length: None,
+        ..tlv_config.clone()
+    };
+    let header_stream = tlv_header_decode(&instance_config, field_name);
+    quote! {
+        #header_stream
+        if __bytes.remaining() < __wire_length {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __wire_length,
+            });
+        }
+        #gathered_name
+            .get_or_insert_with(BytesMut::new)
+            .put(__bytes.split_to(__wire_length));
+    }
+}
+
+// Decodes the value of a gathered TLV once all instances are in. `min_length` and
+// `max_length` bound the joined value.
+fn gathered_value_decode(value_type: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let gathered_name = gathered_name(field_name);
+    let bounds_stream = length_bounds(tlv_config, &field_name_str);
+    let value_stream = value_decode(value_type, field_name, tlv_config);
+    quote! {
+        if let Some(__gathered) = #gathered_name {
+            let mut __gathered = __gathered.freeze();
+            let __wire_length = __gathered.len();
+            #[allow(unused_mut)]
+            let mut __actual_length = __wire_length;
+            #bounds_stream
+            let __decoded = {
+                let __bytes = &mut __gathered;
+                #value_stream
+                __decoded
+            };
+            #field_name = Some(__decoded);
+        }
+    }
+}
+
+// Header and value of a TLV into `__decoded`
+fn tlv_decode_stream(value_type: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    if tlv_config.fragmented {
+        return format_fragmented_decode(value_type, field_name, tlv_config);
+    }
+    let header_stream = tlv_header_decode(tlv_config, field_name);
+    let value_stream = value_decode(value_type, field_name, tlv_config);
+    quote! {
+        #header_stream
+        #value_stream
     }
 }
 
@@ -125,13 +730,11 @@ fn format_tlv_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream,
             abort_call_site!("Unsupported type in generic");
         }
     };
-    let tag_stream = tag_decode(&tlv_config);
-    let length_stream = length_decode(&tlv_config);
+    let decode_stream = tlv_decode_stream(quote! { #field_type }, &field_name, &tlv_config);
 
     Ok(quote! {
-        #tag_stream
-        #length_stream
-        let #field_name = <#field_type>::decode(__actual_length, &mut __bytes.split_to(__actual_length))?;
+        #decode_stream
+        let #field_name = __decoded;
     })
 }
 
@@ -143,11 +746,13 @@ fn format_lv_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream,
             abort_call_site!("Unsupported type in generic");
         }
     };
-    let length_stream = length_decode(&tlv_config);
+    let length_stream = length_decode(&tlv_config, &field_name);
+    let value_stream = value_decode(quote! { #field_type }, &field_name, &tlv_config);
 
     Ok(quote! {
         #length_stream
-        let #field_name = <#field_type>::decode(__actual_length, &mut __bytes.split_to(__actual_length))?;
+        #value_stream
+        let #field_name = __decoded;
     })
 }
 
@@ -162,22 +767,27 @@ fn format_tv_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream,
 
     if tlv_config.tag_bytes_format == 0 {
         // Its a 4bit tag 4bit valie case
-        let _tag = tlv_config.tag.expect("TAG is required to type Tv") as u8;
-        return Ok(quote! {
-            let #field_name = __bytes.get_u8() & 0b00001111;
-        });
+        let tag_stream = tag_4bit_decode(&tlv_config, &field_name);
+        Ok(quote! {
+            #tag_stream
+            let #field_name = __chunk & 0b00001111;
+        })
     } else {
         // Its a 1 or more byte tag and 1 or mote byte value case
-        let tag_stream = tag_decode(&tlv_config);
-        let length = tlv_config.length.expect("LENGTH is required to type Tv") as usize;
-        return Ok(quote! {
+        let tag_stream = tag_decode(&tlv_config, &field_name);
+        let length = tlv_config.length.expect("LENGTH is required to type Tv");
+        let value_stream = value_decode(quote! { #field_type }, &field_name, &tlv_config);
+        Ok(quote! {
             #tag_stream
-            let #field_name = <#field_type>::decode(#length, &mut __bytes.split_to(#length))?;
-        });
+            let __actual_length: usize = #length;
+            let __wire_length: usize = #length;
+            #value_stream
+            let #field_name = __decoded;
+        })
     }
 }
 
-fn format_t_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream, Error> {
+fn format_v_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream, Error> {
     let field_name = field.ident.unwrap();
     let field_type = match field.ty {
         Type::Path(type_path) => type_path.path,
@@ -185,34 +795,101 @@ fn format_t_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream, E
             abort_call_site!("Unsupported type in generic");
         }
     };
-    let tag_stream = tag_decode(&tlv_config);
 
+    // Without a length the value runs until the end of the message, e.g. a message body
+    let length = match tlv_config.length {
+        Some(length) => quote! { #length },
+        None => quote! { __bytes.remaining() },
+    };
+    // The spare check looks at the value before `value_decode` does, so the length is
+    // checked ahead of it
+    let spare_stream = spare_decode(
+        &tlv_config,
+        &field_name,
+        quote! { __bytes[..__wire_length].iter().any(|__octet| *__octet != 0) },
+    );
+    let remaining_stream = if tlv_config.spare {
+        let field_name_str = field_name.to_string();
+        quote! {
+            if __bytes.remaining() < __wire_length {
+                return Err(tlv::prelude::TlvError::InvalidLength {
+                    field: #field_name_str,
+                    length: __wire_length,
+                });
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let value_stream = value_decode(quote! { #field_type }, &field_name, &tlv_config);
     Ok(quote! {
-        #tag_stream
-        let __actual_length = 1usize;
-        let #field_name = <#field_type>::decode(__actual_length, &mut __bytes.split_to(__actual_length))?;
+        let __actual_length: usize = #length;
+        let __wire_length: usize = __actual_length;
+        #remaining_stream
+        #spare_stream
+        #value_stream
+        let #field_name = __decoded;
     })
 }
 
-fn format_v_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream, Error> {
-    let field_name = field.ident.unwrap();
-    let field_type = match field.ty {
-        Type::Path(type_path) => type_path.path,
-        _ => {
-            abort_call_site!("Unsupported type in generic");
-        }
+// An `Option` V whose presence is decided by the length of the whole struct, e.g. the SD
+// of an S-NSSAI which is only there for the lengths 4, 5 and 8.
+fn format_length_driven_decode(field: Field, tlv_config: TlvConfig) -> Result<TokenStream, Error> {
+    let field_name = field.ident.clone().unwrap();
+    let Some(inner_type) = get_option_inner_type(&field.ty) else {
+        abort_call_site!("`present_if_length` fields should be an `Option`")
     };
+    let lengths = tlv_config.present_if_length.clone();
+    let value_stream = format_v_decode(Field { ty: inner_type, ..field }, tlv_config)?;
+    Ok(quote! {
+        let #field_name = if [#(#lengths),*].contains(&length) {
+            #value_stream
+            Some(#field_name)
+        } else {
+            None
+        };
+    })
+}
 
-    let length = tlv_config.length.expect("LENGTH is required to type Tv") as usize;
+// Consecutive `bits` fields fill whole octets, read as one big endian integer. Like the
+// half octet pair the first declared field takes the least significant bits.
+fn format_bits_decode(fields: Vec<(Field, TlvConfig)>) -> Result<TokenStream, Error> {
+    let total_bits: u32 = fields.iter().map(|(_, tlv_config)| tlv_config.bits.unwrap() as u32).sum();
+    let octets = (total_bits / 8) as usize;
+    let first_field_name = fields[0].0.ident.clone().unwrap().to_string();
+    let mut shift = 0u32;
+    let mut value_streams = Vec::<TokenStream>::new();
+    for (field, tlv_config) in fields {
+        let field_name = field.ident.unwrap();
+        let field_type = field.ty;
+        let bits = tlv_config.bits.unwrap();
+        let bits_type = get_bits_type(bits);
+        let mask = (1u64 << bits) - 1;
+        let spare_stream = spare_decode(&tlv_config, &field_name, quote! { __field_bits != 0 });
+        value_streams.push(quote! {
+            let __field_bits = (__bits >> #shift) & #mask;
+            #spare_stream
+            let #field_name = <#field_type>::from(__field_bits as #bits_type);
+        });
+        shift += bits as u32;
+    }
     Ok(quote! {
-        let #field_name = <#field_type>::decode(#length, &mut __bytes.split_to(#length))?;
+        if __bytes.remaining() < #octets {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #first_field_name,
+                length: __bytes.remaining(),
+            });
+        }
+        let __bits: u64 = __bytes.get_uint(#octets);
+        #(#value_streams)*
     })
 }
 
 fn format_4bit_v_decode(
     field_1: Field,
+    tlv_config_1: TlvConfig,
     field_2: Field,
-    _: TlvConfig,
+    tlv_config_2: TlvConfig,
 ) -> Result<TokenStream, Error> {
     // Its a 4bit & 4bit value case
     let field_name_1 = field_1.ident.unwrap();
@@ -222,7 +899,13 @@ fn format_4bit_v_decode(
             abort_call_site!("Unsupported type in 4-bit value field");
         }
     };
+    let spare_stream_1 = spare_decode(
+        &tlv_config_1,
+        &field_name_1,
+        quote! { __chunk & 0b00001111 != 0 },
+    );
     let value_stream_1: TokenStream = quote! {
+        #spare_stream_1
         let #field_name_1: #field_type_1 = <#field_type_1>::from(__chunk & 0b00001111);
     };
 
@@ -233,15 +916,28 @@ fn format_4bit_v_decode(
             abort_call_site!("Unsupported type in 4-bit value field");
         }
     };
+    let spare_stream_2 = spare_decode(&tlv_config_2, &field_name_2, quote! { __chunk >> 4 != 0 });
     let value_stream_2: TokenStream = quote! {
+        #spare_stream_2
         let #field_name_2: #field_type_2 = <#field_type_2>::from(__chunk >> 4);
     };
+    let remaining_stream = remaining_check(1, &field_name_1.to_string());
 
-    return Ok(quote! {
+    Ok(quote! {
+        #remaining_stream
         let __chunk = __bytes.get_u8();
         #value_stream_1
         #value_stream_2
-    });
+    })
+}
+
+// A repeated IE adds every occurrence to its `Vec`
+fn option_store(field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    if tlv_config.repeated {
+        quote! { #field_name.push(__decoded); }
+    } else {
+        quote! { #field_name = Some(__decoded); }
+    }
 }
 
 fn format_option_decode(
@@ -249,10 +945,13 @@ fn format_option_decode(
     field: Field,
     tlv_config: TlvConfig,
 ) -> Result<TokenStream, Error> {
-    // Option with TLV, TV, TLV-E are supported
+    // Option with TLV, TV, TLV-E, T, AVP and BER are supported
 
     match tlv_config.format.clone().as_str() {
         "TLV" | "TLV-E" => {
+            if tlv_config.end && !is_bool_type(&field.ty) {
+                abort_call_site!("`end` with `tag_bits` requires a `bool` field")
+            }
             let field_name = field.ident.unwrap();
             let _field_type = match field.ty {
                 Type::Path(type_path) => type_path.path,
@@ -260,15 +959,20 @@ fn format_option_decode(
                     abort_call_site!("Unsupported type in generic");
                 }
             };
+            if tlv_config.is_gathered() {
+                return Ok(gathered_instance_decode(&field_name, &tlv_config));
+            }
+            if tlv_config.end {
+                return Ok(packed_end_decode(&field_name, &tlv_config));
+            }
 
-            let tag_stream = tag_decode(&tlv_config);
-            let length_stream = length_decode(&tlv_config);
+            let decode_stream = tlv_decode_stream(quote! { #generic }, &field_name, &tlv_config);
+            let store_stream = option_store(&field_name, &tlv_config);
 
-            return Ok(quote! {
-                #tag_stream
-                #length_stream
-                #field_name = Some(<#generic>::decode(__actual_length, &mut __bytes.split_to(__actual_length))?);
-            });
+            Ok(quote! {
+                #decode_stream
+                #store_stream
+            })
         }
         "TV" => {
             let field_name = field.ident.unwrap();
@@ -282,44 +986,375 @@ fn format_option_decode(
             if tlv_config.tag_bytes_format == 0 {
                 // Its a 4bit tag 4bit valie case
                 let _tag = tlv_config.tag.expect("TAG is required to type Tv") as u8;
-                return Ok(quote! {
+                Ok(quote! {
                     #field_name = Some(<#generic>::from(__bytes.get_u8() & 0b00001111));
-                });
+                })
             } else {
                 // Its a 1 or more byte tag and 1 or mote byte value case
-                let tag_stream = tag_decode(&tlv_config);
-                let length = tlv_config.length.expect("LENGTH is required to type Tv") as usize;
-                return Ok(quote! {
+                let tag_stream = tag_decode(&tlv_config, &field_name);
+                let length = tlv_config.length.expect("LENGTH is required to type Tv");
+                let value_stream = value_decode(quote! { #generic }, &field_name, &tlv_config);
+                Ok(quote! {
                     #tag_stream
-                    #field_name = Some(<#generic>::decode(#length, &mut __bytes.split_to(#length))?);
-                });
+                    let __actual_length: usize = #length;
+                    let __wire_length: usize = #length;
+                    #value_stream
+                    #field_name = Some(__decoded);
+                })
             }
         }
+        "AVP" => {
+            let field_name = field.ident.unwrap();
+            let avp_stream = format_avp_decode(quote! { #generic }, &field_name, &tlv_config);
+            let store_stream = option_store(&field_name, &tlv_config);
+            Ok(quote! {
+                #avp_stream
+                #store_stream
+            })
+        }
+        "BER" => {
+            let field_name = field.ident.unwrap();
+            let ber_stream = format_ber_decode(quote! { #generic }, &field_name, &tlv_config);
+            let store_stream = option_store(&field_name, &tlv_config);
+            Ok(quote! {
+                #ber_stream
+                #store_stream
+            })
+        }
+        "T" => {
+            // Type 2 IE, presence is decided by the IEI alone
+            let field_name = field.ident.unwrap();
+            let tag_stream = tag_decode(&tlv_config, &field_name);
+            let present = if is_bool_type(&field.ty) {
+                quote! { true }
+            } else {
+                quote! { Some(()) }
+            };
+            Ok(quote! {
+                #tag_stream
+                #field_name = #present;
+            })
+        }
         _ => {
-            abort_call_site!("Option with TLV, TV, TLV-E are supported")
+            abort_call_site!("Option with TLV, TV, TLV-E, T, AVP, BER are supported")
         }
     }
 }
 
+// The end of the optional IEs in a packed header, the tag is already matched and the length
+// has to be zero
+fn packed_end_decode(field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let (_, length_bits) = tlv_config.packed_header().unwrap();
+    let header_octets = tlv_config.packed_header_octets();
+    let length_mask: u64 = (1 << length_bits) - 1;
+    quote! {
+        let __wire_length = (__bytes.get_uint(#header_octets) & #length_mask) as usize;
+        if __wire_length != 0 {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __wire_length,
+            });
+        }
+        #field_name = true;
+    }
+}
+
 fn init_option_decoder(
-    optional_tlvs: Vec<(GenericArgument, Field, TlvConfig)>,
+    struct_name: &Ident,
+    struct_config: &StructConfig,
+    optional_tlvs: Vec<(GenericArgument, Field, TlvConfig, TokenStream)>,
 ) -> Result<TokenStream, Error> {
     if optional_tlvs.is_empty() {
-        return Ok(quote! {});
+        // Nothing consumes the rest of the message, whatever is left is superfluous
+        let struct_name_str = struct_name.to_string();
+        return Ok(quote! {
+            if __options.is_strict() && __bytes.has_remaining() {
+                return Err(tlv::prelude::TlvError::TrailingBytes {
+                    field: #struct_name_str,
+                    remaining: __bytes.remaining(),
+                });
+            }
+        });
     }
 
-    let mut tag_8_bit_output_stream: Vec<TokenStream> = Vec::<TokenStream>::new();
+    let mut tag_output_stream: Vec<TokenStream> = Vec::<TokenStream>::new();
     let mut tag_4_bit_output_stream: Vec<TokenStream> = Vec::<TokenStream>::new();
 
-    for (opt_tlv_generic, opt_tlv_field, opt_tlv_tlv_config) in optional_tlvs {
+    // IEs with an instance are told apart by the type and the instance, and unknown ones
+    // are skipped with the GTPv2-C header instead of the TS 24.007 rules
+    let instance_count = optional_tlvs
+        .iter()
+        .filter(|(_, _, tlv_config, _)| tlv_config.instance.is_some())
+        .count();
+    if instance_count != 0 && instance_count != optional_tlvs.len() {
+        abort_call_site!("optional IEs with an `instance` can not be mixed with other optional IEs")
+    }
+    let avp_count = optional_tlvs
+        .iter()
+        .filter(|(_, _, tlv_config, _)| tlv_config.format == "AVP")
+        .count();
+    if avp_count != 0 && avp_count != optional_tlvs.len() {
+        abort_call_site!("optional AVPs can not be mixed with other optional IEs")
+    }
+    // BER identifiers are matched whole, whatever octets they take
+    let ber_count = optional_tlvs
+        .iter()
+        .filter(|(_, _, tlv_config, _)| tlv_config.format == "BER")
+        .count();
+    if ber_count != 0 && ber_count != optional_tlvs.len() {
+        abort_call_site!("optional BER elements can not be mixed with other optional IEs")
+    }
+    let packed_header = optional_tlvs[0].2.packed_header();
+    if optional_tlvs
+        .iter()
+        .any(|(_, _, tlv_config, _)| tlv_config.packed_header() != packed_header)
+    {
+        abort_call_site!("optional IEs should have the same `tag_bits` and `length_bits`")
+    }
+    let packed_header_octets = optional_tlvs[0].2.packed_header_octets();
+    // The end is the last field, the optional IEs before it are plain TLVs
+    let end = optional_tlvs
+        .iter()
+        .position(|(_, _, tlv_config, _)| tlv_config.end)
+        .map(|index| {
+            if index + 1 != optional_tlvs.len() {
+                abort_call_site!("`end` should be the last field")
+            }
+            let (_, field, tlv_config, _) = &optional_tlvs[index];
+            (field.ident.clone().unwrap().to_string(), tlv_config.pad)
+        });
+    let length_scale = (optional_tlvs[0].2.length_unit(), optional_tlvs[0].2.length_includes_header);
+    if optional_tlvs.iter().any(|(_, _, tlv_config, _)| {
+        (tlv_config.length_unit(), tlv_config.length_includes_header) != length_scale
+    }) {
+        abort_call_site!("optional IEs should have the same `length_unit` and `length_includes_header`")
+    }
+    let extended_length = optional_tlvs[0].2.extended_length;
+    if optional_tlvs
+        .iter()
+        .any(|(_, _, tlv_config, _)| tlv_config.extended_length != extended_length)
+    {
+        abort_call_site!("optional IEs should have the same `extended_length`")
+    }
+    // The tag is peeked with the width of the optional IEs, 4 bit IEIs share the octet
+    let tag_octets = optional_tlvs[0].2.tag_bytes_format.max(1);
+    if ber_count == 0
+        && optional_tlvs
+            .iter()
+            .any(|(_, _, tlv_config, _)| tlv_config.tag_bytes_format.max(1) != tag_octets)
+    {
+        abort_call_site!("optional IEs should have tags of the same width")
+    }
+    let skip_stream = if struct_config.is_ts24007() {
+        if packed_header.is_some()
+            || extended_length.is_some()
+            || avp_count != 0
+            || ber_count != 0
+            || optional_tlvs[0].2.is_scaled_length()
+            || instance_count != 0
+            || tag_octets > 1
+        {
+            abort_call_site!("`unknown_ie = \"ts24007\"` requires optional IEs with a 1 octet IEI")
+        }
+        quote! { tlv::prelude::skip_unknown_ie(__bytes)?; }
+    } else if let Some((_, length_bits)) = packed_header {
+        quote! { tlv::prelude::skip_packed_tlv(__bytes, #packed_header_octets, #length_bits)?; }
+    } else if let Some(extended_length) = extended_length {
+        quote! { tlv::prelude::skip_extended_length_tlv(__bytes, #extended_length)?; }
+    } else if avp_count != 0 {
+        quote! { tlv::prelude::skip_avp(__bytes)?; }
+    } else if ber_count != 0 {
+        quote! { tlv::prelude::skip_ber(__bytes)?; }
+    } else if optional_tlvs[0].2.is_scaled_length() {
+        let length_octets = optional_tlvs[0].2.length_bytes_format as usize;
+        let length_unit = optional_tlvs[0].2.length_unit();
+        let counted_header_octets = optional_tlvs[0].2.counted_header_octets();
+        let tag_octets = tag_octets as usize;
+        quote! {
+            tlv::prelude::skip_scaled_tlv(__bytes, #tag_octets, #length_octets, #length_unit, #counted_header_octets)?;
+        }
+    } else if instance_count != 0 {
+        quote! { tlv::prelude::skip_tlv(__bytes, 1, 2, 1)?; }
+    } else {
+        // Unknown IEs look like the known ones, e.g. RADIUS attributes, DHCP options and
+        // 802.11 elements. The PFCP enterprise ID is part of the value as far as the
+        // length goes.
+        let mut length_octets = optional_tlvs
+            .iter()
+            .filter(|(_, _, tlv_config, _)| !tlv_config.end)
+            .map(|(_, _, tlv_config, _)| match tlv_config.format.as_str() {
+                "TLV" | "TLV-E" => tlv_config.length_bytes_format,
+                _ => 0,
+            });
+        let first_length_octets = length_octets.next().unwrap_or(1);
+        if first_length_octets == 0 || length_octets.any(|octets| octets != first_length_octets) {
+            abort_call_site!(
+                "unknown optional IEs can only be skipped when all are TLVs with the same length width, IEs of TS 24.007 need `unknown_ie = \"ts24007\"` on the struct"
+            )
+        }
+        let length_octets = first_length_octets as usize;
+        let tag_octets = tag_octets as usize;
+        quote! { tlv::prelude::skip_tlv(__bytes, #tag_octets, #length_octets, 0)?; }
+    };
+
+    let mut gathered_declare_stream = Vec::<TokenStream>::new();
+    let mut gathered_value_stream = Vec::<TokenStream>::new();
+
+    // Position of every optional IE in the message table, used for the order check
+    for (opt_index, (opt_tlv_generic, opt_tlv_field, opt_tlv_tlv_config, context_stream)) in
+        optional_tlvs.into_iter().enumerate()
+    {
+        if opt_tlv_tlv_config.is_gathered() {
+            let field_name = opt_tlv_field.ident.clone().unwrap();
+            let gathered_name = gathered_name(&field_name);
+            gathered_declare_stream.push(quote! {
+                let mut #gathered_name: Option<BytesMut> = None;
+            });
+            gathered_value_stream.push(gathered_value_decode(
+                quote! { #opt_tlv_generic },
+                &field_name,
+                &opt_tlv_tlv_config,
+            ));
+        }
         let opt_tag = opt_tlv_tlv_config
             .tag
             .expect("TAG is required for optional tlvs");
-        let format_option_decode_stream =
+        let instance = opt_tlv_tlv_config.instance;
+        let enterprise_id = opt_tlv_tlv_config.enterprise_id;
+        let vendor_id = opt_tlv_tlv_config.vendor_id;
+        let extended_type = opt_tlv_tlv_config.extended_type;
+        let oui_subtype = opt_tlv_tlv_config.oui_subtype();
+        let opt_tlv_format = opt_tlv_tlv_config.format.clone();
+        let tag_bytes_format = opt_tlv_tlv_config.tag_bytes_format;
+        let length_bytes_format = opt_tlv_tlv_config.length_bytes_format;
+        let field_name = opt_tlv_field.ident.clone().unwrap();
+        let field_name_str = field_name.to_string();
+        let is_present = if is_bool_type(&opt_tlv_field.ty) {
+            quote! { #field_name }
+        } else {
+            quote! { #field_name.is_some() }
+        };
+        let end_break = if opt_tlv_tlv_config.end {
+            quote! { break; }
+        } else {
+            quote! {}
+        };
+        let is_repeated = opt_tlv_tlv_config.repeated;
+        let decode_stream =
             format_option_decode(opt_tlv_generic, opt_tlv_field, opt_tlv_tlv_config).unwrap();
+        let order_stream = if struct_config.unordered {
+            quote! {}
+        } else {
+            quote! {
+                if #opt_index < __last_ie_index {
+                    match __options.out_of_order_ie {
+                        tlv::prelude::OutOfOrderIe::Accept => {}
+                        tlv::prelude::OutOfOrderIe::Error => {
+                            return Err(tlv::prelude::TlvError::OutOfOrderIe { field: #field_name_str });
+                        }
+                        tlv::prelude::OutOfOrderIe::Warn(__warn) => __warn(#field_name_str, #opt_tag),
+                    }
+                }
+                __last_ie_index = __last_ie_index.max(#opt_index);
+            }
+        };
+        let format_option_decode_stream = if is_repeated {
+            quote! {
+                #order_stream
+                #context_stream
+                #decode_stream
+            }
+        } else {
+            quote! {
+                let __is_duplicate = #is_present;
+                if __is_duplicate {
+                    if let tlv::prelude::DuplicateIe::Error = __options.duplicate_ie {
+                        return Err(tlv::prelude::TlvError::DuplicateIe { field: #field_name_str });
+                    }
+                } else {
+                    #order_stream
+                }
+                // The repeated IE still has to be consumed, the first occurrence is kept
+                let __first_ie = ::std::mem::take(&mut #field_name);
+                #context_stream
+                #decode_stream
+                if __is_duplicate {
+                    #field_name = __first_ie;
+                }
+                #end_break
+            }
+        };
 
-        if opt_tag > 0xf {
-            tag_8_bit_output_stream.push(quote! {
+        if let Some(oui_subtype) = oui_subtype {
+            // After the packed header of an LLDP TLV or the tag and length of an 802.11 element
+            let offset = if packed_header.is_some() {
+                packed_header_octets
+            } else {
+                (tag_bytes_format + length_bytes_format) as usize
+            };
+            tag_output_stream.push(quote! {
+                #opt_tag if __bytes
+                    .get(#offset..#offset + 4)
+                    .map(|__id| u32::from_be_bytes([__id[0], __id[1], __id[2], __id[3]]))
+                    == Some(#oui_subtype) => {
+                    #format_option_decode_stream
+                }
+            });
+        } else if opt_tlv_format == "AVP" {
+            // AVPs of a vendor have the V flag and the Vendor-ID after the length
+            let vendor_guard = match vendor_id {
+                Some(vendor_id) => quote! {
+                    __bytes.get(4).is_some_and(|__flags| __flags & 0x80 != 0)
+                        && __bytes.get(8..12).map(|__id| u32::from_be_bytes([__id[0], __id[1], __id[2], __id[3]]))
+                            == Some(#vendor_id)
+                },
+                None => quote! {
+                    __bytes.get(4).is_some_and(|__flags| __flags & 0x80 == 0)
+                },
+            };
+            tag_output_stream.push(quote! {
+                #opt_tag if #vendor_guard => {
+                    #format_option_decode_stream
+                }
+            });
+        } else if let Some(vendor_id) = vendor_id {
+            // A Vendor-Specific attribute, the Vendor-Id follows the length
+            let offset = (tag_bytes_format + length_bytes_format) as usize;
+            tag_output_stream.push(quote! {
+                #opt_tag if __bytes
+                    .get(#offset..#offset + 4)
+                    .map(|__id| u32::from_be_bytes([__id[0], __id[1], __id[2], __id[3]]))
+                    == Some(#vendor_id) => {
+                    #format_option_decode_stream
+                }
+            });
+        } else if let Some(extended_type) = extended_type {
+            let offset = (tag_bytes_format + length_bytes_format) as usize;
+            tag_output_stream.push(quote! {
+                #opt_tag if __bytes.get(#offset) == Some(&#extended_type) => {
+                    #format_option_decode_stream
+                }
+            });
+        } else if let Some(enterprise_id) = enterprise_id {
+            let offset = (tag_bytes_format + length_bytes_format) as usize;
+            tag_output_stream.push(quote! {
+                #opt_tag if __bytes
+                    .get(#offset..#offset + 2)
+                    .map(|__id| u16::from_be_bytes([__id[0], __id[1]]))
+                    == Some(#enterprise_id) => {
+                    #format_option_decode_stream
+                }
+            });
+        } else if let Some(instance) = instance {
+            let offset = (tag_bytes_format + length_bytes_format) as usize;
+            tag_output_stream.push(quote! {
+                #opt_tag if __bytes.get(#offset).map(|__octet| __octet & 0x0F) == Some(#instance) => {
+                    #format_option_decode_stream
+                }
+            });
+        } else if tag_bytes_format != 0 {
+            tag_output_stream.push(quote! {
                 #opt_tag => {
                     #format_option_decode_stream
                 }
@@ -333,73 +1368,207 @@ fn init_option_decoder(
         }
     }
 
-    let tag_4_bit_extension_stream: TokenStream = if tag_4_bit_output_stream.len() != 0 {
-        quote! {
-            let __4bitTag: u8 = __tag >> 4;
+    // The mode decides on unknown IEs, unless the protocol tells by the tag
+    let unknown_stream = match struct_config.skippable_from {
+        Some(skippable_from) => quote! {
+            if (__tag as usize) < #skippable_from {
+                return Err(tlv::prelude::TlvError::UnknownTag { tag: __tag as usize });
+            }
+            #skip_stream
+        },
+        None => quote! {
+            if __options.is_strict() {
+                return Err(tlv::prelude::TlvError::UnknownTag { tag: __tag as usize });
+            }
+            #skip_stream
+        },
+    };
 
-            if (__tag >= 0x80) {
+    // Exact IEIs (including the type 2 ones above 0x80) win over the 4bit IEIs
+    let tag_4_bit_fallback_stream: TokenStream = if !tag_4_bit_output_stream.is_empty() {
+        quote! {
+            _ if __tag >= 0x80 => {
                 // Tag is 4bit
+                let __4bitTag: u8 = __tag >> 4;
                 match __4bitTag as usize {
                     #(#tag_4_bit_output_stream)*
                     _ => {
-                        // Currently panicing for unknown tag, a better impl is required
-                        ::std::panic!("Unknown 4bit tag in Optional TLV parsing")
+                        #unknown_stream
                     }
                 }
-                continue;
             }
         }
     } else {
         quote! {}
     };
 
+    let peek_stream = if let Some((_, length_bits)) = packed_header {
+        quote! {
+            if __bytes.remaining() < #packed_header_octets {
+                return Err(tlv::prelude::TlvError::MalformedPayload);
+            }
+            let __tag = (&__bytes[..]).get_uint(#packed_header_octets) >> #length_bits;
+        }
+    } else if ber_count != 0 {
+        quote! {
+            let Some((__tag, _)) = tlv::prelude::peek_ber_tag(__bytes) else {
+                return Err(tlv::prelude::TlvError::MalformedPayload);
+            };
+        }
+    } else if extended_length.is_some() {
+        // The tag comes after the flags
+        quote! {
+            let __tag: u8 = *__bytes.get(1).ok_or(tlv::prelude::TlvError::MalformedPayload)?;
+        }
+    } else if tag_octets > 1 {
+        let get_bytes = get_get_bytes(tag_octets);
+        quote! {
+            if __bytes.remaining() < #tag_octets as usize {
+                return Err(tlv::prelude::TlvError::MalformedPayload);
+            }
+            let __tag = (&__bytes[..]).#get_bytes();
+        }
+    } else {
+        quote! {
+            let __tag: u8 = *__bytes.chunk().first().ok_or(TlvError::Unknown)?;
+        }
+    };
+
+    let (pad_stream, end_stream) = match end {
+        Some((field_name_str, pad)) => {
+            let pad_stream = match pad {
+                Some(pad) => quote! {
+                    if __tag as usize == #pad as usize {
+                        __bytes.advance(1);
+                        continue;
+                    }
+                },
+                None => quote! {},
+            };
+            // Only padding may follow the end, zeros padding the frame after a packed header
+            let is_trailing = match (pad, packed_header) {
+                (Some(pad), _) => quote! { __bytes.iter().any(|__octet| *__octet != #pad) },
+                (None, Some(_)) => quote! { __bytes.iter().any(|__octet| *__octet != 0) },
+                (None, None) => quote! { __bytes.has_remaining() },
+            };
+            let end_stream = quote! {
+                if __options.is_strict() && #is_trailing {
+                    return Err(tlv::prelude::TlvError::TrailingBytes {
+                        field: #field_name_str,
+                        remaining: __bytes.remaining(),
+                    });
+                }
+                __bytes.advance(__bytes.remaining());
+            };
+            (pad_stream, end_stream)
+        }
+        None => (quote! {}, quote! {}),
+    };
+
+    let last_ie_index_stream = if struct_config.unordered {
+        quote! {}
+    } else {
+        quote! { let mut __last_ie_index: usize = 0; }
+    };
+
     Ok(quote! {
+        #last_ie_index_stream
+        #(#gathered_declare_stream)*
 
         while __bytes.remaining() != 0 {
-            let __tag: u8 = *__bytes.chunk().first().ok_or(TlvError::Unknown)?;
+            #peek_stream
+            #pad_stream
 
-            #tag_4_bit_extension_stream
-
-            // Tag is 8bit
             match __tag as usize {
-                #(#tag_8_bit_output_stream)*
+                #(#tag_output_stream)*
+                #tag_4_bit_fallback_stream
                 _ => {
-                    // Currently panicing for unknown tag, a better impl is required
-                    ::std::panic!("Unknown 8bit tag in Optional TLV parsing")
+                    #unknown_stream
                 }
             }
-            // match __4bitTag as usize {
-            //     #(#output_stream)*
-            //     _ => {
-            //         match __tag as usize {
-            //             #(#output_stream)*
-            //             _ => {
-            //                 // Currently panicing for unknown tag, a better impl is required
-            //                 ::std::panic!("Unknown tag in Optional TLV parsing")
-            //             }
-            //         }
-            //     }
-            // }
         }
+        #end_stream
+        #(#gathered_value_stream)*
     })
 }
 
-fn impl_tlv_decode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenStream, Error> {
+fn impl_tlv_decode(
+    struct_name: Ident,
+    struct_config: StructConfig,
+    data_struct: DataStruct,
+) -> Result<TokenStream, Error> {
     let mut output_stream = Vec::<TokenStream>::new();
     let mut field_names = Vec::<Ident>::new();
 
-    let mut optional_tlvs: Vec<(GenericArgument, Field, TlvConfig)> =
-        Vec::<(GenericArgument, Field, TlvConfig)>::new();
+    let mut optional_tlvs: Vec<(GenericArgument, Field, TlvConfig, TokenStream)> =
+        Vec::<(GenericArgument, Field, TlvConfig, TokenStream)>::new();
+    // Fields decoded so far and whether they are optional, for `depends_on`
+    let mut declared_fields = Vec::<(String, bool)>::new();
 
-    let mut temp_first_value_of_4bit_value: Option<Field> = None;
+    let mut temp_first_value_of_4bit_value: Option<(Field, TlvConfig)> = None;
     let mut is_4bit_value_packed = true;
 
     let mut has_optional_fields_started = false;
+    let mut has_length_driven_fields = false;
+    let mut bit_fields = Vec::<(Field, TlvConfig)>::new();
+    let mut bit_count = 0u32;
 
     for field in data_struct.fields {
         let field_name = field.clone().ident.unwrap();
         field_names.push(field_name.clone());
-        let tlv_config = TlvConfig::from_attributes(field.attrs.clone())?;
+        let tlv_config = TlvConfig::parse(field.attrs.clone())?;
+        let context_stream = context_decode(&tlv_config, &declared_fields);
+        declared_fields.push((
+            field_name.to_string(),
+            get_option_inner_type(&field.ty).is_some(),
+        ));
+
+        if let Some(bits) = tlv_config.bits {
+            if has_optional_fields_started || !is_4bit_value_packed {
+                abort_call_site!("`bits` fields should be mandatory and not mixed with half octets")
+            }
+            bit_fields.push((field, tlv_config));
+            bit_count += bits as u32;
+            if bit_count.is_multiple_of(8) {
+                output_stream.push(format_bits_decode(std::mem::take(&mut bit_fields))?);
+                bit_count = 0;
+            }
+            continue;
+        }
+        if !bit_fields.is_empty() {
+            abort_call_site!("`bits` fields should fill whole octets")
+        }
+
+        if !tlv_config.present_if_length.is_empty() {
+            if has_optional_fields_started || !is_4bit_value_packed {
+                abort_call_site!("`present_if_length` fields should follow the mandatory fields")
+            }
+            has_length_driven_fields = true;
+            output_stream.push(context_stream);
+            output_stream.push(format_length_driven_decode(field, tlv_config)?);
+            continue;
+        }
+
+        if tlv_config.repeated {
+            let Some(inner_type) = get_vec_inner_type(&field.ty) else {
+                abort_call_site!("`repeated` fields should be a `Vec`")
+            };
+            has_optional_fields_started = true;
+            output_stream.push(quote! {
+                let mut #field_name: Vec<#inner_type> = Vec::new();
+            });
+            optional_tlvs.push((GenericArgument::Type(inner_type), field.clone(), tlv_config, context_stream));
+            continue;
+        }
+
+        if is_bool_type(&field.ty) && (tlv_config.format == "T" || tlv_config.end) {
+            has_optional_fields_started = true;
+            optional_tlvs.push((syn::parse_quote!(bool), field.clone(), tlv_config, context_stream));
+            output_stream.push(quote! {
+                let mut #field_name: bool = false;
+            });
+            continue;
+        }
 
         match field.clone().ty {
             Type::Path(type_path) => {
@@ -412,7 +1581,12 @@ fn impl_tlv_decode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
                         if args.args.len() == 1 {
                             has_optional_fields_started = true;
                             let inner_type = &args.args[0];
-                            optional_tlvs.push((inner_type.clone(), field.clone(), tlv_config));
+                            optional_tlvs.push((
+                                inner_type.clone(),
+                                field.clone(),
+                                tlv_config,
+                                context_stream,
+                            ));
                             output_stream.push(quote! {
                                 let mut #field_name: Option<#inner_type> = None;
                             });
@@ -433,22 +1607,21 @@ fn impl_tlv_decode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
         if has_optional_fields_started {
             abort_call_site!("Optional Fields should be the at the last")
         }
+        output_stream.push(context_stream);
 
         match tlv_config.format.clone().as_str() {
             "V" => {
                 if tlv_config.value_bytes_format == 0 {
                     if is_4bit_value_packed {
-                        temp_first_value_of_4bit_value = Some(field);
+                        temp_first_value_of_4bit_value = Some((field, tlv_config));
                         is_4bit_value_packed = false;
                         continue;
                     }
+                    let (first_field, first_tlv_config) =
+                        temp_first_value_of_4bit_value.take().unwrap();
                     output_stream.push(
-                        format_4bit_v_decode(
-                            temp_first_value_of_4bit_value.clone().unwrap(),
-                            field,
-                            tlv_config,
-                        )
-                        .unwrap(),
+                        format_4bit_v_decode(first_field, first_tlv_config, field, tlv_config)
+                            .unwrap(),
                     );
                     is_4bit_value_packed = true;
                 } else {
@@ -473,11 +1646,30 @@ fn impl_tlv_decode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
                 }
                 output_stream.push(format_tv_decode(field, tlv_config).unwrap());
             }
-            "T" => {
+            "AVP" => {
                 if !is_4bit_value_packed {
                     abort_call_site!("Two 4bit value should be consecutive")
                 }
-                output_stream.push(format_t_decode(field, tlv_config).unwrap());
+                let field_type = field.ty;
+                let avp_stream = format_avp_decode(quote! { #field_type }, &field_name, &tlv_config);
+                output_stream.push(quote! {
+                    #avp_stream
+                    let #field_name = __decoded;
+                });
+            }
+            "BER" => {
+                if !is_4bit_value_packed {
+                    abort_call_site!("Two 4bit value should be consecutive")
+                }
+                let field_type = field.ty;
+                let ber_stream = format_ber_decode(quote! { #field_type }, &field_name, &tlv_config);
+                output_stream.push(quote! {
+                    #ber_stream
+                    let #field_name = __decoded;
+                });
+            }
+            "T" => {
+                abort_call_site!("T carries no value, it should be a `bool` or an `Option<()>`")
             }
             _ => {
                 abort_call_site!("Unkown TLV format")
@@ -485,11 +1677,48 @@ fn impl_tlv_decode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
         }
     }
 
-    output_stream.push(init_option_decoder(optional_tlvs).unwrap());
+    if !bit_fields.is_empty() {
+        abort_call_site!("`bits` fields should fill whole octets")
+    }
+
+    if has_length_driven_fields {
+        if !optional_tlvs.is_empty() {
+            abort_call_site!("`present_if_length` can not be combined with optional IEs")
+        }
+        let struct_name_str = struct_name.to_string();
+        let invalid_length = quote! {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #struct_name_str,
+                length,
+            });
+        };
+        output_stream.insert(0, quote! {
+            if __bytes.remaining() < length {
+                #invalid_length
+            }
+            let __start_remaining = __bytes.remaining();
+        });
+        // No combination of the present fields adds up to the given length
+        output_stream.push(quote! {
+            if __start_remaining - __bytes.remaining() != length {
+                #invalid_length
+            }
+        });
+    }
+
+    output_stream.push(init_option_decoder(&struct_name, &struct_config, optional_tlvs).unwrap());
 
     Ok(quote! {
         impl TlvDecode for #struct_name {
             fn decode(length: usize, __bytes: &mut Bytes) -> Result<Self, tlv::prelude::TlvError> {
+                Self::decode_with_options(length, __bytes, &tlv::prelude::DecodeOptions::default())
+            }
+
+            fn decode_with_options(
+                length: usize,
+                __bytes: &mut Bytes,
+                __options: &tlv::prelude::DecodeOptions,
+            ) -> Result<Self, tlv::prelude::TlvError> {
                 #(#output_stream)*
                 Ok(#struct_name{
                     #(#field_names),*
@@ -503,15 +1732,93 @@ fn impl_newtype_decode(struct_name: Ident) -> Result<TokenStream, Error> {
     Ok(quote! {
         impl TlvDecode for #struct_name {
             fn decode(length: usize, __bytes: &mut Bytes) -> Result<Self, tlv::prelude::TlvError> {
-                let inner = <_>::decode(length, __bytes)?;
+                Self::decode_with_options(length, __bytes, &tlv::prelude::DecodeOptions::default())
+            }
+
+            fn decode_with_options(
+                length: usize,
+                __bytes: &mut Bytes,
+                __options: &tlv::prelude::DecodeOptions,
+            ) -> Result<Self, tlv::prelude::TlvError> {
+                let inner = <_>::decode_with_options(length, __bytes, __options)?;
                 Ok(#struct_name(inner))
             }
         }
     })
 }
 
+// The tag is consumed here, the wrapped type decodes everything after it. With a
+// `tag_mask` the tag only shares the first octets with the value, so it is peeked and
+// the wrapped type decodes those octets as well, unit variants still consume it.
+fn impl_enum_decode(enum_name: Ident, data_enum: DataEnum) -> Result<TokenStream, Error> {
+    let mut variant_streams = Vec::<TokenStream>::new();
+    let variants = get_enum_variants(&data_enum);
+    let tag_bytes_format = variants
+        .first()
+        .map_or(1, |(_, _, tlv_config)| tlv_config.tag_bytes_format);
+    let get_bytes = get_get_bytes(tag_bytes_format);
+    let is_masked = variants
+        .first()
+        .is_some_and(|(_, _, tlv_config)| tlv_config.tag_mask.is_some());
+
+    for (variant_name, inner_type, tlv_config) in variants {
+        let tag = tlv_config.tag.unwrap();
+        let pattern = match tlv_config.tag_mask {
+            Some(tag_mask) => quote! { __tag if __tag & #tag_mask == #tag },
+            None => quote! { #tag },
+        };
+        match inner_type {
+            Some(inner_type) => variant_streams.push(quote! {
+                #pattern => Ok(Self::#variant_name(<#inner_type>::decode_with_options(
+                    __bytes.remaining(),
+                    __bytes,
+                    __options,
+                )?)),
+            }),
+            None if is_masked => variant_streams.push(quote! {
+                #pattern => {
+                    __bytes.advance(#tag_bytes_format as usize);
+                    Ok(Self::#variant_name)
+                }
+            }),
+            None => variant_streams.push(quote! {
+                #pattern => Ok(Self::#variant_name),
+            }),
+        }
+    }
+
+    let tag_stream = if is_masked {
+        quote! { let __tag = (&__bytes[..]).#get_bytes() as usize; }
+    } else {
+        quote! { let __tag = __bytes.#get_bytes() as usize; }
+    };
+
+    Ok(quote! {
+        impl TlvDecode for #enum_name {
+            fn decode(length: usize, __bytes: &mut Bytes) -> Result<Self, tlv::prelude::TlvError> {
+                Self::decode_with_options(length, __bytes, &tlv::prelude::DecodeOptions::default())
+            }
+
+            fn decode_with_options(
+                _length: usize,
+                __bytes: &mut Bytes,
+                __options: &tlv::prelude::DecodeOptions,
+            ) -> Result<Self, tlv::prelude::TlvError> {
+                if __bytes.remaining() < #tag_bytes_format as usize {
+                    return Err(tlv::prelude::TlvError::MalformedPayload);
+                }
+                #tag_stream
+                match __tag {
+                    #(#variant_streams)*
+                    _ => Err(tlv::prelude::TlvError::UnknownTag { tag: __tag }),
+                }
+            }
+        }
+    })
+}
+
 pub(crate) fn tlv_decode(token_stream: TokenStream) -> Result<TokenStream, Error> {
-    let DeriveInput { data, .. } = syn::parse2(token_stream.clone())?;
+    let DeriveInput { attrs, data, .. } = syn::parse2(token_stream.clone())?;
     let struct_name = get_struct_name(token_stream.clone());
 
     match data {
@@ -519,11 +1826,12 @@ pub(crate) fn tlv_decode(token_stream: TokenStream) -> Result<TokenStream, Error
             if is_newtype(&data_struct) {
                 impl_newtype_decode(struct_name)
             } else {
-                impl_tlv_decode(struct_name, data_struct)
+                impl_tlv_decode(struct_name, StructConfig::parse(attrs)?, data_struct)
             }
         }
+        syn::Data::Enum(data_enum) => impl_enum_decode(struct_name, data_enum),
         _ => {
-            abort_call_site!("Currently only structs are supported");
+            abort_call_site!("Currently only structs and enums are supported");
         }
     }
 }
@@ -1,11 +1,10 @@
-use crate::tlv_config::{get_bytes_format, get_put_bytes, TlvConfig};
-use crate::utils::{get_struct_name, is_newtype};
-use attribute_derive::Attribute;
+use crate::tlv_config::{get_bits_type, get_bytes_format, get_put_bytes, TlvConfig};
+use crate::utils::{get_enum_variants, get_option_inner_type, get_struct_name, is_bool_type, is_newtype};
 use attribute_derive::__private::proc_macro2;
 use proc_macro2::{Ident, TokenStream};
 use proc_macro_error::abort_call_site;
 use quote::quote;
-use syn::{DataStruct, DeriveInput, Error, Type};
+use syn::{DataEnum, DataStruct, DeriveInput, Error, Type};
 
 fn tag_encode(tlv_config: &TlvConfig) -> TokenStream {
     if tlv_config.tag_bytes_format == 0 {
@@ -50,6 +49,33 @@ fn length_encode(tlv_config: &TlvConfig) -> TokenStream {
     }
 }
 
+// The spare nibble next to the instance is always written as zero
+fn extra_header_encode(tlv_config: &TlvConfig) -> TokenStream {
+    if let Some(instance) = tlv_config.instance {
+        quote! {
+            __bytes.put_u8(#instance);
+        }
+    } else if let Some(enterprise_id) = tlv_config.enterprise_id {
+        quote! {
+            __bytes.put_u16(#enterprise_id);
+        }
+    } else if let Some(vendor_id) = tlv_config.vendor_id {
+        quote! {
+            __bytes.put_u32(#vendor_id);
+        }
+    } else if let Some(extended_type) = tlv_config.extended_type {
+        quote! {
+            __bytes.put_u8(#extended_type);
+        }
+    } else if let Some(oui_subtype) = tlv_config.oui_subtype() {
+        quote! {
+            __bytes.put_u32(#oui_subtype);
+        }
+    } else {
+        quote! {}
+    }
+}
+
 fn fix_length_parameter(tlv_config: &TlvConfig) -> TokenStream {
     if tlv_config.length_bytes_format == 0 {
         return quote! {};
@@ -66,7 +92,30 @@ fn fix_length_parameter(tlv_config: &TlvConfig) -> TokenStream {
     }
 }
 
-fn fix_length_encode(tlv_config: &TlvConfig) -> TokenStream {
+// The `min_length` and `max_length` checks of the decoder, e.g. from the `min_octets` and
+// `max_octets` of an `ie_type`, on the length it would find on the wire. Nothing is encoded
+// that the strict decoder rejects.
+fn length_bounds_encode(tlv_config: &TlvConfig, field_name_str: &str, wire_length: TokenStream) -> TokenStream {
+    if tlv_config.min_length.is_none() && tlv_config.max_length.is_none() {
+        return quote! {};
+    }
+    let min = tlv_config.min_length.unwrap_or(0);
+    let max = match tlv_config.max_length {
+        Some(max) => quote! { #max },
+        None => quote! { usize::MAX },
+    };
+    quote! {
+        let __wire_length: usize = #wire_length;
+        if !(#min..=#max).contains(&__wire_length) {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __wire_length,
+            });
+        }
+    }
+}
+
+fn fix_length_encode(tlv_config: &TlvConfig, field_name: &Ident) -> TokenStream {
     if tlv_config.length_bytes_format == 0 {
         return quote! {};
     }
@@ -74,28 +123,182 @@ fn fix_length_encode(tlv_config: &TlvConfig) -> TokenStream {
         Some(_) => {
             quote! {}
         }
+        None if tlv_config.is_scaled_length() => {
+            // Padded to whole units, the length counts the units
+            let length_bytes_format = tlv_config.length_bytes_format as usize;
+            let length_unit = tlv_config.length_unit();
+            let decoded_header_octets = tlv_config.counted_header_octets();
+            let counted_header_octets = decoded_header_octets + tlv_config.counted_extra_octets();
+            let field_name_str = field_name.to_string();
+            let padding_stream = if length_unit > 1 {
+                quote! {
+                    let __padding = (#length_unit - (__actual_length + #counted_header_octets) % #length_unit) % #length_unit;
+                    __bytes.put_bytes(0, __padding);
+                    __total_length += __padding;
+                }
+            } else {
+                quote! {
+                    let __padding = 0usize;
+                }
+            };
+            // The decoder takes only its own header octets off the units
+            let bounds_stream = length_bounds_encode(
+                tlv_config,
+                &field_name_str,
+                quote! { __fix_length * #length_unit - #decoded_header_octets },
+            );
+            quote! {
+                #padding_stream
+                let __fix_length = (__actual_length + #counted_header_octets + __padding) / #length_unit;
+                if (__fix_length as u64) >> (8 * #length_bytes_format) != 0 {
+                    return Err(tlv::prelude::TlvError::InvalidLength {
+                        field: #field_name_str,
+                        length: __actual_length,
+                    });
+                }
+                #bounds_stream
+                __bytes[__fix_length_index..__fix_length_index + #length_bytes_format]
+                    .copy_from_slice(&(__fix_length as u64).to_be_bytes()[8 - #length_bytes_format..]);
+            }
+        }
         None => {
             let length_bytes_format = tlv_config.length_bytes_format;
             let bytes_format = get_bytes_format(length_bytes_format);
+            // The PFCP length counts the enterprise ID, unlike the GTPv2-C instance octet
+            let counted_octets = tlv_config.counted_extra_octets();
+            let field_name_str = field_name.to_string();
+            let bounds_stream =
+                length_bounds_encode(tlv_config, &field_name_str, quote! { __fix_length as usize });
             quote! {
-                let __fix_length = __actual_length as #bytes_format;
+                let Ok(__fix_length) = #bytes_format::try_from(__actual_length + #counted_octets) else {
+                    return Err(tlv::prelude::TlvError::InvalidLength {
+                        field: #field_name_str,
+                        length: __actual_length,
+                    });
+                };
+                #bounds_stream
                 __bytes[__fix_length_index..__fix_length_index + #length_bytes_format as usize].copy_from_slice(&__fix_length.to_be_bytes());
             }
         }
     }
 }
 
+// The header is written once the value length is known, the tag goes in the most
+// significant bits. The OUI and subtype are counted by the length.
+fn format_packed_tlv_encode(value: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let (_, length_bits) = tlv_config.packed_header().unwrap();
+    let header_octets = tlv_config.packed_header_octets();
+    let tag = tlv_config.tag.unwrap() as u64;
+    let length_mask = (1u64 << length_bits) - 1;
+    let (oui_subtype_stream, oui_subtype_octets) = match tlv_config.oui_subtype() {
+        Some(oui_subtype) => (quote! { __bytes.put_u32(#oui_subtype); }, 4usize),
+        None => (quote! {}, 0usize),
+    };
+    quote! {
+        let __fix_length_index = __bytes.len();
+        __bytes.put_uint(0, #header_octets);
+        #oui_subtype_stream
+        let __actual_length = #value.encode(__bytes)?;
+        let __length = (__actual_length + #oui_subtype_octets) as u64;
+        if __length > #length_mask {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __length as usize,
+            });
+        }
+        let __header = #tag << #length_bits | __length;
+        __bytes[__fix_length_index..__fix_length_index + #header_octets]
+            .copy_from_slice(&__header.to_be_bytes()[8 - #header_octets..]);
+        __total_length += #header_octets + __length as usize;
+    }
+}
+
+// The header width depends on the value length, so the value is encoded first. The
+// `extended_length` flag is only set for values which do not fit a 1 octet length.
+fn format_extended_length_encode(value: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let tag = tlv_config.tag.unwrap() as u8;
+    let extended_length = tlv_config.extended_length.unwrap();
+    let flags = tlv_config.flags.unwrap_or(0);
+    quote! {
+        let mut __value_bytes = BytesMut::new();
+        let __actual_length = #value.encode(&mut __value_bytes)?;
+        if __actual_length > u16::MAX as usize {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __actual_length,
+            });
+        }
+        if __actual_length > u8::MAX as usize {
+            __bytes.put_u8(#flags | #extended_length);
+            __bytes.put_u8(#tag);
+            __bytes.put_u16(__actual_length as u16);
+            __total_length += 4;
+        } else {
+            __bytes.put_u8(#flags);
+            __bytes.put_u8(#tag);
+            __bytes.put_u8(__actual_length as u8);
+            __total_length += 3;
+        }
+        __bytes.put(__value_bytes);
+        __total_length += __actual_length;
+    }
+}
+
+// Values longer than an attribute can hold are split across consecutive attributes of the
+// same type, each with its own Extended-Type. A Long Extended Type has the More flag set on
+// all but the last one. An empty value still takes one attribute.
+fn format_fragmented_encode(value: TokenStream, tlv_config: &TlvConfig) -> TokenStream {
+    let tag = tlv_config.tag.unwrap() as u8;
+    let counted_octets = tlv_config.counted_header_octets() + tlv_config.counted_extra_octets();
+    let max_fragment = 0xFF - counted_octets;
+    let header_octets = tlv_config.header_octets() as usize;
+    let extra_header_stream = match tlv_config.extended_type {
+        Some(extended_type) => quote! {
+            __bytes.put_u8(#extended_type);
+            __bytes.put_u8(if __index + 1 < __fragments { 0x80 } else { 0x00 });
+        },
+        None => quote! {},
+    };
+    quote! {
+        let mut __value_bytes = BytesMut::new();
+        #value.encode(&mut __value_bytes)?;
+        let __fragments = __value_bytes.len().div_ceil(#max_fragment).max(1);
+        for __index in 0..__fragments {
+            let __fragment = &__value_bytes
+                [__index * #max_fragment..((__index + 1) * #max_fragment).min(__value_bytes.len())];
+            __bytes.put_u8(#tag);
+            __bytes.put_u8((__fragment.len() + #counted_octets) as u8);
+            #extra_header_stream
+            __bytes.put_slice(__fragment);
+            __total_length += #header_octets + __fragment.len();
+        }
+    }
+}
+
 fn format_tlv_encode(field_name: Ident, tlv_config: TlvConfig) -> Result<TokenStream, Error> {
+    if tlv_config.fragmented {
+        return Ok(format_fragmented_encode(quote! { self.#field_name }, &tlv_config));
+    }
+    if tlv_config.packed_header().is_some() {
+        return Ok(format_packed_tlv_encode(quote! { self.#field_name }, &field_name, &tlv_config));
+    }
+    if tlv_config.extended_length.is_some() {
+        return Ok(format_extended_length_encode(quote! { self.#field_name }, &field_name, &tlv_config));
+    }
     let tag_stream = tag_encode(&tlv_config);
     let fix_length_parameter_stream = fix_length_parameter(&tlv_config);
     let length_stream = length_encode(&tlv_config);
-    let header_size_bytes = tlv_config.tag_bytes_format + tlv_config.length_bytes_format;
-    let fix_length_stream = fix_length_encode(&tlv_config);
+    let extra_header_stream = extra_header_encode(&tlv_config);
+    let header_size_bytes = tlv_config.header_octets();
+    let fix_length_stream = fix_length_encode(&tlv_config, &field_name);
 
     Ok(quote! {
         #tag_stream
         #fix_length_parameter_stream
         #length_stream
+        #extra_header_stream
         __total_length += #header_size_bytes as usize;
         let __actual_length = self.#field_name.encode(__bytes)?;
         __total_length += __actual_length as usize;
@@ -107,7 +310,7 @@ fn format_lv_encode(field_name: Ident, tlv_config: TlvConfig) -> Result<TokenStr
     let fix_length_parameter_stream = fix_length_parameter(&tlv_config);
     let length_stream = length_encode(&tlv_config);
     let header_size_bytes = tlv_config.length_bytes_format;
-    let fix_length_stream = fix_length_encode(&tlv_config);
+    let fix_length_stream = fix_length_encode(&tlv_config, &field_name);
 
     Ok(quote! {
         #fix_length_parameter_stream
@@ -132,44 +335,111 @@ fn format_tv_encode(field_name: Ident, tlv_config: TlvConfig) -> Result<TokenStr
         let value_stream: TokenStream = quote! {
             let __value: u8 = self.#field_name.to_be();
         };
-        return Ok(quote! {
+        Ok(quote! {
             #tag_stream
             __total_length += #header_size_bytes as usize;
             #value_stream
             __bytes.put_u8(__tag | __value);
             let __actual_length = 1usize;
             __total_length += __actual_length as usize;
-        });
+        })
     } else {
         // Its a 1 or more byte tag and 1 or mote byte value case
         let tag_stream = tag_encode(&tlv_config);
         let header_size_bytes = tlv_config.tag_bytes_format;
 
-        return Ok(quote! {
+        Ok(quote! {
             #tag_stream
             __total_length += #header_size_bytes as usize;
             let __actual_length = self.#field_name.encode(__bytes)?;
             __total_length += __actual_length as usize;
-        });
-    };
+        })
+    }
 }
 
-fn format_t_encode(_: Ident, tlv_config: TlvConfig) -> Result<TokenStream, Error> {
-    let tag_stream = tag_encode(&tlv_config);
-    let header_size_bytes = 1u8;
+// `value` is the expression of the AVP data. The length is patched in once the value is
+// written, the padding after it is not counted.
+fn format_avp_encode(value: TokenStream, field_name: &Ident, tlv_config: &TlvConfig) -> TokenStream {
+    let field_name_str = field_name.to_string();
+    let code = tlv_config.tag.unwrap() as u32;
+    let avp_flags = tlv_config.avp_flags();
+    let header_octets = tlv_config.avp_header_octets();
+    let vendor_stream = match tlv_config.vendor_id {
+        Some(vendor_id) => quote! { __bytes.put_u32(#vendor_id); },
+        None => quote! {},
+    };
+    quote! {
+        __bytes.put_u32(#code);
+        __bytes.put_u8(#avp_flags);
+        let __fix_length_index = __bytes.len();
+        __bytes.put_uint(0, 3);
+        #vendor_stream
+        let __actual_length = #value.encode(__bytes)?;
+        let __avp_length = #header_octets + __actual_length;
+        if __avp_length > 0xFF_FFFF {
+            return Err(tlv::prelude::TlvError::InvalidLength {
+                field: #field_name_str,
+                length: __avp_length,
+            });
+        }
+        __bytes[__fix_length_index..__fix_length_index + 3]
+            .copy_from_slice(&(__avp_length as u32).to_be_bytes()[1..]);
+        let __padding = (4 - __actual_length % 4) % 4;
+        __bytes.put_bytes(0, __padding);
+        __total_length += __avp_length + __padding;
+    }
+}
 
-    Ok(quote! {
-        #tag_stream
-        __total_length += #header_size_bytes as usize;
-    })
+// The DER encoding, the length is written in the fewest octets once the value is known
+fn format_ber_encode(value: TokenStream, tlv_config: &TlvConfig) -> TokenStream {
+    let tag = tlv_config.tag.unwrap() as u64;
+    let tag_octets = tlv_config.tag_bytes_format as usize;
+    quote! {
+        let mut __value_bytes = BytesMut::new();
+        let __actual_length = #value.encode(&mut __value_bytes)?;
+        __bytes.put_uint(#tag, #tag_octets);
+        let __length_octets = tlv::prelude::put_ber_length(__bytes, __actual_length);
+        __bytes.put(__value_bytes);
+        __total_length += #tag_octets + __length_octets + __actual_length;
+    }
 }
 
 fn format_v_encode(field_name: Ident, _: TlvConfig) -> Result<TokenStream, Error> {
     // Its a 1 or mote byte value case
-    return Ok(quote! {
+    Ok(quote! {
         let __actual_length = self.#field_name.encode(__bytes)?;
         __total_length += __actual_length as usize;
-    });
+    })
+}
+
+// Counterpart of `format_bits_decode`, values wider than their `bits` are rejected.
+fn format_bits_encode(fields: Vec<(Ident, TlvConfig)>) -> Result<TokenStream, Error> {
+    let total_bits: u32 = fields.iter().map(|(_, tlv_config)| tlv_config.bits.unwrap() as u32).sum();
+    let octets = (total_bits / 8) as usize;
+    let mut shift = 0u32;
+    let mut value_streams = Vec::<TokenStream>::new();
+    for (field_name, tlv_config) in fields {
+        let bits = tlv_config.bits.unwrap();
+        let bits_type = get_bits_type(bits);
+        let mask = (1u64 << bits) - 1;
+        let field_name_str = field_name.to_string();
+        value_streams.push(quote! {
+            let __field_bits = #bits_type::from(self.#field_name.clone()) as u64;
+            if __field_bits > #mask {
+                return Err(tlv::prelude::TlvError::InvalidValue {
+                    field: #field_name_str,
+                });
+            }
+            __bits |= __field_bits << #shift;
+        });
+        shift += bits as u32;
+    }
+    Ok(quote! {
+        let mut __bits: u64 = 0;
+        #(#value_streams)*
+        __bytes.put_uint(__bits, #octets);
+        __total_length += #octets;
+    })
 }
 
 fn format_4bit_v_encode(
@@ -184,39 +454,82 @@ fn format_4bit_v_encode(
     let value_stream_2: TokenStream = quote! {
         let __value_2: u8 = u8::from(self.#field_name_2.clone()).to_be()<<4;
     };
-    return Ok(quote! {
+    Ok(quote! {
         #value_stream_1
         #value_stream_2
         __bytes.put_u8(__value_1 | __value_2);
         let __actual_length = 1usize;
         __total_length += __actual_length as usize;
-    });
+    })
 }
 
-fn format_option_encode(field_name: Ident, tlv_config: TlvConfig) -> Result<TokenStream, Error> {
-    // Option with TLV, TV, TLV-E are supported
+fn format_option_encode(
+    field_name: Ident,
+    is_flag: bool,
+    tlv_config: TlvConfig,
+) -> Result<TokenStream, Error> {
+    // Option with TLV, TV, TLV-E, T, AVP, BER and bool with T or an `end` are supported,
+    // `repeated` fields write every element of their `Vec`
+    let binding = if tlv_config.repeated {
+        quote! { for __inner in &self.#field_name }
+    } else {
+        quote! { if let Some(__inner) = &self.#field_name }
+    };
+    if is_flag && let Some((_, length_bits)) = tlv_config.packed_header() {
+        // A packed header of a zero length, e.g. the End of LLDPDU TLV
+        let header = (tlv_config.tag.unwrap() << length_bits) as u64;
+        let header_octets = tlv_config.packed_header_octets();
+        return Ok(quote! {
+            if self.#field_name {
+                __bytes.put_uint(#header, #header_octets);
+                __total_length += #header_octets;
+            }
+        });
+    }
+    if tlv_config.format == "AVP"
+        || tlv_config.format == "BER"
+        || tlv_config.packed_header().is_some()
+        || tlv_config.extended_length.is_some()
+        || tlv_config.fragmented
+    {
+        let inner_stream = if tlv_config.format == "AVP" {
+            format_avp_encode(quote! { __inner }, &field_name, &tlv_config)
+        } else if tlv_config.format == "BER" {
+            format_ber_encode(quote! { __inner }, &tlv_config)
+        } else if tlv_config.fragmented {
+            format_fragmented_encode(quote! { __inner }, &tlv_config)
+        } else if tlv_config.extended_length.is_some() {
+            format_extended_length_encode(quote! { __inner }, &field_name, &tlv_config)
+        } else {
+            format_packed_tlv_encode(quote! { __inner }, &field_name, &tlv_config)
+        };
+        return Ok(quote! {
+            #binding {
+                #inner_stream
+            }
+        });
+    }
     let tag_stream = tag_encode(&tlv_config);
     let fix_length_parameter_stream = fix_length_parameter(&tlv_config);
     let length_stream = length_encode(&tlv_config);
-    let header_size_bytes = tlv_config.tag_bytes_format + tlv_config.length_bytes_format;
-    let fix_length_stream = fix_length_encode(&tlv_config);
+    let extra_header_stream = extra_header_encode(&tlv_config);
+    let header_size_bytes = tlv_config.header_octets();
+    let fix_length_stream = fix_length_encode(&tlv_config, &field_name);
 
     match tlv_config.format.clone().as_str() {
         "TLV" | "TLV-E" => {
-            return Ok(quote! {
-                match &self.#field_name {
-                    Some(__inner) => {
-                        #tag_stream
-                           #fix_length_parameter_stream
-                        #length_stream
-                        __total_length += #header_size_bytes as usize;
-                        let __actual_length = __inner.encode(__bytes)?;
-                        __total_length += __actual_length as usize;
-                        #fix_length_stream
-                    }
-                    None => {}
+            Ok(quote! {
+                #binding {
+                    #tag_stream
+                    #fix_length_parameter_stream
+                    #length_stream
+                    #extra_header_stream
+                    __total_length += #header_size_bytes as usize;
+                    let __actual_length = __inner.encode(__bytes)?;
+                    __total_length += __actual_length as usize;
+                    #fix_length_stream
                 }
-            });
+            })
         }
         "TV" => {
             if tlv_config.tag_bytes_format == 0 {
@@ -229,7 +542,7 @@ fn format_option_encode(field_name: Ident, tlv_config: TlvConfig) -> Result<Toke
                 let value_stream: TokenStream = quote! {
                     let __value: u8 = u8::from(__inner.clone()).to_be();
                 };
-                return Ok(quote! {
+                Ok(quote! {
                     match &self.#field_name {
                         Some(__inner) => {
                             #tag_stream
@@ -240,10 +553,11 @@ fn format_option_encode(field_name: Ident, tlv_config: TlvConfig) -> Result<Toke
                         }
                         None => {}
                     }
-                });
+                })
             } else {
-                // Its a 1 or more byte tag and 1 or mote byte value case
-                return Ok(quote! {
+                // Its a 1 or more byte tag and 1 or mote byte value case, no length octets
+                let header_size_bytes = tlv_config.tag_bytes_format;
+                Ok(quote! {
                     match &self.#field_name {
                         Some(__inner) => {
                             #tag_stream
@@ -253,12 +567,66 @@ fn format_option_encode(field_name: Ident, tlv_config: TlvConfig) -> Result<Toke
                         }
                         None => {}
                     }
-                });
+                })
             }
         }
+        "T" => {
+            // Type 2 IE, only the IEI is written
+            let is_present = if is_flag {
+                quote! { self.#field_name }
+            } else {
+                quote! { self.#field_name.is_some() }
+            };
+            let header_size_bytes = tlv_config.tag_bytes_format;
+            Ok(quote! {
+                if #is_present {
+                    #tag_stream
+                    __total_length += #header_size_bytes as usize;
+                }
+            })
+        }
         _ => {
-            abort_call_site!("Option with TLV, TV, TLV-E are supported")
+            abort_call_site!("Option with TLV, TV, TLV-E, T, AVP, BER are supported")
+        }
+    }
+}
+
+// Counterpart of `context_decode`, a `depends_on` value goes through `TlvEncodeWith` with
+// the sibling it depends on before it is written
+fn context_check(
+    field_name: &Ident,
+    field_type: &Type,
+    tlv_config: &TlvConfig,
+    declared_fields: &[(String, bool)],
+) -> TokenStream {
+    let Some(sibling) = &tlv_config.depends_on else {
+        return quote! {};
+    };
+    let Some((_, is_option)) = declared_fields.iter().find(|(field_name, _)| field_name == sibling) else {
+        abort_call_site!("`depends_on` should name a field declared before")
+    };
+    let sibling_ident = Ident::new(sibling, proc_macro2::Span::call_site());
+    let context_stream = if *is_option {
+        quote! {
+            let __context = self.#sibling_ident
+                .as_ref()
+                .ok_or(tlv::prelude::TlvError::MissingIe { field: #sibling })?;
         }
+    } else {
+        quote! {
+            let __context = &self.#sibling_ident;
+        }
+    };
+    let check_stream = quote! {
+        #context_stream
+        tlv::prelude::TlvEncodeWith::check_with(__inner, __context)?;
+    };
+    if tlv_config.repeated {
+        quote! { for __inner in &self.#field_name { #check_stream } }
+    } else if get_option_inner_type(field_type).is_some() {
+        quote! { if let Some(__inner) = &self.#field_name { #check_stream } }
+    } else {
+        quote! { { let __inner = &self.#field_name; #check_stream } }
     }
 }
 
@@ -273,10 +641,65 @@ fn impl_tlv_encode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
     let mut is_4bit_value_packed = true;
 
     let mut has_optional_fields_started = false;
+    let mut length_checks = Vec::<TokenStream>::new();
+    let mut bit_fields = Vec::<(Ident, TlvConfig)>::new();
+    let mut bit_count = 0u32;
+    // Fields seen so far and whether they are an `Option`, for `depends_on`
+    let mut declared_fields = Vec::<(String, bool)>::new();
 
     for field in data_struct.fields {
         let field_name = field.ident.unwrap();
-        let tlv_config = TlvConfig::from_attributes(field.attrs)?;
+        let tlv_config = TlvConfig::parse(field.attrs)?;
+        output_stream.push(context_check(&field_name, &field.ty, &tlv_config, &declared_fields));
+        declared_fields.push((field_name.to_string(), get_option_inner_type(&field.ty).is_some()));
+
+        if let Some(bits) = tlv_config.bits {
+            if has_optional_fields_started || !is_4bit_value_packed {
+                abort_call_site!("`bits` fields should be mandatory and not mixed with half octets")
+            }
+            bit_fields.push((field_name, tlv_config));
+            bit_count += bits as u32;
+            if bit_count.is_multiple_of(8) {
+                output_stream.push(format_bits_encode(std::mem::take(&mut bit_fields))?);
+                bit_count = 0;
+            }
+            continue;
+        }
+        if !bit_fields.is_empty() {
+            abort_call_site!("`bits` fields should fill whole octets")
+        }
+
+        // Present exactly when the total length is one of `present_if_length`
+        if !tlv_config.present_if_length.is_empty() {
+            let lengths = tlv_config.present_if_length;
+            let field_name_str = field_name.to_string();
+            output_stream.push(quote! {
+                if let Some(__value) = &self.#field_name {
+                    __total_length += __value.encode(__bytes)?;
+                }
+            });
+            length_checks.push(quote! {
+                if self.#field_name.is_some() != [#(#lengths),*].contains(&__total_length) {
+                    return Err(tlv::prelude::TlvError::InvalidLength {
+                        field: #field_name_str,
+                        length: __total_length,
+                    });
+                }
+            });
+            continue;
+        }
+
+        if tlv_config.repeated {
+            has_optional_fields_started = true;
+            output_stream.push(format_option_encode(field_name, false, tlv_config).unwrap());
+            continue;
+        }
+
+        if is_bool_type(&field.ty) && (tlv_config.format == "T" || tlv_config.end) {
+            has_optional_fields_started = true;
+            output_stream.push(format_option_encode(field_name, true, tlv_config).unwrap());
+            continue;
+        }
 
         match field.ty {
             Type::Path(type_path) => {
@@ -288,8 +711,9 @@ fn impl_tlv_encode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
                     {
                         if args.args.len() == 1 {
                             has_optional_fields_started = true;
-                            output_stream
-                                .push(format_option_encode(field_name, tlv_config).unwrap());
+                            output_stream.push(
+                                format_option_encode(field_name, false, tlv_config).unwrap(),
+                            );
                             continue;
                         } else {
                             abort_call_site!("Option must have exactly one type parameter");
@@ -347,11 +771,24 @@ fn impl_tlv_encode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
                 }
                 output_stream.push(format_tv_encode(field_name, tlv_config).unwrap());
             }
-            "T" => {
+            "AVP" => {
                 if !is_4bit_value_packed {
                     abort_call_site!("Two 4bit value should be consecutive")
                 }
-                output_stream.push(format_t_encode(field_name, tlv_config).unwrap());
+                output_stream.push(format_avp_encode(
+                    quote! { self.#field_name },
+                    &field_name,
+                    &tlv_config,
+                ));
+            }
+            "BER" => {
+                if !is_4bit_value_packed {
+                    abort_call_site!("Two 4bit value should be consecutive")
+                }
+                output_stream.push(format_ber_encode(quote! { self.#field_name }, &tlv_config));
+            }
+            "T" => {
+                abort_call_site!("T carries no value, it should be a `bool` or an `Option<()>`")
             }
             _ => {
                 abort_call_site!("Unkown TLV format")
@@ -359,11 +796,16 @@ fn impl_tlv_encode(struct_name: Ident, data_struct: DataStruct) -> Result<TokenS
         }
     }
 
+    if !bit_fields.is_empty() {
+        abort_call_site!("`bits` fields should fill whole octets")
+    }
+
     Ok(quote! {
         impl TlvEncode for #struct_name {
             fn encode(&self, __bytes: &mut BytesMut) -> Result<usize, tlv::prelude::TlvError> {
                 #initialize_stream
                 #(#output_stream)*
+                #(#length_checks)*
                 Ok(__total_length)
             }
         }
@@ -380,6 +822,63 @@ fn impl_newtype_encode(struct_name: Ident) -> Result<TokenStream, Error> {
     })
 }
 
+fn impl_enum_encode(enum_name: Ident, data_enum: DataEnum) -> Result<TokenStream, Error> {
+    let mut variant_streams = Vec::<TokenStream>::new();
+    let mut tag_streams = Vec::<TokenStream>::new();
+
+    for (variant_name, inner_type, tlv_config) in get_enum_variants(&data_enum) {
+        let tag_stream = tag_encode(&tlv_config);
+        let header_size_bytes = tlv_config.tag_bytes_format;
+        let tag = tlv_config.tag.unwrap();
+        tag_streams.push(match inner_type {
+            Some(_) => quote! { Self::#variant_name(_) => #tag, },
+            None => quote! { Self::#variant_name => #tag, },
+        });
+        match inner_type {
+            // The wrapped type writes the masked tag bits along with its value
+            Some(_) if tlv_config.tag_mask.is_some() => variant_streams.push(quote! {
+                Self::#variant_name(__inner) => {
+                    __total_length += __inner.encode(__bytes)?;
+                }
+            }),
+            Some(_) => variant_streams.push(quote! {
+                Self::#variant_name(__inner) => {
+                    #tag_stream
+                    __total_length += #header_size_bytes as usize;
+                    __total_length += __inner.encode(__bytes)?;
+                }
+            }),
+            None => variant_streams.push(quote! {
+                Self::#variant_name => {
+                    #tag_stream
+                    __total_length += #header_size_bytes as usize;
+                }
+            }),
+        }
+    }
+
+    Ok(quote! {
+        impl #enum_name {
+            /// The tag this variant is encoded with.
+            pub fn tag(&self) -> usize {
+                match self {
+                    #(#tag_streams)*
+                }
+            }
+        }
+
+        impl TlvEncode for #enum_name {
+            fn encode(&self, __bytes: &mut BytesMut) -> Result<usize, tlv::prelude::TlvError> {
+                let mut __total_length:usize = 0;
+                match self {
+                    #(#variant_streams)*
+                }
+                Ok(__total_length)
+            }
+        }
+    })
+}
+
 pub(crate) fn tlv_encode(token_stream: TokenStream) -> Result<TokenStream, Error> {
     let DeriveInput { data, .. } = syn::parse2(token_stream.clone())?;
     let struct_name = get_struct_name(token_stream.clone());
@@ -392,8 +891,9 @@ pub(crate) fn tlv_encode(token_stream: TokenStream) -> Result<TokenStream, Error
                 impl_tlv_encode(struct_name, data_struct)
             }
         }
+        syn::Data::Enum(data_enum) => impl_enum_encode(struct_name, data_enum),
         _ => {
-            abort_call_site!("Currently only structs are supported");
+            abort_call_site!("Currently only structs and enums are supported");
         }
     }
 }
@@ -1,25 +1,96 @@
-use syn::{DataStruct, DeriveInput};
+use syn::{DataEnum, DataStruct, DeriveInput, Type};
 use proc_macro2::{TokenStream, Ident};
 use proc_macro_error::abort_call_site;
+use crate::tlv_config::TlvConfig;
 
 pub fn get_struct_name(struct_stream: TokenStream) -> Ident {
 	let input = syn::parse2::<DeriveInput>(struct_stream.clone()).unwrap();
 	match input.data {
-		syn::Data::Struct(_) => input.ident,
+		syn::Data::Struct(_) | syn::Data::Enum(_) => input.ident,
 		_ => {
             abort_call_site!(
-                "It's not a struct, check back !!!");
+                "It's not a struct or an enum, check back !!!");
         },
 	}
 }
 
 pub fn is_newtype(data_struct: &DataStruct) -> bool {
-    match &data_struct.fields {
-        syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => true,
-        _ => false,
+    matches!(&data_struct.fields, syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+}
+
+// Every variant of a tag dispatched enum is either a unit variant or wraps exactly one
+// type, e.g. the message types of a NAS message. With a `tag_mask` the tag is only some
+// bits of the first octets, e.g. the type of identity of a mobile identity.
+pub fn get_enum_variants(data_enum: &DataEnum) -> Vec<(Ident, Option<Type>, TlvConfig)> {
+    let mut variants = Vec::<(Ident, Option<Type>, TlvConfig)>::new();
+    for variant in &data_enum.variants {
+        let tlv_config = TlvConfig::parse_variant(variant.attrs.clone())
+            .unwrap_or_else(|error| abort_call_site!("{}", error));
+        let inner_type = match &variant.fields {
+            syn::Fields::Unit => None,
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Some(fields.unnamed[0].ty.clone())
+            }
+            _ => {
+                abort_call_site!("Enum variants should be unit or wrap exactly one type");
+            }
+        };
+        if let Some((_, _, first_tlv_config)) = variants.first()
+            && first_tlv_config.tag_bytes_format != tlv_config.tag_bytes_format
+        {
+            abort_call_site!("All variants should use the same tag_bytes_format");
+        }
+        if let Some((_, _, first_tlv_config)) = variants.first()
+            && first_tlv_config.tag_mask.is_some() != tlv_config.tag_mask.is_some()
+        {
+            abort_call_site!("Either all or none of the variants should have a tag_mask");
+        }
+        variants.push((variant.ident.clone(), inner_type, tlv_config));
+    }
+    variants
+}
+
+// The `T` of an `Option<T>`
+pub fn get_option_inner_type(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if type_path.path.segments.len() != 1 || segment.ident != "Option" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(inner_type) => Some(inner_type.clone()),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
+// The element type of a `Vec`, for `repeated` IEs
+pub fn get_vec_inner_type(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if type_path.path.segments.len() != 1 || segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(inner_type) => Some(inner_type.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Type 2 IEs are modelled as plain `bool`, present when the IEI is on the wire
+pub fn is_bool_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(tp) if tp.qself.is_none() && tp.path.is_ident("bool"))
+}
+
 // pub fn is_u4_type(ty: &Type) -> bool {
 //     if let Type::Path(tp) = ty {
 //         if let Some(last) = tp.path.segments.last() {
@@ -1,6 +1,5 @@
 pub use bytes::{Buf, BufMut, Bytes, BytesMut};
 pub use std::io::Write;
-use std::usize;
 use thiserror::Error;
 
 //Todo: do some better error handling.
@@ -10,6 +9,97 @@ pub enum TlvError {
     Unknown,
     #[error("Payload is not as per specification")]
     MalformedPayload,
+    #[error("Tag mismatch for `{field}`: expected {expected:#x}, found {found:#x}")]
+    TagMismatch {
+        expected: usize,
+        found: usize,
+        field: &'static str,
+    },
+    #[error("Invalid length {length} for `{field}`")]
+    InvalidLength { field: &'static str, length: usize },
+    #[error("{remaining} trailing bytes after `{field}`")]
+    TrailingBytes { field: &'static str, remaining: usize },
+    #[error("Spare bits of `{field}` are not zero")]
+    NonZeroSpare { field: &'static str },
+    #[error("Unknown tag {tag:#x} in optional part")]
+    UnknownTag { tag: usize },
+    #[error("`{field}` is repeated")]
+    DuplicateIe { field: &'static str },
+    #[error("`{field}` is out of order")]
+    OutOfOrderIe { field: &'static str },
+    #[error("`{value}` is not a BCD digit")]
+    InvalidDigit { value: char },
+    #[error("Value of `{field}` can not be encoded")]
+    InvalidValue { field: &'static str },
+    #[error("`{field}` is missing")]
+    MissingIe { field: &'static str },
+}
+
+/// How strictly the generated decoders check the wire against the `tlv_config`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// Conformance testing: wrong mandatory tags, trailing bytes inside an IE or
+    /// after the message, non-zero spare bits, length violations and unknown
+    /// optional tags are all returned as errors.
+    #[default]
+    Strict,
+    /// Production: follows the TS 24.007 error handling, mandatory tags and spare
+    /// bits are not checked, superfluous octets of over long IEs and unknown
+    /// optional IEs are skipped.
+    Lenient,
+}
+
+/// What to do when a non repeatable optional IE shows up more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateIe {
+    /// Only the first occurrence is used, as per TS 24.007 section 8.6.3.
+    FirstWins,
+    Error,
+}
+
+/// What to do when an optional IE shows up before an IE which precedes it in the
+/// message table, i.e. before a field declared earlier in the struct.
+#[derive(Debug, Clone, Copy)]
+pub enum OutOfOrderIe {
+    Accept,
+    Error,
+    /// Accepts the IE and reports the field name and tag to the callback.
+    Warn(fn(&'static str, usize)),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeOptions {
+    pub mode: DecodeMode,
+    pub duplicate_ie: DuplicateIe,
+    pub out_of_order_ie: OutOfOrderIe,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+impl DecodeOptions {
+    pub fn strict() -> Self {
+        DecodeOptions {
+            mode: DecodeMode::Strict,
+            duplicate_ie: DuplicateIe::Error,
+            out_of_order_ie: OutOfOrderIe::Error,
+        }
+    }
+
+    pub fn lenient() -> Self {
+        DecodeOptions {
+            mode: DecodeMode::Lenient,
+            duplicate_ie: DuplicateIe::FirstWins,
+            out_of_order_ie: OutOfOrderIe::Accept,
+        }
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.mode == DecodeMode::Strict
+    }
 }
 
 pub trait TlvEncode {
@@ -19,8 +109,292 @@ pub trait TlvEncode {
 
 pub trait TlvDecode: Sized {
     fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError>;
+
+    // Derived decoders override this and forward the options to every nested field,
+    // hand written leaf types only need `decode`.
+    fn decode_with_options(
+        length: usize,
+        bytes: &mut Bytes,
+        _options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        Self::decode(length, bytes)
+    }
 }
 
+/// Decoding of a value whose coding is selected by a field decoded before it, e.g. the
+/// payload container by the payload container type. Fields with `depends_on` use it.
+pub trait TlvDecodeWith<C>: Sized {
+    fn decode_with(
+        length: usize,
+        bytes: &mut Bytes,
+        context: &C,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError>;
+}
+
+/// Counterpart of `TlvDecodeWith` on encode, a value is checked against the field it
+/// depends on before it is written, e.g. the payload container against the payload container
+/// type. Fields with `depends_on` use it.
+pub trait TlvEncodeWith<C>: TlvEncode {
+    fn check_with(&self, context: &C) -> Result<(), TlvError>;
+}
+
+/// Skips an IE which is not known to the receiver, its format is derived from the IEI
+/// as per TS 24.007 section 11.2.4: IEIs with bit 8 set are single octet (type 1 and 2),
+/// IEIs 0x7X are TLV-E and every other IEI is TLV.
+pub fn skip_unknown_ie(bytes: &mut Bytes) -> Result<(), TlvError> {
+    let tag = *bytes.chunk().first().ok_or(TlvError::MalformedPayload)?;
+    let (header_length, value_length) = if tag & 0x80 != 0 {
+        (1usize, 0usize)
+    } else if tag & 0xf0 == 0x70 {
+        if bytes.remaining() < 3 {
+            return Err(TlvError::MalformedPayload);
+        }
+        (3usize, u16::from_be_bytes([bytes[1], bytes[2]]) as usize)
+    } else {
+        if bytes.remaining() < 2 {
+            return Err(TlvError::MalformedPayload);
+        }
+        (2usize, bytes[1] as usize)
+    };
+    if bytes.remaining() < header_length + value_length {
+        return Err(TlvError::MalformedPayload);
+    }
+    bytes.advance(header_length + value_length);
+    Ok(())
+}
+
+/// Skips an IE with a fixed header layout: `tag_octets` of tag, `length_octets` of length
+/// counting the value only, then `extra_octets` before the value, e.g. the instance octet
+/// of a GTPv2-C IE.
+pub fn skip_tlv(
+    bytes: &mut Bytes,
+    tag_octets: usize,
+    length_octets: usize,
+    extra_octets: usize,
+) -> Result<(), TlvError> {
+    let header_length = tag_octets + length_octets + extra_octets;
+    if bytes.remaining() < header_length {
+        return Err(TlvError::MalformedPayload);
+    }
+    let value_length = (&bytes[tag_octets..]).get_uint(length_octets) as usize;
+    if bytes.remaining() < header_length + value_length {
+        return Err(TlvError::MalformedPayload);
+    }
+    bytes.advance(header_length + value_length);
+    Ok(())
+}
+
+/// Skips a TLV whose length counts `length_unit` octets and `counted_header_octets` of the
+/// header besides the value, e.g. an EAP-AKA attribute with its length in 4 octets units.
+pub fn skip_scaled_tlv(
+    bytes: &mut Bytes,
+    tag_octets: usize,
+    length_octets: usize,
+    length_unit: usize,
+    counted_header_octets: usize,
+) -> Result<(), TlvError> {
+    let header_length = tag_octets + length_octets;
+    if bytes.remaining() < header_length {
+        return Err(TlvError::MalformedPayload);
+    }
+    let length = (&bytes[tag_octets..]).get_uint(length_octets) as usize * length_unit;
+    let value_length = length
+        .checked_sub(counted_header_octets)
+        .ok_or(TlvError::MalformedPayload)?;
+    if bytes.remaining() < header_length + value_length {
+        return Err(TlvError::MalformedPayload);
+    }
+    bytes.advance(header_length + value_length);
+    Ok(())
+}
+
+/// Skips a TLV whose `header_octets` hold the tag followed by a `length_bits` wide length
+/// of the value, e.g. the 7 bit type and 9 bit length of an LLDP TLV.
+pub fn skip_packed_tlv(
+    bytes: &mut Bytes,
+    header_octets: usize,
+    length_bits: u8,
+) -> Result<(), TlvError> {
+    if bytes.remaining() < header_octets {
+        return Err(TlvError::MalformedPayload);
+    }
+    let header = (&bytes[..]).get_uint(header_octets);
+    let value_length = (header & ((1u64 << length_bits) - 1)) as usize;
+    if bytes.remaining() < header_octets + value_length {
+        return Err(TlvError::MalformedPayload);
+    }
+    bytes.advance(header_octets + value_length);
+    Ok(())
+}
+
+/// Skips a TLV with a flags octet in front of a 1 octet tag, the length is 2 octets when
+/// the `extended_length` flag is set and 1 octet otherwise, e.g. a BGP path attribute.
+pub fn skip_extended_length_tlv(bytes: &mut Bytes, extended_length: u8) -> Result<(), TlvError> {
+    if bytes.remaining() < 3 {
+        return Err(TlvError::MalformedPayload);
+    }
+    let (header_length, value_length) = if bytes[0] & extended_length != 0 {
+        if bytes.remaining() < 4 {
+            return Err(TlvError::MalformedPayload);
+        }
+        (4usize, u16::from_be_bytes([bytes[2], bytes[3]]) as usize)
+    } else {
+        (3usize, bytes[2] as usize)
+    };
+    if bytes.remaining() < header_length + value_length {
+        return Err(TlvError::MalformedPayload);
+    }
+    bytes.advance(header_length + value_length);
+    Ok(())
+}
+
+/// Skips a Diameter AVP, its length counts the header but not the padding to 4 octets. The
+/// last AVP may come without the padding. An AVP with the M flag must be understood, RFC
+/// 6733 section 4.1, so it is never skipped.
+pub fn skip_avp(bytes: &mut Bytes) -> Result<(), TlvError> {
+    if bytes.remaining() < 8 {
+        return Err(TlvError::MalformedPayload);
+    }
+    if bytes[4] & 0x40 != 0 {
+        return Err(TlvError::UnknownTag {
+            tag: (&bytes[..4]).get_u32() as usize,
+        });
+    }
+    let length = (&bytes[5..8]).get_uint(3) as usize;
+    if length < 8 || bytes.remaining() < length {
+        return Err(TlvError::MalformedPayload);
+    }
+    bytes.advance(((length + 3) & !3).min(bytes.remaining()));
+    Ok(())
+}
+
+/// Peeks a BER identifier, X.690 section 8.1.2, and returns it with the octets it takes.
+/// The identifier is the big endian integer of its octets, e.g. 0x9F1F for the context
+/// specific primitive tag 31. The high tag number form is read up to 4 octets in all.
+pub fn peek_ber_tag(bytes: &[u8]) -> Option<(usize, usize)> {
+    let first = *bytes.first()?;
+    if first & 0x1F != 0x1F {
+        return Some((first as usize, 1));
+    }
+    // The tag number takes as few octets as possible
+    if *bytes.get(1)? == 0x80 {
+        return None;
+    }
+    let mut tag = first as usize;
+    for octets in 2..=4 {
+        let octet = *bytes.get(octets - 1)?;
+        tag = tag << 8 | octet as usize;
+        if octet & 0x80 == 0 {
+            return Some((tag, octets));
+        }
+    }
+    None
+}
+
+// A BER length, X.690 section 8.1.3: the length or `None` for the indefinite form, the
+// octets it takes and whether it is in the fewest octets as DER requires
+fn peek_ber_length(bytes: &[u8]) -> Option<(Option<usize>, usize, bool)> {
+    match *bytes.first()? {
+        length @ 0..=0x7F => Some((Some(length as usize), 1, true)),
+        0x80 => Some((None, 1, false)),
+        0xFF => None,
+        first => {
+            let octets = (first & 0x7F) as usize;
+            if octets > size_of::<usize>() {
+                return None;
+            }
+            let length_octets = bytes.get(1..=octets)?;
+            let length = length_octets
+                .iter()
+                .fold(0usize, |length, octet| length << 8 | *octet as usize);
+            Some((Some(length), 1 + octets, length >= 0x80 && length_octets[0] != 0))
+        }
+    }
+}
+
+// Nesting of indefinite lengths followed before giving up
+const BER_MAX_DEPTH: usize = 32;
+
+// Octets of a whole BER element, the end-of-contents octets of an indefinite length included
+fn ber_element_octets(bytes: &[u8], depth: usize) -> Option<usize> {
+    let (tag, tag_octets) = peek_ber_tag(bytes)?;
+    let (length, length_octets, _) = peek_ber_length(&bytes[tag_octets..])?;
+    let header_octets = tag_octets + length_octets;
+    let contents_octets = match length {
+        Some(length) => length,
+        // Only constructed encodings can have the indefinite form
+        None if tag >> (8 * (tag_octets - 1)) & 0x20 != 0 => {
+            ber_indefinite_contents(&bytes[header_octets..], depth + 1)? + 2
+        }
+        None => return None,
+    };
+    let element_octets = header_octets.checked_add(contents_octets)?;
+    (bytes.len() >= element_octets).then_some(element_octets)
+}
+
+// Octets of the contents of an indefinite length, up to the end-of-contents octets
+fn ber_indefinite_contents(bytes: &[u8], depth: usize) -> Option<usize> {
+    if depth > BER_MAX_DEPTH {
+        return None;
+    }
+    let mut offset = 0;
+    loop {
+        let contents = bytes.get(offset..)?;
+        if contents.starts_with(&[0, 0]) {
+            return Some(offset);
+        }
+        offset += ber_element_octets(contents, depth)?;
+    }
+}
+
+/// Reads the identifier and the length of a BER element, returning the identifier, the
+/// octets of the contents and the end-of-contents octets after them, 2 for the indefinite
+/// form and 0 otherwise. `strict` asks for the DER length, definite and in the fewest
+/// octets.
+pub fn get_ber_header(
+    bytes: &mut Bytes,
+    field: &'static str,
+    strict: bool,
+) -> Result<(usize, usize, usize), TlvError> {
+    let invalid_length = |length| TlvError::InvalidLength { field, length };
+    let (tag, tag_octets) = peek_ber_tag(bytes).ok_or(invalid_length(bytes.remaining()))?;
+    let (length, length_octets, is_minimal) =
+        peek_ber_length(&bytes[tag_octets..]).ok_or(invalid_length(bytes.remaining()))?;
+    let header_octets = tag_octets + length_octets;
+    let (contents_octets, end_of_contents_octets) = match length {
+        Some(length) => (length, 0),
+        None => {
+            let element_octets =
+                ber_element_octets(bytes, 0).ok_or(invalid_length(bytes.remaining()))?;
+            (element_octets - header_octets - 2, 2)
+        }
+    };
+    if strict && !is_minimal {
+        return Err(invalid_length(contents_octets));
+    }
+    bytes.advance(header_octets);
+    Ok((tag, contents_octets, end_of_contents_octets))
+}
+
+/// Writes a BER length in the fewest octets, the DER length, and returns the octets taken.
+pub fn put_ber_length(bytes: &mut BytesMut, length: usize) -> usize {
+    if length < 0x80 {
+        bytes.put_u8(length as u8);
+        return 1;
+    }
+    let octets = size_of::<usize>() - length.leading_zeros() as usize / 8;
+    bytes.put_u8(0x80 | octets as u8);
+    bytes.put_uint(length as u64, octets);
+    1 + octets
+}
+
+/// Skips a BER element in the definite or the indefinite form.
+pub fn skip_ber(bytes: &mut Bytes) -> Result<(), TlvError> {
+    let element_octets = ber_element_octets(bytes, 0).ok_or(TlvError::MalformedPayload)?;
+    bytes.advance(element_octets);
+    Ok(())
+}
 
 impl TlvEncode for u8 {
     fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
@@ -36,6 +410,26 @@ impl TlvEncode for Vec<u8> {
 	}
 }
 
+impl<T: TlvEncode> TlvEncode for Box<T> {
+    fn encode(&self, bytes: &mut BytesMut) -> Result<usize, TlvError> {
+        self.as_ref().encode(bytes)
+    }
+}
+
+impl<T: TlvDecode> TlvDecode for Box<T> {
+    fn decode(length: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
+        T::decode(length, bytes).map(Box::new)
+    }
+
+    fn decode_with_options(
+        length: usize,
+        bytes: &mut Bytes,
+        options: &DecodeOptions,
+    ) -> Result<Self, TlvError> {
+        T::decode_with_options(length, bytes, options).map(Box::new)
+    }
+}
+
 impl TlvDecode for u8 {
     fn decode(_lenght: usize, bytes: &mut Bytes) -> Result<Self, TlvError> {
         Ok(bytes.get_u8())